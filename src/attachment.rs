@@ -0,0 +1,123 @@
+//! Kleine Dateien (Zertifikate, Schlüsseldateien) als Wert eines Eintrags. Sie
+//! liegen base64-kodiert im verschlüsselten JSON und werden nie inline angezeigt.
+
+use std::path::Path;
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::trf;
+
+/// Alles steckt in einem einzigen verschlüsselten Block, der bei jedem
+/// Speichern komplett neu geschrieben wird
+pub(crate) const MAX_ATTACHMENT_BYTES: usize = 1024 * 1024;
+
+/// Gespeichert als `{"$attachment": Dateiname, "mime": ..., "base64": ...}`; andere
+/// Objekte bleiben normales JSON, weil keine weiteren Felder erlaubt sind
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Attachment {
+    #[serde(rename = "$attachment")]
+    pub(crate) filename: String,
+    pub(crate) mime: String,
+    pub(crate) base64: String,
+}
+
+impl Attachment {
+    /// Liest eine Datei ein; zu große Dateien werden mit Größe und Grenze abgelehnt
+    pub(crate) fn from_file(path: &Path) -> Result<Self, String> {
+        let size = std::fs::metadata(path).map_err(|e| e.to_string())?.len();
+        if size > MAX_ATTACHMENT_BYTES as u64 {
+            return Err(trf(
+                "Datei zu groß: {} (höchstens {}, da alles in einer verschlüsselten Datei liegt)",
+                &[
+                    &format_size(size),
+                    &format_size(MAX_ATTACHMENT_BYTES as u64),
+                ],
+            ));
+        }
+        let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Ok(Self {
+            mime: guess_mime(&filename).to_string(),
+            filename,
+            base64: BASE64.encode(bytes),
+        })
+    }
+
+    pub(crate) fn bytes(&self) -> Result<Vec<u8>, String> {
+        BASE64.decode(&self.base64).map_err(|e| e.to_string())
+    }
+
+    /// Größe der Datei, ohne sie zu dekodieren
+    pub(crate) fn size(&self) -> u64 {
+        let padding = self.base64.bytes().rev().take_while(|&b| b == b'=').count();
+        (self.base64.len() / 4 * 3).saturating_sub(padding) as u64
+    }
+}
+
+/// MIME-Typ anhand der Endung; unbekannte Dateien gelten als Binärdaten
+pub(crate) fn guess_mime(filename: &str) -> &'static str {
+    let extension = Path::new(filename)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "pem" | "key" | "crt" | "cer" => "application/x-pem-file",
+        "der" => "application/pkix-cert",
+        "p12" | "pfx" => "application/x-pkcs12",
+        "pub" | "txt" | "env" => "text/plain",
+        "json" => "application/json",
+        "asc" | "gpg" | "pgp" => "application/pgp-keys",
+        "kdbx" => "application/x-keepass2",
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+/// `512 B`, `2.5 KiB`, `1.0 MiB`
+pub(crate) fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..=1023 => format!("{} B", bytes),
+        1024..=1_048_575 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_round_trip_and_oversized_ones_are_refused() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("client.P12");
+        let bytes: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        std::fs::write(&path, &bytes).unwrap();
+
+        let attachment = Attachment::from_file(&path).unwrap();
+        assert_eq!(attachment.filename, "client.P12");
+        assert_eq!(attachment.mime, "application/x-pkcs12");
+        assert_eq!(attachment.size(), 1000);
+        assert_eq!(attachment.bytes().unwrap(), bytes);
+        assert_eq!(format_size(attachment.size()), "1000 B");
+        assert_eq!(format_size(2560), "2.5 KiB");
+
+        let json = serde_json::to_value(&attachment).unwrap();
+        assert_eq!(json["$attachment"], "client.P12");
+        let mut other = json.clone();
+        other["extra"] = true.into();
+        assert!(serde_json::from_value::<Attachment>(other).is_err());
+
+        let big = dir.path().join("big.bin");
+        std::fs::write(&big, vec![0u8; MAX_ATTACHMENT_BYTES + 1]).unwrap();
+        let e = Attachment::from_file(&big).unwrap_err();
+        assert!(e.contains("1.0 MiB"), "{}", e);
+    }
+}
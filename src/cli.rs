@@ -0,0 +1,228 @@
+//! Kommandozeilenmodus ohne Oberfläche, z.B. für Skripte:
+//! `encrypted-json-editor get <key>`, `set <key> <value>`, `list`, `remove <key>`,
+//! sowie `--decrypt --stdin` / `--encrypt --stdin` für Pipes
+
+use std::io::Read;
+use std::path::Path;
+
+use crate::vault::{decrypt_content, encrypt_content, read_key_file, Vault};
+use crate::{load_settings, normalize_key, resolve_vault_path, AppData, EntryValue};
+
+/// Umgebungsvariable mit dem Passwort; fehlt sie, wird interaktiv gefragt
+const PASSWORD_ENV: &str = "VAULT_PASSWORD";
+
+pub(crate) const EXIT_ERROR: i32 = 1;
+pub(crate) const EXIT_USAGE: i32 = 2;
+pub(crate) const EXIT_NOT_FOUND: i32 = 3;
+
+const COMMANDS: [&str; 4] = ["get", "set", "list", "remove"];
+
+pub(crate) fn is_command(arg: &str) -> bool {
+    COMMANDS.contains(&arg)
+}
+
+/// `--decrypt` oder `--encrypt` irgendwo in den Argumenten wählt den Pipe-Modus
+pub(crate) fn is_pipe_mode(args: &[String]) -> bool {
+    args.iter()
+        .any(|arg| arg == "--decrypt" || arg == "--encrypt")
+}
+
+/// Pipe-Modus: liest einen Vault-Inhalt bzw. JSON von stdin und schreibt das
+/// Gegenstück nach stdout, ohne dass eine Vault-Datei im Spiel ist
+pub(crate) fn run_pipe(args: &[String]) -> i32 {
+    let mut decrypt = None;
+    let mut stdin = false;
+    let mut key_file_arg = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--decrypt" | "--encrypt" if decrypt.is_some() => {
+                return usage_error("--decrypt und --encrypt schließen sich aus")
+            }
+            "--decrypt" => decrypt = Some(true),
+            "--encrypt" => decrypt = Some(false),
+            "--stdin" => stdin = true,
+            "--key-file" => match iter.next() {
+                Some(path) => key_file_arg = Some(path.clone()),
+                None => return usage_error("--key-file braucht einen Pfad"),
+            },
+            other => return usage_error(&format!("Unbekanntes Argument: {}", other)),
+        }
+    }
+    let Some(decrypt) = decrypt else {
+        return usage_error("--decrypt oder --encrypt fehlt");
+    };
+    if !stdin {
+        return usage_error("--decrypt und --encrypt lesen nur von --stdin");
+    }
+
+    let key_file = match key_file_arg
+        .map(|path| read_key_file(Path::new(&path)))
+        .transpose()
+    {
+        Ok(key_file) => key_file,
+        Err(e) => return fail(&e),
+    };
+    let mut input = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+        return fail(&format!("stdin nicht lesbar: {}", e));
+    }
+    let password = match read_password() {
+        Ok(password) => password,
+        Err(e) => return fail(&e),
+    };
+    match pipe(decrypt, &input, &password, key_file.as_ref()) {
+        Ok(output) => {
+            println!("{}", output);
+            0
+        }
+        Err(e) => fail(&e),
+    }
+}
+
+/// Beim Verschlüsseln muss die Eingabe wie ein Vault-Inhalt aussehen, damit die
+/// Oberfläche das Ergebnis später auch öffnen kann
+fn pipe(
+    decrypt: bool,
+    input: &str,
+    password: &str,
+    key_file: Option<&[u8; 32]>,
+) -> Result<String, String> {
+    if decrypt {
+        let plaintext = decrypt_content(input, password, key_file)?;
+        return String::from_utf8(plaintext).map_err(|e| e.to_string());
+    }
+    let mut data: AppData =
+        serde_json::from_str(input).map_err(|e| format!("Kein gültiges Vault-JSON: {}", e))?;
+    data.fill_missing_meta();
+    let json = serde_json::to_string(&data).map_err(|e| e.to_string())?;
+    encrypt_content(json.as_bytes(), password, key_file)
+}
+
+/// Führt einen Befehl aus und liefert den Exit-Code
+pub(crate) fn run(command: &str, args: &[String]) -> i32 {
+    let mut vault_arg = None;
+    let mut key_file_arg = None;
+    let mut positional = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--vault" {
+            match iter.next() {
+                Some(path) => vault_arg = Some(path.clone()),
+                None => return usage_error("--vault braucht einen Pfad"),
+            }
+        } else if arg == "--key-file" {
+            match iter.next() {
+                Some(path) => key_file_arg = Some(path.clone()),
+                None => return usage_error("--key-file braucht einen Pfad"),
+            }
+        } else {
+            positional.push(arg.as_str());
+        }
+    }
+
+    let expected = match command {
+        "list" => 0,
+        "get" | "remove" => 1,
+        _ => 2,
+    };
+    if positional.len() != expected {
+        return usage_error(&format!(
+            "\"{}\" erwartet {} Argument(e), erhalten {}",
+            command,
+            expected,
+            positional.len()
+        ));
+    }
+
+    let (vault_path, _) = resolve_vault_path(vault_arg);
+    // Dieselben Grenzen wie in `App::apply_settings`
+    let settings = load_settings();
+    let backup_count = settings.backup_count.min(20);
+    let password = match read_password() {
+        Ok(password) => password,
+        Err(e) => return fail(&e),
+    };
+    let mut vault = Vault::new(vault_path);
+    if let Some(path) = key_file_arg {
+        match read_key_file(Path::new(&path)) {
+            Ok(key_file) => vault.key_file = Some(key_file),
+            Err(e) => return fail(&e),
+        }
+    }
+    let mut data = if vault.exists() {
+        match vault.load(&password) {
+            Ok(data) => data,
+            Err(e) => return fail(&e),
+        }
+    } else if command == "set" {
+        // Ein neuer Vault entsteht: die Passwort-Richtlinie aus den Einstellungen gilt
+        if let Err(e) = settings.policy.check(&password) {
+            return fail(&e);
+        }
+        AppData::default()
+    } else {
+        return fail(&format!("Vault nicht gefunden: {}", vault.path.display()));
+    };
+
+    match (command, positional.as_slice()) {
+        ("list", []) => {
+            for key in data.items.keys() {
+                println!("{}", key);
+            }
+            0
+        }
+        ("get", [key]) => match data.items.get(*key) {
+            Some(value) => {
+                println!("{}", value.as_text());
+                0
+            }
+            None => not_found(key),
+        },
+        ("set", [key, value]) => {
+            let Some(key) = normalize_key(key) else {
+                return usage_error("Schlüssel darf nicht leer sein");
+            };
+            data.set_value(key, EntryValue::Text(value.to_string()));
+            data.trim_history(settings.history_limit.min(100));
+            save(&mut vault, &password, &data, backup_count)
+        }
+        ("remove", [key]) => {
+            if data.remove_entry(key).is_none() {
+                return not_found(key);
+            }
+            save(&mut vault, &password, &data, backup_count)
+        }
+        _ => usage_error(&format!("Unbekannter Befehl: {}", command)),
+    }
+}
+
+fn save(vault: &mut Vault, password: &str, data: &AppData, backup_count: usize) -> i32 {
+    match vault.save(password, data, backup_count) {
+        Ok(_) => 0,
+        Err(e) => fail(&e),
+    }
+}
+
+fn read_password() -> Result<String, String> {
+    if let Ok(password) = std::env::var(PASSWORD_ENV) {
+        return Ok(password);
+    }
+    rpassword::prompt_password("Passwort: ").map_err(|e| e.to_string())
+}
+
+fn fail(message: &str) -> i32 {
+    eprintln!("Fehler: {}", message);
+    EXIT_ERROR
+}
+
+fn not_found(key: &str) -> i32 {
+    eprintln!("Schlüssel nicht gefunden: {}", key);
+    EXIT_NOT_FOUND
+}
+
+fn usage_error(message: &str) -> i32 {
+    eprintln!("Fehler: {}", message);
+    crate::print_usage();
+    EXIT_USAGE
+}
@@ -0,0 +1,81 @@
+//! Kalenderdaten in UTC ohne eigene Zeitzonen-Bibliothek, nach Howard Hinnants
+//! `civil_from_days`/`days_from_civil`
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Tage seit 1970-01-01 als (Jahr, Monat, Tag)
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (yoe + era * 400 + i64::from(month <= 2), month, day)
+}
+
+/// Umkehrung von `civil_from_days`
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from((month + 9) % 12);
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Ganze Tage und restliche Sekunden seit 1970; frühere Zeitpunkte zählen als 1970
+pub(crate) fn split_days(time: SystemTime) -> (i64, u64) {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    (
+        (seconds / SECONDS_PER_DAY) as i64,
+        seconds % SECONDS_PER_DAY,
+    )
+}
+
+/// `2026-10-15`
+pub(crate) fn format_date(time: SystemTime) -> String {
+    let (year, month, day) = civil_from_days(split_days(time).0);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Liest `JJJJ-MM-TT` als Tagesbeginn in UTC; unmögliche Daten wie der 31.02. fallen durch
+pub(crate) fn parse_date(text: &str) -> Option<SystemTime> {
+    let mut parts = text.trim().splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    let days = days_from_civil(year, month, day);
+    if days < 0 || civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+    Some(UNIX_EPOCH + Duration::from_secs(days as u64 * SECONDS_PER_DAY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_round_trip_and_reject_impossible_days() {
+        for days in [0, 59, 60, 11_016, 20_741, 30_000] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        let date = parse_date("2026-10-15").unwrap();
+        assert_eq!(format_date(date), "2026-10-15");
+        assert_eq!(
+            format_date(date + Duration::from_secs(86_399)),
+            "2026-10-15"
+        );
+        assert_eq!(format_date(parse_date("2024-02-29").unwrap()), "2024-02-29");
+        assert!(parse_date("2025-02-29").is_none());
+        assert!(parse_date("2026-13-01").is_none());
+        assert!(parse_date("15.10.2026").is_none());
+    }
+}
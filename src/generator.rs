@@ -0,0 +1,140 @@
+//! Zufällige Passwörter für neue und bestehende Einträge
+
+use aes_gcm::aead::OsRng;
+use argon2::password_hash::rand_core::RngCore;
+use eframe::egui;
+
+use crate::i18n::tr;
+
+const UPPER: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const LOWER: &str = "abcdefghijklmnopqrstuvwxyz";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!#$%&*+-.:;=?@^_~";
+
+pub(crate) const MIN_LENGTH: usize = 4;
+pub(crate) const MAX_LENGTH: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct PasswordGenerator {
+    pub(crate) length: usize,
+    pub(crate) upper: bool,
+    pub(crate) lower: bool,
+    pub(crate) digits: bool,
+    pub(crate) symbols: bool,
+}
+
+impl Default for PasswordGenerator {
+    fn default() -> Self {
+        Self {
+            length: 20,
+            upper: true,
+            lower: true,
+            digits: true,
+            symbols: true,
+        }
+    }
+}
+
+impl PasswordGenerator {
+    fn classes(&self) -> Vec<&'static str> {
+        [
+            (self.upper, UPPER),
+            (self.lower, LOWER),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, chars)| enabled.then_some(chars))
+        .collect()
+    }
+
+    /// Erzeugt ein Passwort, das jede gewählte Zeichenklasse mindestens einmal enthält.
+    /// Ohne gewählte Klasse gibt es nichts zu erzeugen.
+    pub(crate) fn generate(&self) -> Option<String> {
+        let classes = self.classes();
+        if classes.is_empty() {
+            return None;
+        }
+        let alphabet: Vec<char> = classes.concat().chars().collect();
+        let length = self.length.clamp(MIN_LENGTH, MAX_LENGTH);
+        loop {
+            let password: String = (0..length)
+                .map(|_| alphabet[random_index(alphabet.len())])
+                .collect();
+            if classes
+                .iter()
+                .all(|class| password.chars().any(|c| class.contains(c)))
+            {
+                return Some(password);
+            }
+        }
+    }
+}
+
+/// Gleichverteilte Zahl in `0..bound` ohne Modulo-Verzerrung
+fn random_index(bound: usize) -> usize {
+    let bound = bound as u32;
+    let zone = u32::MAX - u32::MAX % bound;
+    loop {
+        let n = OsRng.next_u32();
+        if n < zone {
+            return (n % bound) as usize;
+        }
+    }
+}
+
+/// Inhalt des 🎲-Menüs; liefert das Passwort, sobald übernommen wurde
+pub(crate) fn show_generator_menu(
+    ui: &mut egui::Ui,
+    generator: &mut PasswordGenerator,
+) -> Option<String> {
+    ui.add(egui::Slider::new(&mut generator.length, MIN_LENGTH..=MAX_LENGTH).text(tr("Länge")));
+    ui.checkbox(&mut generator.upper, tr("Großbuchstaben (A-Z)"));
+    ui.checkbox(&mut generator.lower, tr("Kleinbuchstaben (a-z)"));
+    ui.checkbox(&mut generator.digits, tr("Ziffern (0-9)"));
+    ui.checkbox(&mut generator.symbols, tr("Sonderzeichen (!#$…)"));
+    ui.add_space(4.0);
+
+    let button = egui::Button::new(tr("🎲 Passwort übernehmen"))
+        .fill(egui::Color32::from_rgb(40, 167, 69))
+        .rounding(egui::Rounding::same(6.0));
+    if ui
+        .add_enabled(!generator.classes().is_empty(), button)
+        .clicked()
+    {
+        ui.close_menu();
+        return generator.generate();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_passwords_respect_length_and_classes() {
+        let generator = PasswordGenerator {
+            length: 12,
+            symbols: false,
+            ..PasswordGenerator::default()
+        };
+        for _ in 0..50 {
+            let password = generator.generate().unwrap();
+            assert_eq!(password.chars().count(), 12);
+            assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+            assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+            assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+            assert!(password.chars().any(|c| c.is_ascii_digit()));
+        }
+
+        let none = PasswordGenerator {
+            upper: false,
+            lower: false,
+            digits: false,
+            symbols: false,
+            ..PasswordGenerator::default()
+        };
+        assert_eq!(none.generate(), None);
+    }
+}
@@ -0,0 +1,892 @@
+//! Übersetzungen der Oberfläche. Schlüssel ist jeweils der deutsche Originaltext;
+//! fehlt eine Übersetzung, wird er unverändert angezeigt.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Language {
+    #[default]
+    German,
+    English,
+}
+
+impl Language {
+    pub(crate) const ALL: [Language; 2] = [Language::German, Language::English];
+
+    /// Name in der eigenen Sprache, damit man ihn auch ohne Kenntnis der aktuellen findet
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            Language::German => "Deutsch",
+            Language::English => "English",
+        }
+    }
+
+    /// Sprache aus `LC_ALL`, `LC_MESSAGES` oder `LANG`; ohne Treffer Deutsch
+    pub(crate) fn from_environment() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .map(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    fn from_locale(locale: &str) -> Self {
+        if locale.to_lowercase().starts_with("en") {
+            Language::English
+        } else {
+            Language::German
+        }
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub(crate) fn set_language(language: Language) {
+    CURRENT.store(language as u8, Ordering::Relaxed);
+}
+
+pub(crate) fn language() -> Language {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Language::English,
+        _ => Language::German,
+    }
+}
+
+/// Übersetzt in die aktuelle Sprache
+pub(crate) fn tr(text: &str) -> &str {
+    translate(language(), text)
+}
+
+/// Wie `tr`, ersetzt danach die `{}` der Reihe nach durch `args`
+pub(crate) fn trf(text: &str, args: &[&dyn std::fmt::Display]) -> String {
+    let mut out = String::new();
+    let mut parts = tr(text).split("{}");
+    if let Some(first) = parts.next() {
+        out.push_str(first);
+    }
+    for (i, part) in parts.enumerate() {
+        if let Some(arg) = args.get(i) {
+            out.push_str(&arg.to_string());
+        }
+        out.push_str(part);
+    }
+    out
+}
+
+pub(crate) fn translate(language: Language, text: &str) -> &str {
+    match language {
+        Language::German => text,
+        Language::English => english().get(text).copied().unwrap_or(text),
+    }
+}
+
+fn english() -> &'static HashMap<&'static str, &'static str> {
+    static TABLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    TABLE.get_or_init(|| ENGLISH.iter().copied().collect())
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    // Anmeldung und Einrichtung
+    (
+        "Sichere Verwaltung deiner JSON-Daten",
+        "Secure management of your JSON data",
+    ),
+    ("Passwort eingeben:", "Enter password:"),
+    ("Dein sicheres Passwort", "Your secure password"),
+    ("Passwort anzeigen", "Show password"),
+    ("🚀 Entsperren", "🚀 Unlock"),
+    ("🗂 Aus Backup wiederherstellen", "🗂 Restore from backup"),
+    (
+        "Oben das Passwort des Backups eingeben",
+        "Enter the backup's password above",
+    ),
+    ("Laden", "Load"),
+    (
+        "Backup geladen – Speichern übernimmt es",
+        "Backup loaded – saving keeps it",
+    ),
+    ("Vault: {} ({})", "Vault: {} ({})"),
+    ("📂 Datei öffnen", "📂 Open file"),
+    ("Alle Dateien", "All files"),
+    (
+        "Deine Daten werden lokal mit AES-256 oder ChaCha20 verschlüsselt",
+        "Your data is encrypted locally with AES-256 or ChaCha20",
+    ),
+    ("🔐 Neuen Vault anlegen", "🔐 Create new vault"),
+    (
+        "Das Passwort lässt sich nicht wiederherstellen",
+        "The password cannot be recovered",
+    ),
+    ("Passwort wählen:", "Choose password:"),
+    ("Neues Passwort", "New password"),
+    ("Passwort wiederholen:", "Repeat password:"),
+    ("Zur Bestätigung erneut eingeben", "Enter again to confirm"),
+    (
+        "Die Passwörter stimmen nicht überein",
+        "The passwords do not match",
+    ),
+    ("Verschlüsselung:", "Encryption:"),
+    ("✨ Vault anlegen", "✨ Create vault"),
+    ("Vault angelegt", "Vault created"),
+    ("Erfolgreich entsperrt", "Unlocked successfully"),
+    ("Passwortstärke: {}", "Password strength: {}"),
+    ("Sehr schwach", "Very weak"),
+    ("Schwach", "Weak"),
+    ("Mittel", "Fair"),
+    ("Stark", "Strong"),
+    ("Sehr stark", "Very strong"),
+    (
+        "⚠ Leicht zu erraten – ein längeres Passwort ist sicherer",
+        "⚠ Easy to guess – a longer password is safer",
+    ),
+    // Fehler aus dem Vault
+    ("Falsches Passwort", "Wrong password"),
+    (
+        "Datei beschädigt oder manipuliert",
+        "File is corrupted or has been tampered with",
+    ),
+    (
+        "Salt-Datei fehlt – Entschlüsselung unmöglich",
+        "Salt file missing – decryption impossible",
+    ),
+    ("Kein gültiger Vault", "Not a valid vault"),
+    // Vault-Quellen
+    ("Kommandozeile", "command line"),
+    ("Umgebungsvariable", "environment variable"),
+    ("zuletzt verwendet", "last used"),
+    ("Standard-Datenverzeichnis", "default data directory"),
+    ("Arbeitsverzeichnis (Legacy)", "working directory (legacy)"),
+    ("Dateidialog", "file dialog"),
+    // Editor
+    ("Ungespeicherte Änderungen", "Unsaved changes"),
+    ("Alles gespeichert", "Everything saved"),
+    ("💾 Speichern", "💾 Save"),
+    ("💾 Speichern ●", "💾 Save ●"),
+    ("Erfolgreich gespeichert", "Saved successfully"),
+    ("Fehler beim Speichern", "Error while saving"),
+    ("🚪 Speichern & Beenden", "🚪 Save & quit"),
+    ("Vault sperren", "Lock vault"),
+    (
+        "Falsches Passwort oder falsche Schlüsseldatei",
+        "Wrong password or key file",
+    ),
+    (
+        "Dieser Vault braucht eine Schlüsseldatei",
+        "This vault needs a key file",
+    ),
+    ("Schlüsseldatei ist leer", "Key file is empty"),
+    ("Schlüsseldatei:", "Key file:"),
+    ("🗝 Schlüsseldatei:", "🗝 Key file:"),
+    ("keine", "none"),
+    ("Wählen…", "Choose…"),
+    ("Keine Schlüsseldatei", "No key file"),
+    (
+        "Optional: Dann wird zum Entsperren neben dem Passwort auch diese Datei gebraucht",
+        "Optional: unlocking will then need this file in addition to the password",
+    ),
+    ("⏳ Noch {} s warten", "⏳ Wait {} s"),
+    (
+        "{} Fehlversuche in Folge – jeder weitere verlängert die Wartezeit",
+        "{} failed attempts in a row – each further one extends the wait",
+    ),
+    ("🔒 Vault gesperrt", "🔒 Vault locked"),
+    (
+        "Sperren abgebrochen: Speichern fehlgeschlagen",
+        "Lock cancelled: saving failed",
+    ),
+    ("Schlüsselableitung läuft noch", "Key derivation still running"),
+    (
+        "Gesperrt, aber {} Vault(s) ließen sich nicht sichern: Änderungen verworfen",
+        "Locked, but {} vault(s) could not be saved: changes discarded",
+    ),
+    (
+        "Gesperrt; Speichern fehlgeschlagen, Änderungen liegen in {}",
+        "Locked; saving failed, changes are in {}",
+    ),
+    ("Rückgängig (Strg+Z)", "Undo (Ctrl+Z)"),
+    ("Wiederholen (Strg+Y)", "Redo (Ctrl+Y)"),
+    ("Rückgängig gemacht", "Undone"),
+    ("Wiederhergestellt", "Redone"),
+    ("🔑 Passwort ändern", "🔑 Change password"),
+    ("🙈 Alle verbergen", "🙈 Hide all"),
+    ("👁 Alle anzeigen", "👁 Show all"),
+    ("📓 Notizen", "📓 Notes"),
+    (
+        "Wird zusammen mit den Einträgen verschlüsselt",
+        "Encrypted together with the entries",
+    ),
+    (
+        "z.B. Rotationsplan, offene Aufgaben...",
+        "e.g. rotation plan, open tasks...",
+    ),
+    ("CSV mit Kopfzeile", "CSV with header row"),
+    ("Helles Design", "Light theme"),
+    ("Dunkles Design", "Dark theme"),
+    ("Einstellungen", "Settings"),
+    ("🔍 Einträge durchsuchen...", "🔍 Search entries..."),
+    ("📋 wird in {} s geleert", "📋 cleared in {} s"),
+    ("Schlüssel", "Keys"),
+    ("Werte", "Values"),
+    ("Schlüssel & Werte", "Keys & values"),
+    ("➕ Neuen Eintrag hinzufügen", "➕ Add new entry"),
+    ("Schlüssel:", "Key:"),
+    ("z.B. api_key", "e.g. api_key"),
+    ("Wert:", "Value:"),
+    ("z.B. sk-1234567890abcdef", "e.g. sk-1234567890abcdef"),
+    (
+        "z.B. {\"host\": \"db\", \"port\": 5432}",
+        "e.g. {\"host\": \"db\", \"port\": 5432}",
+    ),
+    ("Passwort erzeugen", "Generate password"),
+    ("Mehrzeilig", "Multiline"),
+    ("Hinzufügen", "Add"),
+    ("Zahl", "Number"),
+    ("Ja/Nein", "Yes/No"),
+    ("Sofort verbergen", "Hide immediately"),
+    ("Kurz anzeigen", "Show briefly"),
+    ("Anzeigen", "Show"),
+    ("Sichtbarkeit neuer Einträge", "Visibility of new entries"),
+    ("Sortierung", "Sort order"),
+    ("Schlüssel A–Z", "Key A–Z"),
+    ("Schlüssel Z–A", "Key Z–A"),
+    ("Wert A–Z", "Value A–Z"),
+    ("Zuletzt geändert", "Recently modified"),
+    ("Reihenfolge des Hinzufügens", "Order of insertion"),
+    ("📝 Noch keine Einträge vorhanden", "📝 No entries yet"),
+    (
+        "Füge oben deinen ersten Eintrag hinzu",
+        "Add your first entry above",
+    ),
+    ("Eintrag hinzugefügt", "Entry added"),
+    ("Ungültiges JSON", "Invalid JSON"),
+    ("❌ Ungültiges JSON: {}", "❌ Invalid JSON: {}"),
+    // Zeilen
+    ("Doppelklick zum Umbenennen", "Double-click to rename"),
+    ("kritisch", "critical"),
+    ("geändert {}", "modified {}"),
+    ("Erstellt {}\nGeändert {}", "Created {}\nModified {}"),
+    ("🔍 Treffer im Wert", "🔍 Match in value"),
+    ("⚠ Steuerzeichen", "⚠ Control characters"),
+    (
+        "Der Wert enthält nicht druckbare Zeichen",
+        "The value contains non-printable characters",
+    ),
+    ("🧹 bereinigen", "🧹 clean up"),
+    ("Steuerzeichen entfernen", "Remove control characters"),
+    (
+        "Als Escape-Sequenz (\\u{..})",
+        "As escape sequence (\\u{..})",
+    ),
+    ("Eintrag löschen", "Delete entry"),
+    (
+        "Markierung \"kritisch\" entfernen",
+        "Remove \"critical\" mark",
+    ),
+    ("Als kritisch markieren", "Mark as critical"),
+    ("Wert verbergen", "Hide value"),
+    ("Wert anzeigen", "Show value"),
+    ("Wert kopieren", "Copy value"),
+    ("In Zwischenablage kopiert", "Copied to clipboard"),
+    ("Weitere Kopieroptionen", "More copy options"),
+    ("Schlüssel kopieren", "Copy key"),
+    ("Als JSON kopieren", "Copy as JSON"),
+    ("Schlüssel kopiert", "Key copied"),
+    ("Wert kopiert", "Value copied"),
+    ("Als JSON kopiert", "Copied as JSON"),
+    ("Zwischenablage geleert", "Clipboard cleared"),
+    ("Mehrzeilig bearbeiten", "Edit as multiline"),
+    ("Neues Passwort erzeugen", "Generate new password"),
+    ("🔗 öffnen", "🔗 open"),
+    ("Im Browser öffnen", "Open in browser"),
+    (
+        "Browser konnte nicht geöffnet werden",
+        "Could not open browser",
+    ),
+    ("📝 Notiz", "📝 Note"),
+    ("📝 Notiz: {}", "📝 Note: {}"),
+    ("Wofür ist dieser Eintrag?", "What is this entry for?"),
+    ("Tag entfernen", "Remove tag"),
+    ("➕ Tag", "➕ Tag"),
+    ("🏷 Filter:", "🏷 Filter:"),
+    ("✖ Filter aufheben", "✖ Clear filter"),
+    ("Schlüssel darf nicht leer sein", "Key must not be empty"),
+    (
+        "Leerzeichen am Anfang und Ende werden entfernt",
+        "Leading and trailing spaces will be removed",
+    ),
+    (
+        "Schlüssel \"{}\" existiert bereits",
+        "Key \"{}\" already exists",
+    ),
+    ("Eintrag umbenannt", "Entry renamed"),
+    ("gerade eben", "just now"),
+    // Dialoge
+    ("Abbrechen", "Cancel"),
+    ("Löschen bestätigen", "Confirm deletion"),
+    (
+        "Eintrag \"{}\" wirklich löschen?",
+        "Really delete entry \"{}\"?",
+    ),
+    (
+        "Dieser Eintrag ist als kritisch markiert.",
+        "This entry is marked as critical.",
+    ),
+    (
+        "Zur Bestätigung den Schlüssel exakt eintippen:",
+        "Type the key exactly to confirm:",
+    ),
+    ("Löschen", "Delete"),
+    ("Eintrag gelöscht", "Entry deleted"),
+    ("Kopie", "copy"),
+    ("Eintrag dupliziert", "Entry duplicated"),
+    ("Eintrag duplizieren", "Duplicate entry"),
+    ("🔒 Nur lesen", "🔒 Read-only"),
+    ("✏ Bearbeiten", "✏ Editing"),
+    (
+        "Nur ansehen und kopieren; klicken zum Bearbeiten",
+        "View and copy only; click to edit",
+    ),
+    ("In den Lesemodus wechseln", "Switch to read-only mode"),
+    ("zuletzt geöffnet", "recently opened"),
+    ("Zuletzt geöffnet:", "Recently opened:"),
+    ("{} Einträge gelöscht", "{} entries deleted"),
+    ("Ausgewählte löschen", "Delete selected"),
+    ("🗑 Ausgewählte löschen ({})", "🗑 Delete selected ({})"),
+    ("{} Einträge wirklich löschen?", "Really delete {} entries?"),
+    (
+        "Davon sind {} als kritisch markiert.",
+        "{} of them are marked as critical.",
+    ),
+    (
+        "Zur Bestätigung \"{}\" eintippen:",
+        "Type \"{}\" to confirm:",
+    ),
+    ("löschen", "delete"),
+    ("Alle auswählen", "Select all"),
+    (
+        "Gilt für die aktuell angezeigten Einträge",
+        "Applies to the entries currently shown",
+    ),
+    ("Überschreiben bestätigen", "Confirm overwrite"),
+    (
+        "Schlüssel \"{}\" existiert bereits — überschreiben?",
+        "Key \"{}\" already exists — overwrite?",
+    ),
+    ("Überschreiben", "Overwrite"),
+    (
+        "Es gibt ungespeicherte Änderungen. Vor dem Beenden speichern?",
+        "There are unsaved changes. Save before quitting?",
+    ),
+    ("Verwerfen", "Discard"),
+    ("Klartext exportieren ({})", "Export plaintext ({})"),
+    (
+        "⚠ Alle Einträge werden UNVERSCHLÜSSELT gespeichert.",
+        "⚠ All entries will be saved UNENCRYPTED.",
+    ),
+    (
+        "Jeder mit Zugriff auf die Datei kann die Geheimnisse lesen.",
+        "Anyone with access to the file can read the secrets.",
+    ),
+    ("Trotzdem exportieren", "Export anyway"),
+    ("Klartext-Export gespeichert", "Plaintext export saved"),
+    (
+        "Klartext-Export gespeichert, {} Einträge übersprungen (ungeeigneter Schlüssel, Anhang oder JSON)",
+        "Plaintext export saved, {} entries skipped (unsuitable key, attachment or JSON)",
+    ),
+    ("Export fehlgeschlagen", "Export failed"),
+    ("Passwort ändern", "Change password"),
+    ("Aktuelles Passwort:", "Current password:"),
+    ("Neues Passwort:", "New password:"),
+    ("Wiederholen:", "Repeat:"),
+    ("Ändern", "Change"),
+    ("Passwort geändert", "Password changed"),
+    (
+        "Neues Passwort darf nicht leer sein",
+        "New password must not be empty",
+    ),
+    (
+        "Die neuen Passwörter stimmen nicht überein",
+        "The new passwords do not match",
+    ),
+    ("Aktuelles Passwort ist falsch", "Current password is wrong"),
+    ("⚙ Einstellungen", "⚙ Settings"),
+    ("Zwischenablage leeren nach:", "Clear clipboard after:"),
+    (
+        "0 = nie automatisch leeren",
+        "0 = never clear automatically",
+    ),
+    ("Anzahl Backups:", "Number of backups:"),
+    (
+        "Rotierende .bak-Kopien vor jedem Speichern",
+        "Rotating .bak copies before every save",
+    ),
+    ("Automatisch speichern alle:", "Save automatically every:"),
+    ("Undo-Schritte:", "Undo steps:"),
+    ("Design:", "Theme:"),
+    ("Keine Regel", "No rule"),
+    ("URL", "URL"),
+    ("Ganzzahl", "Integer"),
+    ("Regulärer Ausdruck", "Regular expression"),
+    (
+        "Keine gültige URL (z.B. https://example.com)",
+        "Not a valid URL (e.g. https://example.com)",
+    ),
+    ("Keine ganze Zahl", "Not a whole number"),
+    ("Ungültiges Muster: {}", "Invalid pattern: {}"),
+    ("Passt nicht zum Muster {}", "Does not match the pattern {}"),
+    ("Übernehmen", "Apply"),
+    ("Prüfregel: {}", "Validation rule: {}"),
+    ("Prüfregeln verletzt", "Validation rules violated"),
+    (
+        "{} Einträge verletzen ihre Prüfregel:",
+        "{} entries violate their validation rule:",
+    ),
+    ("… und {} weitere", "… and {} more"),
+    ("Trotzdem speichern", "Save anyway"),
+    ("Verdeckte Werte:", "Masked values:"),
+    ("Beim Überfahren anzeigen", "Show on hover"),
+    ("ins Fenster gezogen", "dropped onto the window"),
+    ("Nicht unterstützter Dateityp", "Unsupported file type"),
+    (
+        "❌ {} kann nicht geöffnet werden, erwartet wird .json, .env, .csv oder .enc",
+        "❌ {} cannot be opened, expected .json, .env, .csv or .enc",
+    ),
+    (
+        "Import nur in einen entsperrten, bearbeitbaren Vault",
+        "Import only into an unlocked, editable vault",
+    ),
+    ("Datei importieren", "Import file"),
+    ("Entschlüssele...", "Decrypting..."),
+    ("Verschlüssele...", "Encrypting..."),
+    ("JSON-Quelltext anzeigen", "Show JSON source"),
+    ("{ } JSON-Quelltext", "{ } JSON source"),
+    (
+        "⚠ Klartext – nur lesen, Änderungen im Editor vornehmen",
+        "⚠ Plaintext – read only, make changes in the editor",
+    ),
+    ("Verdeckte Werte schwärzen", "Redact masked values"),
+    ("Leerzeichen:", "Whitespace:"),
+    (
+        "Beim Einfügen und Hinzufügen entfernen",
+        "Remove when pasting and adding",
+    ),
+    (
+        "Entfernt Leerzeichen und Zeilenumbrüche am Anfang und Ende",
+        "Removes leading and trailing spaces and line breaks",
+    ),
+    ("✂ Entfernen", "✂ Remove"),
+    ("Tab schließen", "Close tab"),
+    ("Verlauf je Eintrag:", "History per entry:"),
+    (
+        "Frühere Werte, gekürzt beim Speichern",
+        "Previous values, trimmed when saving",
+    ),
+    ("Frühere Werte mitexportieren", "Include previous values"),
+    ("Wiederherstellen", "Restore"),
+    ("Frühere Werte", "Previous values"),
+    (
+        "Die Datei wurde inzwischen von außen geändert",
+        "The file has been changed by another program in the meantime",
+    ),
+    ("Neu geladen", "Reloaded"),
+    ("Neu laden fehlgeschlagen", "Reload failed"),
+    ("Lade neu...", "Reloading..."),
+    (
+        "{} wurde inzwischen von außen geändert",
+        "{} has been changed by another program in the meantime",
+    ),
+    ("Datei von außen geändert", "File changed externally"),
+    (
+        "{} wurde von einem anderen Programm geändert ({}).",
+        "{} was changed by another program ({}).",
+    ),
+    (
+        "Speichern würde diese Änderungen überschreiben.",
+        "Saving would overwrite those changes.",
+    ),
+    (
+        "Neu laden: eigene Änderungen verwerfen (Rückgängig möglich)",
+        "Reload: discard your changes (can be undone)",
+    ),
+    (
+        "Zusammenführen: abweichende Einträge aus der Datei übernehmen",
+        "Merge: take over differing entries from the file",
+    ),
+    (
+        "Überschreiben: den eigenen Stand speichern",
+        "Overwrite: save your version",
+    ),
+    ("Neu laden", "Reload"),
+    ("Zusammenführen", "Merge"),
+    ("Später", "Later"),
+    ("Eintrag hinzufügen", "Add entry"),
+    ("Sperren", "Lock"),
+    ("Exportieren (JSON)", "Export (JSON)"),
+    ("JSON-Quelltext anzeigen", "Show JSON source"),
+    ("Befehlspalette", "Command palette"),
+    (
+        "Schlüssel oder Befehl suchen...",
+        "Search keys or commands...",
+    ),
+    ("Keine Treffer", "No matches"),
+    (
+        "↑↓ auswählen · Enter ausführen · Esc schließen",
+        "↑↓ select · Enter run · Esc close",
+    ),
+    ("Neue Einträge:", "New entries:"),
+    ("Schlüssel vorschlagen", "Suggest keys"),
+    (
+        "Erkennt bekannte Token-Formate im Wert und merkt sich \
+         zuletzt verwendete Schlüsselnamen",
+        "Recognizes known token formats in the value and remembers \
+         recently used key names",
+    ),
+    ("Zuletzt verwendete Schlüssel", "Recently used keys"),
+    ("💡 Vorschlag:", "💡 Suggestion:"),
+    ("Vorschlag ausblenden", "Hide suggestion"),
+    ("Oben links", "Top left"),
+    ("Oben mittig", "Top center"),
+    ("Oben rechts", "Top right"),
+    ("Unten links", "Bottom left"),
+    ("Unten rechts", "Bottom right"),
+    ("Meldungen:", "Notifications:"),
+    (
+        "Anzeigedauer im Verhältnis zum Standard",
+        "Display time relative to the default",
+    ),
+    ("Nicht mehr anheften", "Unpin"),
+    ("Als Favorit oben anheften", "Pin to the top as a favorite"),
+    ("⭐ Favoriten", "⭐ Favorites"),
+    ("Sofort sperren und verdecken", "Lock and hide immediately"),
+    ("Panik-Sperre:", "Panic lock:"),
+    ("aus", "off"),
+    (
+        "Z.B. Ctrl+L oder Ctrl+Shift+F12; leer schaltet sie aus",
+        "E.g. Ctrl+L or Ctrl+Shift+F12; empty turns it off",
+    ),
+    ("Fenster minimieren", "Minimize window"),
+    ("Schnell", "Fast"),
+    ("Ausgewogen", "Balanced"),
+    ("Paranoid", "Paranoid"),
+    ("Eigene Werte", "Custom values"),
+    ("Vault neu verschlüsselt", "Vault re-encrypted"),
+    ("Neu verschlüsseln fehlgeschlagen", "Re-encrypting failed"),
+    ("Schlüsselableitung:", "Key derivation:"),
+    ("Dieser Vault nutzt noch: {}", "This vault still uses: {}"),
+    ("🔑 Neu verschlüsseln", "🔑 Re-encrypt"),
+    (
+        "Leitet den Schlüssel mit diesen Werten neu ab und speichert",
+        "Derives the key again with these values and saves",
+    ),
+    ("Speicher", "Memory"),
+    ("Durchläufe", "Iterations"),
+    ("Parallelität", "Parallelism"),
+    (
+        "Ungefähre Dauer beim Entsperren",
+        "Approximate time to unlock",
+    ),
+    ("≈ {} ms", "≈ {} ms"),
+    ("≈ {} s", "≈ {} s"),
+    (
+        "Der geöffnete Vault kann nicht das Ziel sein",
+        "The open vault cannot be the target",
+    ),
+    ("📦 Als Vault teilen ({})", "📦 Share as vault ({})"),
+    (
+        "Ausgewählte Einträge mit eigenem Passwort in eine neue Datei",
+        "Selected entries into a new file with its own password",
+    ),
+    ("Auswahl als Vault teilen", "Share selection as vault"),
+    (
+        "Nur diese {} Einträge kommen in die Datei:",
+        "Only these {} entries go into the file:",
+    ),
+    (
+        "Passwort für die Datei (nicht dein Master-Passwort):",
+        "Password for the file (not your master password):",
+    ),
+    (
+        "Öffnen lässt sich die Datei mit dieser App und dem Passwort",
+        "The file opens with this app and the password",
+    ),
+    (
+        "{} Einträge als Vault exportiert",
+        "{} entries exported as vault",
+    ),
+    ("Exportieren…", "Export…"),
+    ("Export fehlgeschlagen: {}", "Export failed: {}"),
+    ("Gleiche Werte:", "Identical values:"),
+    (
+        "Mehrfach verwendete markieren",
+        "Mark values used more than once",
+    ),
+    ("⚠ mehrfach", "⚠ reused"),
+    (
+        "Derselbe Wert steht auch unter: {}",
+        "The same value is also stored under: {}",
+    ),
+    (
+        "⚠ {} Werte werden mehrfach verwendet",
+        "⚠ {} values are used more than once",
+    ),
+    (
+        "Suche als regulären Ausdruck",
+        "Search as regular expression",
+    ),
+    (
+        "ungültiger Ausdruck, suche wörtlich",
+        "invalid expression, searching literally",
+    ),
+    (
+        "HTTP-Zugang konnte nicht starten",
+        "HTTP access could not start",
+    ),
+    ("Kartenansicht", "Card view"),
+    ("Tabellenansicht", "Table view"),
+    ("Titel", "Title"),
+    ("Benutzername", "Username"),
+    ("Passwort", "Password"),
+    ("Notizen", "Notes"),
+    ("🔑 Passwort-Manager (CSV)...", "🔑 Password manager (CSV)..."),
+    ("Passwort-Manager importieren", "Import password manager"),
+    (
+        "Der Titel wird zum Schlüssel, Benutzername, Passwort und URL zum Wert, die Notizen zur Notiz.",
+        "The title becomes the key, username, password and URL the value, the notes the note.",
+    ),
+    ("– nicht übernehmen –", "– skip –"),
+    ("{} Zeilen gefunden", "{} rows found"),
+    ("Ohne Titel-Spalte gibt es keine Schlüssel", "Without a title column there are no keys"),
+    (
+        "Ohne Titel, werden übersprungen: Zeile {}",
+        "Without a title, will be skipped: line {}",
+    ),
+    ("{} Zeilen ohne Titel übersprungen", "{} rows without a title skipped"),
+    ("❌ Backup-Ordner: {}", "❌ Backup folder: {}"),
+    ("Backup-Ordner:", "Backup folder:"),
+    ("Keiner", "None"),
+    ("Keine Kopien mehr anlegen", "Stop making copies"),
+    ("Behalten:", "Keep:"),
+    ("Höchstens alle", "At most every"),
+    ("0 = bei jedem Speichern", "0 = on every save"),
+    (
+        "Verschlüsselte Kopien mit Zeitstempel, z.B. in einem synchronisierten Ordner",
+        "Encrypted timestamped copies, e.g. in a synced folder",
+    ),
+    (
+        "Vault-Datei war für andere lesbar, jetzt nur noch für dich (0600)",
+        "Vault file was readable by others, now only by you (0600)",
+    ),
+    (
+        "Vault-Datei ist für andere lesbar, Rechte ließen sich nicht ändern",
+        "Vault file is readable by others, permissions could not be changed",
+    ),
+    ("Ablauf zuerst", "Expiring first"),
+    ("Ablaufdatum", "Expiry date"),
+    ("Läuft ab am {}", "Expires on {}"),
+    ("In 30 Tagen", "In 30 days"),
+    ("In 90 Tagen", "In 90 days"),
+    ("In 1 Jahr", "In 1 year"),
+    ("JJJJ-MM-TT", "YYYY-MM-DD"),
+    ("Kein Ablaufdatum", "No expiry date"),
+    ("⏰ abgelaufen", "⏰ expired"),
+    ("⏰ läuft in {} Tagen ab", "⏰ expires in {} days"),
+    ("⏰ {} Einträge abgelaufen", "⏰ {} entries expired"),
+    ("⏰ {} laufen bald ab", "⏰ {} expiring soon"),
+    ("ℹ Über", "ℹ About"),
+    ("Über", "About"),
+    ("Version", "Version"),
+    ("Dateiformat", "File format"),
+    ("älteres Format ohne Kennung", "older format without marker"),
+    ("Verschlüsselung", "Encryption"),
+    ("Schlüsselableitung", "Key derivation"),
+    ("Noch keine Datei gespeichert", "No file saved yet"),
+    (
+        "Unscharfe Suche: Tippfehler und Lücken erlaubt, beste Treffer zuerst",
+        "Fuzzy search: gaps and typos allowed, best matches first",
+    ),
+    ("Nach Übereinstimmung", "By match"),
+    (
+        "Datei zu groß: {} (höchstens {}, da alles in einer verschlüsselten Datei liegt)",
+        "File too large: {} (at most {}, since everything lives in one encrypted file)",
+    ),
+    (
+        "Datei anhängen (ersetzt den Wert, höchstens {})",
+        "Attach file (replaces the value, at most {})",
+    ),
+    ("Anhang speichern unter...", "Save attachment as..."),
+    ("Datei angehängt", "File attached"),
+    ("Anhang abgelehnt", "Attachment refused"),
+    ("Anhang gespeichert", "Attachment saved"),
+    ("mindestens {} Zeichen", "at least {} characters"),
+    ("ein Kleinbuchstabe", "a lowercase letter"),
+    ("ein Großbuchstabe", "an uppercase letter"),
+    ("eine Ziffer", "a digit"),
+    ("ein Sonderzeichen", "a symbol"),
+    (
+        "Passwort erfüllt die Richtlinie nicht, es fehlt: {}",
+        "Password does not meet the policy, missing: {}",
+    ),
+    ("Passwort-Richtlinie: {}", "Password policy: {}"),
+    ("Passwort-Richtlinie:", "Password policy:"),
+    ("Mindestlänge:", "Minimum length:"),
+    ("0 = keine Vorgabe", "0 = no requirement"),
+    ("Durch --policy vorgegeben", "Set by --policy"),
+    (
+        "Gilt für neue Vaults und Passwortwechsel, nicht beim Entsperren",
+        "Applies to new vaults and password changes, not when unlocking",
+    ),
+    ("Sicherung fehlgeschlagen", "Backup failed"),
+    (
+        "❌ Sicherung fehlgeschlagen, nichts gelöscht: {}",
+        "❌ Backup failed, nothing was deleted: {}",
+    ),
+    ("LÖSCHEN", "DELETE"),
+    ("Alle Einträge löschen", "Delete all entries"),
+    (
+        "Alle {} Einträge aus \"{}\" samt Notizen, Tags und Verlauf löschen?",
+        "Delete all {} entries from \"{}\" including notes, tags and history?",
+    ),
+    (
+        "Vorher verschlüsselte Sicherung anlegen",
+        "Create an encrypted backup first",
+    ),
+    ("Im Backup-Ordner {}", "In the backup folder {}"),
+    (
+        "Neben dem Vault, mit Zeitstempel im Namen",
+        "Next to the vault, with a timestamp in the name",
+    ),
+    (
+        "Zur Bestätigung \"{}\" oder den Vault-Namen \"{}\" eintippen:",
+        "Type \"{}\" or the vault name \"{}\" to confirm:",
+    ),
+    ("Alle löschen", "Delete all"),
+    ("🗑 Alle löschen…", "🗑 Delete all…"),
+    (
+        "Leert diesen Vault nach einer getippten Bestätigung",
+        "Empties this vault after a typed confirmation",
+    ),
+    ("📂 Vault öffnen…", "📂 Open vault…"),
+    ("✨ Neuer Vault…", "✨ New vault…"),
+    (
+        "Weiteren Vault in neuem Tab öffnen",
+        "Open another vault in a new tab",
+    ),
+    (
+        "\"{}\" hat ungespeicherte Änderungen. Vor dem Schließen speichern?",
+        "\"{}\" has unsaved changes. Save before closing?",
+    ),
+    (
+        "␣ Leerzeichen oder Zeilenumbruch am Anfang/Ende",
+        "␣ Leading/trailing space or line break",
+    ),
+    ("Schlüsselableitung abgebrochen", "Key derivation aborted"),
+    (
+        "{} Einträge aus \"{}\" in diesen Vault importieren?",
+        "Import {} entries from \"{}\" into this vault?",
+    ),
+    (
+        "Aus: Werte nur per 👁 aufdecken",
+        "Off: reveal values only via 👁",
+    ),
+    ("Dunkel", "Dark"),
+    ("Hell", "Light"),
+    (
+        "Gespeichert unverschlüsselt in settings.json, ohne Passwort",
+        "Stored unencrypted in settings.json, without the password",
+    ),
+    ("Tastenkürzel", "Keyboard shortcuts"),
+    ("❓ Tastenkürzel", "❓ Keyboard shortcuts"),
+    ("Strg+S", "Ctrl+S"),
+    ("Strg+F", "Ctrl+F"),
+    ("Strg+Z", "Ctrl+Z"),
+    ("Strg+Y", "Ctrl+Y"),
+    ("Speichern", "Save"),
+    ("Suche fokussieren", "Focus search"),
+    ("Rückgängig", "Undo"),
+    ("Wiederholen", "Redo"),
+    ("Dialog schließen", "Close dialog"),
+    ("Eingabe bestätigen", "Confirm input"),
+    ("Sprache:", "Language:"),
+    ("Systemsprache", "System language"),
+    ("Speichern fehlgeschlagen: {}", "Saving failed: {}"),
+    ("Automatisch gespeichert", "Saved automatically"),
+    (
+        "Automatisches Speichern fehlgeschlagen",
+        "Automatic save failed",
+    ),
+    // Import
+    ("JSON importieren", "Import JSON"),
+    ("Datei:", "File:"),
+    ("/pfad/zur/datei.json", "/path/to/file.json"),
+    ("📂 Auswählen", "📂 Browse"),
+    ("Format", "Format"),
+    ("Automatisch erkennen", "Detect automatically"),
+    ("Schlüsselfeld:", "Key field:"),
+    ("Wertfeld:", "Value field:"),
+    ("Importieren", "Import"),
+    ("Import fehlgeschlagen", "Import failed"),
+    ("{} Einträge importiert", "{} entries imported"),
+    (
+        "{} Einträge importiert, {} übersprungen",
+        "{} entries imported, {} skipped",
+    ),
+    ("Schlüssel existieren bereits", "Keys already exist"),
+    (
+        "{} von {} importierten Schlüsseln gibt es schon.",
+        "{} of {} imported keys already exist.",
+    ),
+    ("Überspringen", "Skip"),
+    ("Beide behalten", "Keep both"),
+    (
+        "Importierte Schlüssel erhalten ein Suffix wie \" (2)\"",
+        "Imported keys get a suffix like \" (2)\"",
+    ),
+    // Wiederherstellung
+    ("Daten wiederherstellen", "Recover data"),
+    (
+        "⚠ Der Inhalt wird unverschlüsselt angezeigt",
+        "⚠ The content is shown unencrypted",
+    ),
+    ("Erneut laden", "Load again"),
+    (
+        "Daten wiederhergestellt – bitte speichern",
+        "Data recovered – please save",
+    ),
+    ("💾 Als Datei sichern", "💾 Save as file"),
+    ("Rohdaten gesichert", "Raw data saved"),
+    // Passwortgenerator
+    ("Länge", "Length"),
+    ("Großbuchstaben (A-Z)", "Uppercase (A-Z)"),
+    ("Kleinbuchstaben (a-z)", "Lowercase (a-z)"),
+    ("Ziffern (0-9)", "Digits (0-9)"),
+    ("Sonderzeichen (!#$…)", "Symbols (!#$…)"),
+    ("🎲 Passwort übernehmen", "🎲 Use password"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translations_keep_placeholders_and_fall_back_to_german() {
+        for (german, english) in ENGLISH {
+            assert_eq!(
+                german.matches("{}").count(),
+                english.matches("{}").count(),
+                "Platzhalter in \"{}\"",
+                german
+            );
+        }
+        assert_eq!(translate(Language::English, "Abbrechen"), "Cancel");
+        assert_eq!(translate(Language::German, "Abbrechen"), "Abbrechen");
+        assert_eq!(translate(Language::English, "unbekannt"), "unbekannt");
+        assert_eq!(Language::from_locale("en_US.UTF-8"), Language::English);
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), Language::German);
+    }
+}
@@ -0,0 +1,221 @@
+//! Import von Passwort-Manager-Exporten, allen voran KeePass-CSV mit der Kopfzeile
+//! „Title,Username,Password,URL,Notes“. Die Spalten werden anhand der Kopfzeile
+//! erraten und lassen sich im Dialog neu zuordnen.
+
+use std::collections::HashSet;
+
+use crate::i18n::tr;
+use crate::unique_key;
+
+/// Eine CSV-Datei mit der Zeilennummer jedes Datensatzes für Fehlermeldungen
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CsvTable {
+    pub(crate) headers: Vec<String>,
+    pub(crate) rows: Vec<(u64, Vec<String>)>,
+}
+
+/// Liest CSV mit beliebig vielen Spalten. Felder in Anführungszeichen dürfen
+/// Kommas und Zeilenumbrüche enthalten; fehlerhafte Zeilen werden mit Nummer gemeldet.
+pub(crate) fn read_csv(text: &str, has_header: bool) -> Result<CsvTable, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(has_header)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let headers = if has_header {
+        reader
+            .headers()
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(|header| header.trim().to_string())
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| match e.position() {
+            Some(position) => format!("Zeile {}: {}", position.line(), e),
+            None => e.to_string(),
+        })?;
+        let line = record.position().map_or(0, |p| p.line());
+        rows.push((line, record.iter().map(str::to_string).collect()));
+    }
+    Ok(CsvTable { headers, rows })
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum LoginField {
+    Title,
+    Username,
+    Password,
+    Url,
+    Notes,
+}
+
+impl LoginField {
+    pub(crate) const ALL: [LoginField; 5] = [
+        LoginField::Title,
+        LoginField::Username,
+        LoginField::Password,
+        LoginField::Url,
+        LoginField::Notes,
+    ];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            LoginField::Title => tr("Titel"),
+            LoginField::Username => tr("Benutzername"),
+            LoginField::Password => tr("Passwort"),
+            LoginField::Url => tr("URL"),
+            LoginField::Notes => tr("Notizen"),
+        }
+    }
+
+    /// Feldname im JSON-Wert des importierten Eintrags
+    fn json_name(self) -> &'static str {
+        match self {
+            LoginField::Title => "title",
+            LoginField::Username => "username",
+            LoginField::Password => "password",
+            LoginField::Url => "url",
+            LoginField::Notes => "notes",
+        }
+    }
+
+    /// Kopfzeilen gängiger Exporte (KeePass, Bitwarden, Chrome, 1Password), klein geschrieben
+    fn header_names(self) -> &'static [&'static str] {
+        match self {
+            LoginField::Title => &["title", "name", "titel", "account"],
+            LoginField::Username => &[
+                "username",
+                "user name",
+                "login_username",
+                "login",
+                "user",
+                "benutzername",
+            ],
+            LoginField::Password => &["password", "login_password", "passwort", "pass"],
+            LoginField::Url => &["url", "login_uri", "website", "web site", "uri", "adresse"],
+            LoginField::Notes => &["notes", "note", "notizen", "notiz", "comments", "extra"],
+        }
+    }
+}
+
+/// Spalte je Feld in der Reihenfolge von `LoginField::ALL`; `None` wird nicht übernommen
+pub(crate) type ColumnMap = [Option<usize>; 5];
+
+/// Ordnet die Spalten anhand der Kopfzeile zu
+pub(crate) fn guess_columns(headers: &[String]) -> ColumnMap {
+    LoginField::ALL.map(|field| {
+        headers.iter().position(|header| {
+            field
+                .header_names()
+                .contains(&header.to_lowercase().as_str())
+        })
+    })
+}
+
+/// Ergebnis der Zuordnung, bereit für den normalen Import
+#[derive(Debug, Default)]
+pub(crate) struct LoginImport {
+    pub(crate) entries: Vec<(String, serde_json::Value)>,
+    /// Notiz je importiertem Schlüssel
+    pub(crate) notes: Vec<(String, String)>,
+    /// Zeilennummern der Datensätze ohne Titel
+    pub(crate) skipped_lines: Vec<u64>,
+}
+
+/// Macht aus jeder Zeile einen Eintrag: der Titel wird zum Schlüssel, Benutzername,
+/// Passwort und URL zu einem JSON-Objekt, die Notizen zur Notiz des Eintrags.
+/// Doppelte Titel innerhalb der Datei erhalten ein Suffix wie " (2)".
+pub(crate) fn map_rows(table: &CsvTable, columns: &ColumnMap) -> LoginImport {
+    // Passwort und Notizen bleiben exakt, Leerzeichen können dort dazugehören
+    let cell = |row: &[String], field: LoginField| {
+        let value = columns[field as usize]
+            .and_then(|column| row.get(column))
+            .map_or("", String::as_str);
+        match field {
+            LoginField::Password | LoginField::Notes => value.to_string(),
+            _ => value.trim().to_string(),
+        }
+    };
+    let mut import = LoginImport::default();
+    let mut seen = HashSet::new();
+    for (line, row) in &table.rows {
+        let title = cell(row, LoginField::Title);
+        if title.is_empty() {
+            import.skipped_lines.push(*line);
+            continue;
+        }
+        let key = if seen.contains(&title) {
+            unique_key(&title, |candidate| seen.contains(candidate))
+        } else {
+            title
+        };
+        seen.insert(key.clone());
+        let mut value = serde_json::Map::new();
+        for field in [LoginField::Username, LoginField::Password, LoginField::Url] {
+            let text = cell(row, field);
+            if columns[field as usize].is_some() && !text.is_empty() {
+                value.insert(field.json_name().into(), text.into());
+            }
+        }
+        let note = cell(row, LoginField::Notes);
+        if !note.trim().is_empty() {
+            import.notes.push((key.clone(), note));
+        }
+        import.entries.push((key, value.into()));
+    }
+    import
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keepass_rows_become_entries_with_notes() {
+        let text = "\"Title\",\"Username\",\"Password\",\"URL\",\"Notes\"\n\
+                    \"Mail\",\"tim\",\"geheim\",\"https://mail.example\",\"Zeile 1\nZeile 2\"\n\
+                    \"\",\"niemand\",\"x\",\"\",\"\"\n\
+                    \"Mail\",\"work\",\"pw2\",\"\",\"\"\n\
+                    \" Bank \",\" kunde \",\" pa ss \",\"\",\" eingerückt\"\n";
+        let table = read_csv(text, true).unwrap();
+        let columns = guess_columns(&table.headers);
+        assert_eq!(columns, [Some(0), Some(1), Some(2), Some(3), Some(4)]);
+
+        let import = map_rows(&table, &columns);
+        assert_eq!(import.skipped_lines, [4]);
+        assert_eq!(import.entries[0].0, "Mail");
+        assert_eq!(
+            import.entries[0].1,
+            serde_json::json!({"username": "tim", "password": "geheim", "url": "https://mail.example"})
+        );
+        assert_eq!(import.entries[1].0, "Mail (2)");
+        // Titel und Benutzername werden getrimmt, Passwort und Notiz nicht
+        assert_eq!(import.entries[2].0, "Bank");
+        assert_eq!(
+            import.entries[2].1,
+            serde_json::json!({"username": "kunde", "password": " pa ss "})
+        );
+        assert_eq!(
+            import.notes,
+            [
+                ("Mail".into(), "Zeile 1\nZeile 2".into()),
+                ("Bank".into(), " eingerückt".into())
+            ]
+        );
+
+        // Abweichende Kopfzeile: fehlende Zuordnung bleibt leer und wird von Hand gesetzt
+        let table = read_csv("Konto,Geheimnis\nBank,1234\n", true).unwrap();
+        let mut columns = guess_columns(&table.headers);
+        assert_eq!(columns, [None; 5]);
+        columns[LoginField::Title as usize] = Some(0);
+        columns[LoginField::Password as usize] = Some(1);
+        let import = map_rows(&table, &columns);
+        assert_eq!(
+            import.entries,
+            [("Bank".into(), serde_json::json!({"password": "1234"}))]
+        );
+    }
+}
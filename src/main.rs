@@ -1,54 +1,772 @@
 use eframe::egui;
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
 
-use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
-    Aes256Gcm, Key, Nonce,
-};
-use argon2::{
-    password_hash::{rand_core::RngCore, SaltString},
-    Argon2, PasswordHasher,
+mod attachment;
+mod cli;
+mod dates;
+mod generator;
+mod i18n;
+mod logins;
+mod palette;
+mod policy;
+mod serve;
+mod validation;
+mod vault;
+
+use attachment::{format_size, Attachment, MAX_ATTACHMENT_BYTES};
+use dates::{format_date, parse_date};
+use generator::{show_generator_menu, PasswordGenerator};
+use i18n::{tr, trf, Language};
+use palette::{fuzzy_match, fuzzy_score, palette_matches, PaletteCommand, PaletteItem};
+use policy::PasswordPolicy;
+use validation::{show_rule_menu, ValidationRule};
+use vault::{
+    backup_path, is_wrong_password, read_key_file, CipherSuite, FileHeader, KdfParams, KdfPreset,
+    Vault, ERR_KEY_FILE_REQUIRED, FORMAT_VERSION,
 };
-use base64;
 
 const ENCRYPTED_FILE: &str = "data.enc";
-const SALT_FILE: &str = "salt.txt";
+/// Umgebungsvariable, die den Vault-Pfad vorgibt
+const VAULT_PATH_ENV: &str = "ENCRYPTED_JSON_EDITOR_VAULT";
+/// Datei im Konfigurationsverzeichnis mit dem zuletzt geöffneten Vault
+const LAST_VAULT_FILE: &str = "last_vault.txt";
+/// Nicht geheime Einstellungen im Konfigurationsverzeichnis, unverschlüsselt
+const SETTINGS_FILE: &str = "settings.json";
+/// So viele zuletzt geöffnete Vaults merkt sich der Login-Bildschirm
+const MAX_RECENT_FILES: usize = 8;
+const MAX_RECENT_KEY_NAMES: usize = 8;
+/// Fenstergröße beim ersten Start und kleinste erlaubte Größe
+const DEFAULT_WINDOW_SIZE: [f32; 2] = [1000.0, 700.0];
+const MIN_WINDOW_SIZE: [f32; 2] = [600.0, 500.0];
+/// Fehlversuche beim Entsperren, die ohne Wartezeit erlaubt sind
+const FREE_LOGIN_ATTEMPTS: u32 = 3;
+/// Obergrenze der Wartezeit nach wiederholten Fehlversuchen
+const MAX_LOGIN_DELAY_SECONDS: u64 = 300;
+/// Standardzeit, nach der ein kopierter Wert aus der Zwischenablage entfernt wird
+const DEFAULT_CLIPBOARD_CLEAR_SECONDS: f64 = 20.0;
+/// Tastenkürzel, das sofort sperrt und den Inhalt verdeckt
+const DEFAULT_PANIC_SHORTCUT: &str = "Ctrl+L";
+/// Anzahl der rotierenden `.bak`-Kopien, die vor dem Überschreiben behalten werden
+const DEFAULT_BACKUP_COUNT: usize = 3;
+/// Kopien mit Zeitstempel, die im Backup-Ordner je Vault bleiben
+const DEFAULT_BACKUP_DIR_KEEP: usize = 30;
+/// So viele Tage vor dem Ablaufdatum wird ein Eintrag gelb markiert
+const EXPIRY_WARNING_DAYS: u64 = 14;
+/// Wie lange ein neuer Eintrag bei `NewEntryVisibility::RevealBriefly` sichtbar bleibt
+const NEW_ENTRY_REVEAL_SECONDS: f64 = 5.0;
+const DEFAULT_UNDO_DEPTH: usize = 100;
+const DEFAULT_HISTORY_LIMIT: usize = 10;
+/// Änderungen in kürzerem Abstand gelten als ein Bearbeitungsvorgang, in den
+/// Verlauf kommt nur der Wert davor
+const HISTORY_COALESCE_SECONDS: u64 = 5;
+/// Abstand, in dem die Änderungszeit der offenen Vault-Datei geprüft wird
+const DISK_CHECK_SECONDS: f64 = 2.0;
+/// So lange leuchtet eine über die Befehlspalette angesprungene Zeile nach
+const JUMP_HIGHLIGHT_SECONDS: f64 = 1.5;
+const DEFAULT_AUTO_SAVE_SECONDS: f64 = 60.0;
+/// Änderungen am selben Wert innerhalb dieser Zeit bilden einen Undo-Schritt
+const UNDO_COALESCE_SECONDS: f64 = 1.0;
+/// Wartezeit nach dem letzten Tastendruck, bevor die Suche neu filtert
+const SEARCH_DEBOUNCE_SECONDS: f64 = 0.15;
+/// Geschätzte Höhe einer Eintragszeile für die virtualisierte Liste
+const ENTRY_ROW_HEIGHT: f32 = 124.0;
+/// Zeilenhöhe der kompakten Tabellenansicht
+const GRID_ROW_HEIGHT: f32 = 26.0;
+/// Mehr Toasts werden nicht gleichzeitig gezeigt, der Rest wartet in einer Schlange
+const MAX_VISIBLE_TOASTS: usize = 4;
+const TOAST_SPACING: f32 = 8.0;
+/// Oben bleibt die Werkzeugleiste frei, wie beim Tastenkürzel-Fenster
+const TOAST_TOP_MARGIN: f32 = 64.0;
+/// Kürzer lassen sich Ein- und Ausblenden nicht mehr unterbringen
+const MIN_TOAST_SECONDS: f64 = 1.0;
 
 #[derive(Serialize, Deserialize, Clone)]
 struct AppData {
-    items: HashMap<String, String>,
+    /// Reihenfolge des Einfügens bleibt über Speichern/Laden erhalten
+    items: IndexMap<String, EntryValue>,
+    /// Freier Notizblock, ältere Vaults ohne dieses Feld laden mit leerem Text
+    #[serde(default)]
+    notes: String,
+    /// Schlüssel, deren Löschung eine getippte Bestätigung verlangt
+    #[serde(default)]
+    critical: HashSet<String>,
+    /// Notiz und Zeitstempel je Schlüssel; fehlende Einträge ergänzt `fill_missing_meta`
+    #[serde(default)]
+    meta: HashMap<String, EntryMeta>,
+    /// Neu vergeben bei jeder Änderung an Schlüsseln, Werten oder Tags, damit
+    /// abgeleitete Caches erkennen, wann sie veraltet sind. Wird nicht gespeichert.
+    #[serde(skip, default = "next_revision")]
+    revision: u64,
+}
+
+fn next_revision() -> u64 {
+    static NEXT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+    NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+struct EntryMeta {
+    #[serde(default)]
+    note: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Frühere Werte mit dem Zeitpunkt ihrer Ablösung, ältester zuerst
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    history: Vec<(SystemTime, EntryValue)>,
+    /// Prüfregel für den Wert; ohne Regel wird das Feld nicht gespeichert
+    #[serde(default, skip_serializing_if = "ValidationRule::is_none")]
+    rule: ValidationRule,
+    /// Favoriten stehen angeheftet über der übrigen Liste
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    favorite: bool,
+    /// Ab hier gilt der Wert als abgelaufen und sollte erneuert werden
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    expires_at: Option<SystemTime>,
+    created: SystemTime,
+    modified: SystemTime,
+}
+
+impl EntryMeta {
+    fn new(now: SystemTime) -> Self {
+        Self {
+            note: String::new(),
+            tags: Vec::new(),
+            history: Vec::new(),
+            rule: ValidationRule::None,
+            favorite: false,
+            expires_at: None,
+            created: now,
+            modified: now,
+        }
+    }
+}
+
+impl AppData {
+    /// Setzt einen Wert und pflegt dabei Erstell- und Änderungszeit
+    /// Der bisherige Wert wandert in den Verlauf, außer er stammt selbst aus dem
+    /// laufenden Bearbeitungsvorgang (siehe `HISTORY_COALESCE_SECONDS`)
+    fn set_value(&mut self, key: String, value: EntryValue) {
+        let now = SystemTime::now();
+        let previous = self.items.get(&key).filter(|previous| **previous != value);
+        let meta = self
+            .meta
+            .entry(key.clone())
+            .or_insert_with(|| EntryMeta::new(now));
+        if let Some(previous) = previous {
+            let editing = now
+                .duration_since(meta.modified)
+                .is_ok_and(|since| since < Duration::from_secs(HISTORY_COALESCE_SECONDS));
+            if !editing {
+                meta.history.push((now, previous.clone()));
+            }
+        }
+        meta.modified = now;
+        self.items.insert(key, value);
+        self.touch();
+    }
+
+    /// Kürzt jeden Verlauf auf die neuesten `limit` Werte
+    fn trim_history(&mut self, limit: usize) {
+        for meta in self.meta.values_mut() {
+            let excess = meta.history.len().saturating_sub(limit);
+            meta.history.drain(..excess);
+        }
+    }
+
+    /// Nur die Einträge aus `keys` samt Metadaten, in Listenreihenfolge. Der
+    /// Verlauf bleibt draußen, er enthält womöglich längst ersetzte Geheimnisse.
+    fn subset(&self, keys: &HashSet<String>) -> AppData {
+        let mut subset = AppData::default();
+        for (key, value) in self.items.iter().filter(|(key, _)| keys.contains(*key)) {
+            subset.items.insert(key.clone(), value.clone());
+            if let Some(meta) = self.meta.get(key) {
+                subset.meta.insert(
+                    key.clone(),
+                    EntryMeta {
+                        history: Vec::new(),
+                        ..meta.clone()
+                    },
+                );
+            }
+            if self.critical.contains(key) {
+                subset.critical.insert(key.clone());
+            }
+        }
+        subset
+    }
+
+    fn remove_entry(&mut self, key: &str) -> Option<EntryValue> {
+        self.touch();
+        self.critical.remove(key);
+        self.meta.remove(key);
+        self.items.shift_remove(key)
+    }
+
+    fn touch(&mut self) {
+        self.revision = next_revision();
+    }
+
+    /// Wie viele Einträge jeden Tag tragen, alphabetisch
+    fn tag_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for meta in self.meta.values() {
+            for tag in &meta.tags {
+                *counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Trägt der Eintrag alle angegebenen Tags?
+    fn has_tags(&self, key: &str, tags: &BTreeSet<String>) -> bool {
+        tags.is_empty()
+            || self
+                .meta
+                .get(key)
+                .is_some_and(|meta| tags.iter().all(|tag| meta.tags.contains(tag)))
+    }
+
+    fn is_favorite(&self, key: &str) -> bool {
+        self.meta.get(key).is_some_and(|meta| meta.favorite)
+    }
+
+    fn expires_at(&self, key: &str) -> Option<SystemTime> {
+        self.meta.get(key).and_then(|meta| meta.expires_at)
+    }
+
+    /// Anzahl (abgelaufen, läuft bald ab) über alle Einträge
+    fn expiry_counts(&self, now: SystemTime) -> (usize, usize) {
+        self.items
+            .keys()
+            .filter_map(|key| expiry_state(self.expires_at(key)?, now))
+            .fold((0, 0), |(expired, soon), state| match state {
+                Expiry::Expired => (expired + 1, soon),
+                Expiry::Soon(_) => (expired, soon + 1),
+            })
+    }
+
+    /// Ältere Vaults kennen keine Metadaten: deren Einträge gelten als jetzt angelegt
+    fn fill_missing_meta(&mut self) {
+        let now = SystemTime::now();
+        for key in self.items.keys() {
+            self.meta
+                .entry(key.clone())
+                .or_insert_with(|| EntryMeta::new(now));
+        }
+        let items = &self.items;
+        self.meta.retain(|key, _| items.contains_key(key));
+    }
+}
+
+/// Wert eines Eintrags. Ohne Tag serialisiert, damit die Datei natives JSON bleibt:
+/// alte Vaults mit reinen Strings laden als `Text`, Objekte und Listen als `Json`.
+/// `Attachment` muss vor `Json` stehen, sonst würde jede Datei als Objekt geladen.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+enum EntryValue {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    Attachment(Attachment),
+    Json(serde_json::Value),
+}
+
+impl From<serde_json::Value> for EntryValue {
+    fn from(json: serde_json::Value) -> Self {
+        match json {
+            serde_json::Value::String(text) => EntryValue::Text(text),
+            serde_json::Value::Bool(flag) => EntryValue::Bool(flag),
+            serde_json::Value::Number(number) => match number.as_f64() {
+                Some(number) => EntryValue::Number(number),
+                None => EntryValue::Json(serde_json::Value::Number(number)),
+            },
+            serde_json::Value::Object(_) => match serde_json::from_value(json.clone()) {
+                Ok(attachment) => EntryValue::Attachment(attachment),
+                Err(_) => EntryValue::Json(json),
+            },
+            other => EntryValue::Json(other),
+        }
+    }
+}
+
+impl EntryValue {
+    /// Textdarstellung für Kopieren, Suche und Prüfungen; Anhänge zeigen nur
+    /// ihren Dateinamen, der Inhalt wird über „Speichern unter“ exportiert
+    fn as_text(&self) -> String {
+        match self {
+            EntryValue::Text(text) => text.clone(),
+            EntryValue::Number(number) => number.to_string(),
+            EntryValue::Bool(flag) => flag.to_string(),
+            EntryValue::Attachment(attachment) => attachment.filename.clone(),
+            EntryValue::Json(json) => json.to_string(),
+        }
+    }
+
+    /// Wert für .env und CSV, die nur einzelne Texte kennen. Anhänge und JSON
+    /// ließen sich dort nicht verlustfrei ablegen und liefern `None`.
+    fn as_flat_text(&self) -> Option<String> {
+        match self {
+            EntryValue::Text(_) | EntryValue::Number(_) | EntryValue::Bool(_) => {
+                Some(self.as_text())
+            }
+            EntryValue::Attachment(_) | EntryValue::Json(_) => None,
+        }
+    }
+}
+
+/// Alles, wovon die gefilterte Schlüsselliste abhängt
+#[derive(PartialEq)]
+struct FilterInputs {
+    revision: u64,
+    query: String,
+    regex: bool,
+    fuzzy: bool,
+    scope: SearchScope,
+    tags: BTreeSet<String>,
+    sort: SortMode,
+}
+
+/// Worin die Suche nach Treffern sucht
+#[derive(Clone, Copy, PartialEq)]
+enum SearchScope {
+    Keys,
+    Values,
+    Both,
+}
+
+impl SearchScope {
+    fn label(&self) -> &'static str {
+        match self {
+            SearchScope::Keys => tr("Schlüssel"),
+            SearchScope::Values => tr("Werte"),
+            SearchScope::Both => tr("Schlüssel & Werte"),
+        }
+    }
+}
+
+/// Reihenfolge der Einträge in der Liste
+#[derive(Clone, Copy, PartialEq)]
+enum SortMode {
+    KeyAscending,
+    KeyDescending,
+    ValueAscending,
+    RecentlyModified,
+    ExpiresSoonest,
+    Insertion,
+}
+
+impl SortMode {
+    const ALL: [SortMode; 6] = [
+        SortMode::KeyAscending,
+        SortMode::KeyDescending,
+        SortMode::ValueAscending,
+        SortMode::RecentlyModified,
+        SortMode::ExpiresSoonest,
+        SortMode::Insertion,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            SortMode::KeyAscending => tr("Schlüssel A–Z"),
+            SortMode::KeyDescending => tr("Schlüssel Z–A"),
+            SortMode::ValueAscending => tr("Wert A–Z"),
+            SortMode::RecentlyModified => tr("Zuletzt geändert"),
+            SortMode::ExpiresSoonest => tr("Ablauf zuerst"),
+            SortMode::Insertion => tr("Reihenfolge des Hinzufügens"),
+        }
+    }
+}
+
+/// Auswahl im Hinzufügen-Formular
+#[derive(Clone, Copy, PartialEq)]
+enum ValueType {
+    Text,
+    Number,
+    Bool,
+    Json,
+}
+
+impl ValueType {
+    fn label(&self) -> &'static str {
+        match self {
+            ValueType::Text => "Text",
+            ValueType::Number => tr("Zahl"),
+            ValueType::Bool => tr("Ja/Nein"),
+            ValueType::Json => "JSON",
+        }
+    }
 }
 
 impl Default for AppData {
     fn default() -> Self {
-        let items = HashMap::new();
-        Self { items }
+        let items = IndexMap::new();
+        Self {
+            items,
+            notes: String::new(),
+            critical: HashSet::new(),
+            meta: HashMap::new(),
+            revision: next_revision(),
+        }
     }
 }
 
 enum Screen {
+    /// Erster Start: Vault existiert noch nicht, Passwort wird doppelt abgefragt
+    Setup,
     PasswordInput,
     Editor,
 }
 
+/// Woher der verwendete Vault-Pfad stammt, in absteigender Priorität
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum VaultPathSource {
+    CliArgument,
+    Environment,
+    LastUsed,
+    DataDir,
+    WorkingDirectory,
+    FileDialog,
+    RecentFile,
+    DroppedFile,
+}
+
+impl VaultPathSource {
+    fn label(&self) -> &'static str {
+        match self {
+            VaultPathSource::CliArgument => tr("Kommandozeile"),
+            VaultPathSource::Environment => tr("Umgebungsvariable"),
+            VaultPathSource::LastUsed => tr("zuletzt verwendet"),
+            VaultPathSource::DataDir => tr("Standard-Datenverzeichnis"),
+            VaultPathSource::WorkingDirectory => tr("Arbeitsverzeichnis (Legacy)"),
+            VaultPathSource::FileDialog => tr("Dateidialog"),
+            VaultPathSource::RecentFile => tr("zuletzt geöffnet"),
+            VaultPathSource::DroppedFile => tr("ins Fenster gezogen"),
+        }
+    }
+}
+
+/// Sichtbarkeit des Werts direkt nach dem Hinzufügen eines Eintrags
+#[derive(Clone, Copy, PartialEq)]
+enum NewEntryVisibility {
+    Masked,
+    RevealBriefly,
+    Revealed,
+}
+
+impl NewEntryVisibility {
+    fn label(&self) -> &'static str {
+        match self {
+            NewEntryVisibility::Masked => tr("Sofort verbergen"),
+            NewEntryVisibility::RevealBriefly => tr("Kurz anzeigen"),
+            NewEntryVisibility::Revealed => tr("Anzeigen"),
+        }
+    }
+}
+
+/// Erwartete Struktur einer JSON-Importdatei
+#[derive(Clone, Copy, PartialEq)]
+enum ImportShape {
+    Auto,
+    Vault,
+    Object,
+    KeyValueArray,
+}
+
+impl ImportShape {
+    fn label(&self) -> &'static str {
+        match self {
+            ImportShape::Auto => tr("Automatisch erkennen"),
+            ImportShape::Vault => "Vault-Export {\"items\": {...}}",
+            ImportShape::Object => "Objekt {\"key\": \"value\"}",
+            ImportShape::KeyValueArray => "Liste [{key, value}, ...]",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    fn label(&self) -> &'static str {
+        match self {
+            Theme::Dark => tr("Dunkel"),
+            Theme::Light => tr("Hell"),
+        }
+    }
+
+    fn visuals(&self) -> egui::Visuals {
+        match self {
+            Theme::Dark => egui::Visuals::dark(),
+            Theme::Light => egui::Visuals::light(),
+        }
+    }
+}
+
+/// Wo Toasts erscheinen; neuere stehen am Rand, ältere rücken davon weg
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+enum ToastPosition {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl ToastPosition {
+    const ALL: [ToastPosition; 5] = [
+        ToastPosition::TopLeft,
+        ToastPosition::TopCenter,
+        ToastPosition::TopRight,
+        ToastPosition::BottomLeft,
+        ToastPosition::BottomRight,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            ToastPosition::TopLeft => tr("Oben links"),
+            ToastPosition::TopCenter => tr("Oben mittig"),
+            ToastPosition::TopRight => tr("Oben rechts"),
+            ToastPosition::BottomLeft => tr("Unten links"),
+            ToastPosition::BottomRight => tr("Unten rechts"),
+        }
+    }
+
+    /// Anker eines Toasts, der `offset` vom Rand weggerückt ist
+    fn anchor(&self, offset: f32) -> (egui::Align2, egui::Vec2) {
+        let top = TOAST_TOP_MARGIN + offset;
+        let bottom = -16.0 - offset;
+        match self {
+            ToastPosition::TopLeft => (egui::Align2::LEFT_TOP, egui::vec2(16.0, top)),
+            ToastPosition::TopCenter => (egui::Align2::CENTER_TOP, egui::vec2(0.0, top)),
+            ToastPosition::TopRight => (egui::Align2::RIGHT_TOP, egui::vec2(-16.0, top)),
+            ToastPosition::BottomLeft => (egui::Align2::LEFT_BOTTOM, egui::vec2(16.0, bottom)),
+            ToastPosition::BottomRight => (egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, bottom)),
+        }
+    }
+}
+
+/// Inhalt von `SETTINGS_FILE`; unbekannte oder fehlende Felder fallen auf Standardwerte.
+/// Die Datei ist unverschlüsselt, Passwort und Schlüssel gehören nie hierher.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+struct Settings {
+    theme: Theme,
+    toast_position: ToastPosition,
+    /// Faktor auf die Anzeigedauer aller Toasts
+    toast_duration_factor: f64,
+    /// `None` folgt der Systemsprache
+    language: Option<Language>,
+    /// 0 = nie automatisch leeren
+    clipboard_clear_seconds: f64,
+    /// Leer = kein Tastenkürzel, siehe `parse_shortcut`
+    panic_shortcut: String,
+    panic_minimize: bool,
+    reveal_on_hover: bool,
+    reuse_warnings: bool,
+    /// Aus, weil manche Werte ihre Leerzeichen am Ende wirklich brauchen
+    auto_trim_values: bool,
+    /// Aus, weil die zuletzt verwendeten Schlüsselnamen dafür unverschlüsselt hier landen
+    key_suggestions: bool,
+    recent_key_names: Vec<String>,
+    backup_count: usize,
+    /// Ordner für zusätzliche Kopien mit Zeitstempel, z.B. ein synchronisierter Ordner
+    backup_dir: Option<PathBuf>,
+    backup_dir_keep: usize,
+    /// 0 = bei jedem Speichern
+    backup_dir_interval_minutes: u32,
+    history_limit: usize,
+    /// Argon2-Parameter für neue Vaults und für das Neuverschlüsseln
+    kdf: KdfParams,
+    /// Gilt beim Anlegen eines Vaults und beim Passwortwechsel, nicht beim Entsperren
+    policy: PasswordPolicy,
+    auto_save_enabled: bool,
+    auto_save_interval: f64,
+    undo_depth: usize,
+    /// Zuletzt geöffnete Vaults, neuester zuerst; die Pfade sind nicht geheim
+    recent_files: Vec<PathBuf>,
+    /// Fehlversuche in Folge; gespeichert, damit ein Neustart die Wartezeit nicht aufhebt
+    failed_logins: u32,
+    last_failed_login: Option<SystemTime>,
+    window: Option<WindowGeometry>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            toast_position: ToastPosition::default(),
+            toast_duration_factor: 1.0,
+            language: None,
+            clipboard_clear_seconds: DEFAULT_CLIPBOARD_CLEAR_SECONDS,
+            panic_shortcut: DEFAULT_PANIC_SHORTCUT.to_string(),
+            panic_minimize: false,
+            reveal_on_hover: true,
+            reuse_warnings: true,
+            auto_trim_values: false,
+            key_suggestions: false,
+            recent_key_names: Vec::new(),
+            backup_count: DEFAULT_BACKUP_COUNT,
+            backup_dir: None,
+            backup_dir_keep: DEFAULT_BACKUP_DIR_KEEP,
+            backup_dir_interval_minutes: 0,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            kdf: KdfParams::default(),
+            policy: PasswordPolicy::default(),
+            auto_save_enabled: false,
+            auto_save_interval: DEFAULT_AUTO_SAVE_SECONDS,
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            recent_files: Vec::new(),
+            failed_logins: 0,
+            last_failed_login: None,
+            window: None,
+        }
+    }
+}
+
+/// Fenstergröße und -position beim Beenden, dazu die damalige Monitorgröße
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+struct WindowGeometry {
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    #[serde(default)]
+    monitor: Option<[f32; 2]>,
+}
+
+impl WindowGeometry {
+    /// Holt das Fenster vollständig auf einen Monitor der Größe `monitor`,
+    /// ohne die Mindestgröße zu unterschreiten
+    fn clamped_to(self, monitor: egui::Vec2) -> Self {
+        let width = self.width.min(monitor.x).max(MIN_WINDOW_SIZE[0]);
+        let height = self.height.min(monitor.y).max(MIN_WINDOW_SIZE[1]);
+        Self {
+            x: self.x.clamp(0.0, (monitor.x - width).max(0.0)),
+            y: self.y.clamp(0.0, (monitor.y - height).max(0.0)),
+            width,
+            height,
+            monitor: Some([monitor.x, monitor.y]),
+        }
+    }
+}
+
+/// Klartext-Formate für Import und Export
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PlaintextFormat {
+    Json,
+    Env,
+    Csv,
+}
+
+impl PlaintextFormat {
+    fn label(&self) -> &'static str {
+        match self {
+            PlaintextFormat::Json => "JSON",
+            PlaintextFormat::Env => ".env",
+            PlaintextFormat::Csv => "CSV",
+        }
+    }
+
+    fn file_name(&self) -> &'static str {
+        match self {
+            PlaintextFormat::Json => "vault-export.json",
+            PlaintextFormat::Env => ".env",
+            PlaintextFormat::Csv => "vault-export.csv",
+        }
+    }
+}
+
+/// Wofür die laufende Schlüsselableitung gestartet wurde
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum KeyJobKind {
+    Unlock,
+    Create,
+    /// Von außen geänderte Datei übernehmen
+    Reload,
+    /// Von außen geänderte Datei mit den eigenen Änderungen zusammenführen
+    Merge,
+    /// Mit neuen Argon2-Parametern neu verschlüsseln
+    Rekey,
+    /// Aktuelles Passwort prüfen und mit dem neuen speichern
+    ChangePassword,
+}
+
+/// Argon2-Ableitung samt Ver- oder Entschlüsselung auf einem eigenen Thread, damit
+/// die Oberfläche nicht einfriert. Der Thread arbeitet auf einer Kopie des Vaults und
+/// schickt sie mit dem Ergebnis zurück; abgeholt wird in `poll_key_job`.
+struct KeyJob {
+    kind: KeyJobKind,
+    receiver: mpsc::Receiver<(Vault, Result<Vec<u8>, String>)>,
+}
+
+/// Was eine ins Fenster gezogene Datei auslöst
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum DroppedFile {
+    Vault,
+    Import(PlaintextFormat),
+}
+
+impl DroppedFile {
+    /// Nach Dateiendung; `.env` zählt auch ohne Namen davor (Rust sieht dort keine Endung)
+    fn classify(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        let extension = name.rsplit_once('.').map_or("", |(_, extension)| extension);
+        match extension {
+            "enc" | "vault" => Some(DroppedFile::Vault),
+            "json" => Some(DroppedFile::Import(PlaintextFormat::Json)),
+            "env" => Some(DroppedFile::Import(PlaintextFormat::Env)),
+            "csv" => Some(DroppedFile::Import(PlaintextFormat::Csv)),
+            _ => None,
+        }
+    }
+}
+
+/// Umgang mit importierten Schlüsseln, die es schon gibt
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum ImportCollision {
+    Skip,
+    Overwrite,
+    KeepBoth,
+}
+
 struct ToastMessage {
+    // Stable window id, so egui does not mix up sizes when older toasts expire
+    id: u64,
     text: String,
     color: egui::Color32,
     start_time: f64,
     duration: f64,
     fade_progress: f32,
+    // Rendered height of the last frame, used to stack the toasts above it
+    height: f32,
+    // Animated distance from the edge the toasts are anchored to
+    offset: f32,
 }
 
 impl ToastMessage {
-    fn new(text: String, color: egui::Color32, duration: f64, current_time: f64) -> Self {
+    fn new(id: u64, text: String, color: egui::Color32, duration: f64, current_time: f64) -> Self {
         Self {
+            id,
             text,
             color,
             start_time: current_time,
             duration,
             fade_progress: 0.0,
+            height: 40.0,
+            offset: 0.0,
         }
     }
 
@@ -72,10 +790,67 @@ impl ToastMessage {
     }
 }
 
+/// Zustand eines geöffneten Vaults. Der aktive Tab lebt in den Feldern von `App`,
+/// die übrigen warten hier, bis `switch_tab` sie wieder einsetzt.
+struct VaultSession {
+    screen: Screen,
+    vault: Vault,
+    vault_path_source: VaultPathSource,
+    password: String,
+    key_file_path: Option<PathBuf>,
+    key_file_required: bool,
+    data: AppData,
+    dirty: bool,
+    undo_stack: Vec<AppData>,
+    redo_stack: Vec<AppData>,
+    last_value_edit: Option<(String, f64)>,
+    validation_errors: HashMap<String, String>,
+    revealed_items: HashSet<String>,
+    reveal_deadlines: HashMap<String, f64>,
+    expanded_values: HashSet<String>,
+    selected_keys: HashSet<String>,
+    list_scroll_offset: f32,
+    last_focused_key: Option<String>,
+}
+
+impl VaultSession {
+    /// Verwirft alles Entschlüsselte; der Tab zeigt danach die Passworteingabe
+    fn lock(&mut self) {
+        self.screen = Screen::PasswordInput;
+        self.password.clear();
+        self.vault.key = None;
+        self.vault.key_file = None;
+        self.data = AppData::default();
+        self.dirty = false;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_value_edit = None;
+        self.validation_errors.clear();
+        self.revealed_items.clear();
+        self.reveal_deadlines.clear();
+        self.expanded_values.clear();
+        self.selected_keys.clear();
+    }
+}
+
 struct App {
+    // Open vaults in tab order; `None` marks the active one, whose state is below
+    tabs: Vec<Option<VaultSession>>,
+    // Closing the active tab waits for save/discard because it has unsaved changes
+    tab_close_prompt: bool,
     screen: Screen,
+    vault: Vault,
+    vault_path_source: VaultPathSource,
+    new_vault_cipher: CipherSuite,
     password: String,
     show_password: bool,
+    // zxcvbn score (0-4) of the password, only shown for new vaults
+    password_score: u8,
+    // Repeated password on the setup screen
+    password_repeat: String,
+
+    // Key derivation running in the background, see `KeyJob`
+    key_job: Option<KeyJob>,
 
     // Animation states
     login_shake_time: f64,
@@ -87,746 +862,9971 @@ struct App {
     // UI & UX State
     error_message: String,
     toast_messages: Vec<ToastMessage>,
+    // Toasts waiting for a free slot, shown once older ones expire
+    toast_queue: VecDeque<ToastMessage>,
+    next_toast_id: u64,
     search_query: String,
+    search_scope: SearchScope,
+    // Lowercased query, applied once typing pauses until `search_debounce_until`
+    search_query_lower: String,
+    search_query_cached: String,
+    search_debounce_until: f64,
+    // Regex mode: the pattern is compiled once per cached query, an invalid one
+    // keeps its error and the search falls back to plain text
+    search_regex: bool,
+    search_regex_compiled: Option<(String, Result<regex::Regex, String>)>,
+    // Fuzzy mode: subsequence matches ranked by score instead of `sort_mode`
+    search_fuzzy: bool,
+    // (key, lowercased key), rebuilt when `data.revision` changes
+    key_cache: Vec<(String, String)>,
+    key_cache_revision: u64,
+    // Filter result and the inputs it was computed from
+    filtered_keys: Vec<String>,
+    filtered_for: Option<FilterInputs>,
     delete_candidate: Option<String>,
+    delete_confirm_text: String,
+    // Key from the add form that already exists and awaits overwrite confirmation
+    overwrite_candidate: Option<String>,
+    // Inline rename: key being edited, new name, and whether the field still needs focus
+    rename_key: Option<String>,
+    rename_buffer: String,
+    rename_focus_pending: bool,
+    // Row the list should scroll to on the next frame
+    scroll_to_key: Option<String>,
+    // Where the user was in the list, restored after it is rebuilt
+    list_scroll_offset: f32,
+    last_focused_key: Option<String>,
+    // Offset the list jumps back to on the next frame if no row is targeted
+    list_scroll_restore: Option<f32>,
+    show_notes: bool,
+    // Compact key/value table instead of the cards
+    grid_view: bool,
+    // Date typed into a row's ⏰ menu
+    expiry_input: String,
+    // Vault files shown as chips on the login screen, most recent first
+    recent_files: Vec<PathBuf>,
+    // Consecutive wrong passwords and when the last one happened (wall clock, persisted)
+    failed_logins: u32,
+    last_failed_login: Option<SystemTime>,
+    // Last known window geometry, and a restored one still to check against the monitor
+    window_geometry: Option<WindowGeometry>,
+    window_restore: Option<WindowGeometry>,
+    // View mode: values can be looked at and copied, but nothing can be changed
+    read_only: bool,
+    sort_mode: SortMode,
+    // Tags that every listed entry must carry (ANDed with the search)
+    tag_filter: BTreeSet<String>,
+    // Row whose tag input is open, and the tag being typed
+    tag_input_key: Option<String>,
+    tag_input: String,
+    // Single-line text values the user switched to multiline editing
+    expanded_values: HashSet<String>,
+
+    // Change master password dialog
+    password_change_open: bool,
+    password_change_current: String,
+    password_change_new: String,
+    password_change_repeat: String,
+    password_change_error: String,
+    // Key file for the vault after the password change; None switches it off
+    password_change_key_file: Option<PathBuf>,
+    // New password and key file of a running change, applied once the key job is done
+    password_change_pending: Option<(String, Option<PathBuf>)>,
+    // Exporting the selected entries into a separate vault with its own password
+    share_export_open: bool,
+    share_password: String,
+    share_password_repeat: String,
+    share_error: String,
+    // Optional second factor: chosen key file, and whether the vault header demands one
+    key_file_path: Option<PathBuf>,
+    key_file_required: bool,
+
+    // Recovery of undecodable vault contents
+    recovery_json: Option<String>,
+    recovery_error: String,
+
+    // JSON import dialog
+    import_dialog_open: bool,
+    import_path: String,
+    import_shape: ImportShape,
+    import_key_field: String,
+    import_value_field: String,
+    import_error: String,
+    // Parsed entries waiting for a decision on key collisions
+    pending_import: Option<Vec<(String, serde_json::Value)>>,
+    // Entries read from a dropped file, merged only after confirmation
+    dropped_import: Option<(PathBuf, Vec<(String, serde_json::Value)>)>,
+    csv_has_header: bool,
+    // Password manager CSV waiting for its column mapping
+    login_import: Option<(logins::CsvTable, logins::ColumnMap)>,
+    // Notes of imported entries, attached when the import is merged
+    pending_import_notes: HashMap<String, String>,
+
+    // Plaintext export, confirmed first because it writes secrets unencrypted
+    export_confirm: Option<PlaintextFormat>,
 
     // Add form
     new_key: String,
+    // Inline hint under the key field, e.g. for a key that is empty after trimming
+    new_key_error: String,
     new_value: String,
-    add_form_expanded: bool,
+    // Settings of the 🎲 password generator, shared by add form and rows
+    generator: PasswordGenerator,
+    new_value_type: ValueType,
+    new_value_multiline: bool,
+    new_number: f64,
+    new_bool: bool,
+    new_entry_visibility: NewEntryVisibility,
+
+    // Clipboard auto-clear: copied text and the time it gets wiped
+    pending_clipboard_clear: Option<(String, f64)>,
+    clipboard_clear_seconds: f64,
+    // Panic lock: shortcut text and whether it also minimizes
+    panic_shortcut: String,
+    panic_minimize: bool,
+
+    settings_open: bool,
+    help_open: bool,
+    // About dialog with the format version and header of the open file, read once on opening
+    about_open: bool,
+    about_file: Option<(u32, FileHeader)>,
+    // Read-only mirror of the decrypted JSON; values are redacted unless switched off
+    json_source_open: bool,
+    json_source_redact: bool,
+    // Set by Ctrl+F, consumed when the search field is drawn
+    search_focus_pending: bool,
+    // Same for the add form's key field, set from the command palette
+    new_key_focus_pending: bool,
+    // Ctrl+P palette: query and highlighted result
+    palette_open: bool,
+    palette_query: String,
+    palette_selected: usize,
+    // Row jumped to from the palette: its value field still needs focus, and
+    // when the highlight started
+    value_focus_pending: Option<String>,
+    jump_highlight: Option<(String, f64)>,
+    theme: Theme,
+    // Where toasts appear and how long they stay, relative to each call site's duration
+    toast_position: ToastPosition,
+    toast_duration_factor: f64,
+    // Chosen UI language, `None` follows the system locale
+    language: Option<Language>,
+    backup_count: usize,
+    // Timestamped copies in a separate folder, and when each vault was last copied there
+    backup_dir: Option<PathBuf>,
+    backup_dir_keep: usize,
+    backup_dir_interval_minutes: u32,
+    last_backup_dir_copy: HashMap<PathBuf, SystemTime>,
+    // Previous values kept per entry, and whether a JSON export includes them
+    history_limit: usize,
+    export_history: bool,
+    // Argon2 params for new vaults and rekeying; the custom editor stays open
+    // after picking "custom" even while the values still match a preset
+    kdf_params: KdfParams,
+    kdf_custom_open: bool,
+    // Rules for new and changed passwords; `--policy` overrides the settings for this run
+    password_policy: PasswordPolicy,
+    policy_override: Option<PasswordPolicy>,
+
+    // Periodic auto-save while there are unsaved changes
+    auto_save_enabled: bool,
+    auto_save_interval: f64,
+    last_auto_save: f64,
+    // Save button or Ctrl+S, run at the end of the editor frame once the focused
+    // field has applied this frame's input; holds `ignore_rules`
+    save_requested: Option<bool>,
+
+    // Unsaved changes in `data`, and the state last pushed to the window title
+    dirty: bool,
+    title_shows_dirty: bool,
+    // Window close was requested with unsaved changes; `close_confirmed` lets it through
+    close_confirm_open: bool,
+    close_confirmed: bool,
+
+    // Undo/redo: snapshots of `data` taken before each change
+    undo_stack: Vec<AppData>,
+    redo_stack: Vec<AppData>,
+    undo_depth: usize,
+    // Key and time of the last value edit, used to merge rapid keystrokes
+    last_value_edit: Option<(String, f64)>,
+
+    // Rule violations per key, refreshed whenever a checked value changes
+    validation_errors: HashMap<String, String>,
+    // Regex typed into a row's ✔ menu, and whether saving waits for "save anyway"
+    rule_pattern: String,
+    validation_save_prompt: bool,
+
+    // Vault file rewritten by another process: its new modification time while the
+    // prompt is open, the one dismissed with "later", and when we last looked
+    external_change: Option<SystemTime>,
+    external_change_dismissed: Option<SystemTime>,
+    last_disk_check: f64,
+
+    // `--serve`: loopback address and bearer token from the command line, and
+    // the server itself, which only runs while a vault is unlocked
+    serve_config: Option<(std::net::SocketAddr, String)>,
+    server: Option<serve::Server>,
+    served_revision: u64,
+
+    // Value masking; `reveal_on_hover` shows a value while its row is hovered
+    revealed_items: HashSet<String>,
+    reveal_on_hover: bool,
+    // Values shared by several keys: key -> the other keys, rebuilt when
+    // `data.revision` changes
+    reuse_warnings: bool,
+    reused_values: HashMap<String, Vec<String>>,
+    reused_revision: u64,
+    // Strip surrounding whitespace from pasted values and new entries
+    auto_trim_values: bool,
+    // Key suggestions on the add form: recently used names, and the value whose
+    // token-based suggestion was dismissed
+    key_suggestions: bool,
+    recent_key_names: Vec<String>,
+    key_suggestion_dismissed: Option<String>,
+    reveal_deadlines: HashMap<String, f64>,
 
     // Item animations
     item_hover_states: HashMap<String, f32>,
     item_delete_animations: HashMap<String, f32>,
+    // Rows ticked for bulk actions, and whether the bulk delete confirmation is open
+    selected_keys: HashSet<String>,
+    bulk_delete_open: bool,
+    // "Clear all" from the settings: typed confirmation goes into `delete_confirm_text`,
+    // and an encrypted backup is written first unless unticked
+    clear_all_open: bool,
+    clear_all_backup: bool,
 }
 
 impl Default for App {
     fn default() -> Self {
         Self {
             screen: Screen::PasswordInput,
+            tabs: vec![None],
+            tab_close_prompt: false,
+            vault: Vault::new(PathBuf::from(ENCRYPTED_FILE)),
+            vault_path_source: VaultPathSource::WorkingDirectory,
+            new_vault_cipher: CipherSuite::default(),
             password: String::new(),
             show_password: false,
+            password_score: 0,
+            password_repeat: String::new(),
+            key_job: None,
             login_shake_time: 0.0,
             login_button_hover: 0.0,
             screen_transition_progress: 0.0,
             data: AppData::default(),
             error_message: String::new(),
             toast_messages: Vec::new(),
+            toast_queue: VecDeque::new(),
+            next_toast_id: 0,
             search_query: String::new(),
+            search_scope: SearchScope::Both,
+            search_regex: false,
+            search_regex_compiled: None,
+            search_fuzzy: false,
+            search_query_lower: String::new(),
+            search_query_cached: String::new(),
+            search_debounce_until: 0.0,
+            key_cache: Vec::new(),
+            key_cache_revision: 0,
+            filtered_keys: Vec::new(),
+            filtered_for: None,
             delete_candidate: None,
+            overwrite_candidate: None,
+            delete_confirm_text: String::new(),
+            rename_key: None,
+            rename_buffer: String::new(),
+            rename_focus_pending: false,
+            scroll_to_key: None,
+            list_scroll_offset: 0.0,
+            last_focused_key: None,
+            list_scroll_restore: None,
+            show_notes: false,
+            grid_view: false,
+            expiry_input: String::new(),
+            read_only: false,
+            recent_files: Vec::new(),
+            failed_logins: 0,
+            last_failed_login: None,
+            window_geometry: None,
+            window_restore: None,
+            sort_mode: SortMode::KeyAscending,
+            tag_filter: BTreeSet::new(),
+            tag_input_key: None,
+            tag_input: String::new(),
+            expanded_values: HashSet::new(),
+            password_change_open: false,
+            password_change_current: String::new(),
+            password_change_new: String::new(),
+            password_change_repeat: String::new(),
+            password_change_error: String::new(),
+            password_change_key_file: None,
+            password_change_pending: None,
+            share_export_open: false,
+            share_password: String::new(),
+            share_password_repeat: String::new(),
+            share_error: String::new(),
+            key_file_path: None,
+            key_file_required: false,
+            recovery_json: None,
+            recovery_error: String::new(),
+            import_dialog_open: false,
+            pending_import: None,
+            dropped_import: None,
+            csv_has_header: true,
+            login_import: None,
+            pending_import_notes: HashMap::new(),
+            export_confirm: None,
+            import_path: String::new(),
+            import_shape: ImportShape::Auto,
+            import_key_field: "key".to_string(),
+            import_value_field: "value".to_string(),
+            import_error: String::new(),
             new_key: String::new(),
+            new_key_error: String::new(),
             new_value: String::new(),
-            add_form_expanded: false,
+            generator: PasswordGenerator::default(),
+            new_value_type: ValueType::Text,
+            new_value_multiline: false,
+            new_number: 0.0,
+            new_bool: false,
+            new_entry_visibility: NewEntryVisibility::RevealBriefly,
+            pending_clipboard_clear: None,
+            clipboard_clear_seconds: DEFAULT_CLIPBOARD_CLEAR_SECONDS,
+            panic_shortcut: DEFAULT_PANIC_SHORTCUT.to_string(),
+            panic_minimize: false,
+            settings_open: false,
+            json_source_open: false,
+            json_source_redact: true,
+            help_open: false,
+            about_open: false,
+            about_file: None,
+            search_focus_pending: false,
+            new_key_focus_pending: false,
+            palette_open: false,
+            palette_query: String::new(),
+            palette_selected: 0,
+            value_focus_pending: None,
+            jump_highlight: None,
+            theme: Theme::default(),
+            toast_position: ToastPosition::default(),
+            toast_duration_factor: 1.0,
+            language: None,
+            backup_count: DEFAULT_BACKUP_COUNT,
+            backup_dir: None,
+            backup_dir_keep: DEFAULT_BACKUP_DIR_KEEP,
+            backup_dir_interval_minutes: 0,
+            last_backup_dir_copy: HashMap::new(),
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            export_history: false,
+            kdf_params: KdfParams::default(),
+            kdf_custom_open: false,
+            password_policy: PasswordPolicy::default(),
+            policy_override: None,
+            auto_save_enabled: false,
+            auto_save_interval: DEFAULT_AUTO_SAVE_SECONDS,
+            last_auto_save: 0.0,
+            save_requested: None,
+            dirty: false,
+            title_shows_dirty: false,
+            close_confirm_open: false,
+            close_confirmed: false,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            undo_depth: DEFAULT_UNDO_DEPTH,
+            last_value_edit: None,
+            validation_errors: HashMap::new(),
+            rule_pattern: String::new(),
+            validation_save_prompt: false,
+            external_change: None,
+            external_change_dismissed: None,
+            last_disk_check: 0.0,
+            serve_config: None,
+            server: None,
+            served_revision: 0,
+            revealed_items: HashSet::new(),
+            reveal_on_hover: true,
+            reuse_warnings: true,
+            reused_values: HashMap::new(),
+            reused_revision: 0,
+            auto_trim_values: false,
+            key_suggestions: false,
+            recent_key_names: Vec::new(),
+            key_suggestion_dismissed: None,
+            reveal_deadlines: HashMap::new(),
             item_hover_states: HashMap::new(),
             item_delete_animations: HashMap::new(),
+            selected_keys: HashSet::new(),
+            bulk_delete_open: false,
+            clear_all_open: false,
+            clear_all_backup: true,
         }
     }
 }
 
 impl App {
-    fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
-        let argon2 = Argon2::default();
-        let salt_string = SaltString::encode_b64(salt).map_err(|e| e.to_string())?;
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt_string)
-            .map_err(|e| e.to_string())?;
-        let hash_binding = password_hash.hash.ok_or("No hash".to_string())?;
-        let hash_bytes = hash_binding.as_bytes();
-        if hash_bytes.len() < 32 {
-            return Err("Hash too short".into());
-        }
-        let mut key = [0u8; 32];
-        key.copy_from_slice(&hash_bytes[..32]);
-        Ok(key)
+    fn set_vault_path(&mut self, vault_path: PathBuf, source: VaultPathSource) {
+        self.vault = Vault::new(vault_path);
+        self.vault_path_source = source;
+        self.key_file_required = self.vault.requires_key_file();
+        self.screen = if self.vault.exists() {
+            Screen::PasswordInput
+        } else {
+            Screen::Setup
+        };
     }
 
-    fn encrypt_data(&self) -> Result<(), String> {
-        let json_data = serde_json::to_string(&self.data).map_err(|e| e.to_string())?;
-        let salt = if fs::metadata(SALT_FILE).is_ok() {
-            fs::read(SALT_FILE).map_err(|e| e.to_string())?
-        } else {
-            let mut salt = [0u8; 16];
-            OsRng.fill_bytes(&mut salt);
-            fs::write(SALT_FILE, &salt).map_err(|e| e.to_string())?;
-            salt.to_vec()
-        };
-        let key = Self::derive_key(&self.password, &salt)?;
-        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
-        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
-        let ciphertext = cipher
-            .encrypt(&nonce, json_data.as_bytes())
-            .map_err(|e| e.to_string())?;
-        let mut encrypted_data = nonce.to_vec();
-        encrypted_data.extend_from_slice(&ciphertext);
-        let encoded = base64::encode(&encrypted_data);
-        fs::write(ENCRYPTED_FILE, encoded).map_err(|e| e.to_string())?;
+    /// Wechselt auf eine im Dateidialog gewählte, bestehende Vault-Datei.
+    /// Ohne Salt (im Header oder daneben) lässt sie sich nicht entschlüsseln.
+    fn open_vault_file(&mut self, path: PathBuf, source: VaultPathSource) -> Result<(), String> {
+        let vault = Vault::new(path);
+        let (header, _) = vault.read_file()?;
+        vault.read_salt(&header)?;
+        self.key_file_required = header.key_file;
+        self.vault = vault;
+        self.vault_path_source = source;
+        self.screen = Screen::PasswordInput;
         Ok(())
     }
 
-    fn decrypt_data(&mut self) -> Result<(), String> {
-        if !fs::metadata(ENCRYPTED_FILE).is_ok() {
-            self.data = AppData::default();
-            self.encrypt_data()?;
-            return Ok(());
+    fn encrypt_data(&mut self) -> Result<(), String> {
+        // Der Hintergrund-Job schreibt gerade selbst über dieselbe .tmp-Datei
+        if self.key_job.is_some() {
+            return Err(tr("Schlüsselableitung läuft noch").to_string());
         }
-        let encoded_data = fs::read_to_string(ENCRYPTED_FILE).map_err(|e| e.to_string())?;
-        let encrypted_data = base64::decode(encoded_data.trim()).map_err(|e| e.to_string())?;
-        if encrypted_data.len() < 12 {
-            return Err("Beschädigte Datendatei".into());
-        }
-        let salt = fs::read(SALT_FILE).map_err(|e| e.to_string())?;
-        let key = Self::derive_key(&self.password, &salt)?;
-        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key));
-        let (nonce_bytes, ciphertext) = encrypted_data.split_at(12);
-        let nonce = Nonce::from_slice(nonce_bytes);
-        let plaintext = cipher
-            .decrypt(nonce, ciphertext)
-            .map_err(|_| "Falsches Passwort".to_string())?;
-        let json_str = String::from_utf8(plaintext).map_err(|e| e.to_string())?;
-        self.data = serde_json::from_str(&json_str).map_err(|e| e.to_string())?;
+        if self.check_external_change() {
+            return Err(tr("Die Datei wurde inzwischen von außen geändert").to_string());
+        }
+        self.finish_pending_deletes();
+        self.data.trim_history(self.history_limit);
+        self.vault
+            .save(&self.password, &self.data, self.backup_count)?;
+        self.dirty = false;
+        self.copy_to_backup_dir(SystemTime::now());
         Ok(())
     }
 
-    fn try_login(&mut self, current_time: f64) {
-        match self.decrypt_data() {
+    /// Warnt, wenn Gruppe oder andere die Vault-Dateien lesen dürfen, und schränkt
+    /// sie auf den Besitzer ein; jedes Speichern schreibt sie ohnehin mit 0600
+    fn tighten_vault_permissions(&mut self, current_time: f64) {
+        if self.vault.loosely_permitted_files().is_empty() {
+            return;
+        }
+        let text = match self.vault.restrict_permissions() {
+            Ok(_) => "Vault-Datei war für andere lesbar, jetzt nur noch für dich (0600)",
+            Err(_) => "Vault-Datei ist für andere lesbar, Rechte ließen sich nicht ändern",
+        };
+        self.add_toast(
+            text,
+            egui::Color32::from_rgb(255, 193, 7),
+            5.0,
+            current_time,
+        );
+    }
+
+    /// Legt nach dem Speichern eine Kopie im Backup-Ordner ab, sofern einer gewählt
+    /// ist und die letzte Kopie dieses Vaults lange genug her ist. Scheitert das,
+    /// bleibt das Speichern gültig und der Fehler wird nur angezeigt.
+    fn copy_to_backup_dir(&mut self, now: SystemTime) {
+        let Some(dir) = &self.backup_dir else {
+            return;
+        };
+        let interval = Duration::from_secs(u64::from(self.backup_dir_interval_minutes) * 60);
+        let due = self
+            .last_backup_dir_copy
+            .get(&self.vault.path)
+            .and_then(|last| now.duration_since(*last).ok())
+            .is_none_or(|since| since >= interval);
+        if !due {
+            return;
+        }
+        match self
+            .vault
+            .copy_to_backup_dir(dir, self.backup_dir_keep, now)
+        {
             Ok(_) => {
-                self.screen = Screen::Editor;
-                self.error_message.clear();
-                self.screen_transition_progress = 0.0;
-                self.add_toast(
-                    "Erfolgreich entsperrt",
-                    egui::Color32::from_rgb(46, 160, 67),
-                    2.0,
-                    current_time,
-                );
+                self.last_backup_dir_copy
+                    .insert(self.vault.path.clone(), now);
             }
-            Err(e) => {
-                self.error_message = e;
-                self.password.clear();
-                self.login_shake_time = current_time;
+            Err(e) => self.error_message = trf("❌ Backup-Ordner: {}", &[&e]),
+        }
+    }
+
+    /// Führt einen Treffer der Befehlspalette aus
+    fn run_palette_item(&mut self, item: PaletteItem, current_time: f64) {
+        self.palette_open = false;
+        match item {
+            PaletteItem::Key(key) => self.jump_to_entry(key, current_time),
+            PaletteItem::Command(PaletteCommand::AddEntry) => self.new_key_focus_pending = true,
+            PaletteItem::Command(PaletteCommand::Save) => self.save(current_time, false),
+            PaletteItem::Command(PaletteCommand::Export) => {
+                self.export_confirm = Some(PlaintextFormat::Json)
             }
+            PaletteItem::Command(PaletteCommand::Lock) => self.lock(current_time),
+            PaletteItem::Command(PaletteCommand::Settings) => self.settings_open = true,
+            PaletteItem::Command(PaletteCommand::JsonSource) => self.json_source_open = true,
+            PaletteItem::Command(PaletteCommand::Shortcuts) => self.help_open = true,
+            PaletteItem::Command(PaletteCommand::About) => self.open_about(),
         }
     }
 
-    fn add_toast(&mut self, text: &str, color: egui::Color32, duration: f64, current_time: f64) {
-        self.toast_messages.push(ToastMessage::new(
-            text.to_string(),
-            color,
-            duration,
-            current_time,
-        ));
+    /// Holt nach Speichern, Import, Sortierwechsel oder Tabwechsel die zuletzt
+    /// bearbeitete Zeile zurück in den sichtbaren Bereich; gibt es sie nicht mehr,
+    /// springt die Liste zur gemerkten Position
+    fn restore_list_position(&mut self) {
+        match &self.last_focused_key {
+            Some(key) if self.data.items.contains_key(key) => {
+                self.scroll_to_key = Some(key.clone());
+            }
+            _ => self.list_scroll_restore = Some(self.list_scroll_offset),
+        }
     }
 
-    fn add_new_entry(&mut self, current_time: f64) {
-        if !self.new_key.trim().is_empty() {
-            self.data
-                .items
-                .insert(self.new_key.clone(), self.new_value.clone());
-            self.new_key.clear();
-            self.new_value.clear();
-            self.add_toast(
-                "Eintrag hinzugefügt",
-                egui::Color32::from_rgb(46, 160, 67),
-                2.0,
-                current_time,
-            );
+    /// Scrollt zur Zeile, hebt sie kurz hervor und setzt den Fokus in ihren Wert.
+    /// Blenden Suche oder Tag-Filter die Zeile aus, werden beide zurückgesetzt.
+    fn jump_to_entry(&mut self, key: String, current_time: f64) {
+        if !self.visible_keys(current_time).contains(&key) {
+            self.search_query.clear();
+            self.search_query_cached.clear();
+            self.search_query_lower.clear();
+            self.tag_filter.clear();
         }
+        self.scroll_to_key = Some(key.clone());
+        self.value_focus_pending = Some(key.clone());
+        self.jump_highlight = Some((key, current_time));
     }
 
-    fn update_animations(&mut self, ctx: &egui::Context, dt: f32) {
-        // Update login button hover animation
-        self.login_button_hover = (self.login_button_hover + dt * 8.0).min(1.0);
+    /// Öffnet die Rückfrage, falls ein anderer Prozess die Datei ersetzt hat
+    fn check_external_change(&mut self) -> bool {
+        self.external_change = self.vault.external_change();
+        self.external_change.is_some()
+    }
 
-        // Update screen transition
-        if matches!(self.screen, Screen::Editor) {
-            self.screen_transition_progress = (self.screen_transition_progress + dt * 4.0).min(1.0);
+    /// Leitet den Schlüssel mit `kdf_params` neu ab und verschlüsselt den Vault
+    /// damit neu. Solange der Job läuft, fängt das Overlay Klicks ab, kein Textfeld
+    /// hat den Fokus und Speichern, Rückgängig und Tastenkürzel ruhen; daher gilt
+    /// der gespeicherte Stand danach als aktuell.
+    fn start_rekey(&mut self) {
+        if self.read_only || self.key_job.is_some() || !matches!(self.screen, Screen::Editor) {
+            return;
         }
-
-        // Update item hover states
-        for (_, hover_state) in self.item_hover_states.iter_mut() {
-            *hover_state = (*hover_state - dt * 6.0).max(0.0);
+        if self.check_external_change() {
+            self.error_message =
+                format!("❌ {}", tr("Die Datei wurde inzwischen von außen geändert"));
+            return;
         }
-
-        // Update item delete animations
-        self.item_delete_animations.retain(|_, progress| {
-            *progress += dt * 8.0;
-            *progress < 1.0
+        self.finish_pending_deletes();
+        self.data.trim_history(self.history_limit);
+        let password = self.password.clone();
+        let data = self.data.clone();
+        let kdf = self.kdf_params;
+        let backup_count = self.backup_count;
+        self.start_key_job(KeyJobKind::Rekey, move |vault| {
+            vault
+                .rekey(&password, kdf, &data, backup_count)
+                .map(|_| Vec::new())
         });
+    }
 
-        // Update toast messages
-        let current_time = ctx.input(|i| i.time);
-        self.toast_messages
-            .retain_mut(|toast| toast.update(current_time));
-
-        ctx.request_repaint();
+    /// Liest die von außen geänderte Datei mit dem aktuellen Passwort neu ein
+    fn reload_from_disk(&mut self, kind: KeyJobKind) {
+        self.external_change = None;
+        let password = self.password.clone();
+        self.start_key_job(kind, move |vault| vault.decrypt(&password));
     }
 
-    fn show_toasts(&self, ctx: &egui::Context) {
-        for (i, toast) in self.toast_messages.iter().enumerate() {
-            let alpha = (toast.fade_progress * 255.0) as u8;
-            let bg_color = egui::Color32::from_rgba_unmultiplied(40, 40, 40, alpha);
-            let text_color = egui::Color32::from_rgba_unmultiplied(
-                toast.color.r(),
-                toast.color.g(),
-                toast.color.b(),
-                alpha,
+    /// Beim Neuladen bleibt der eigene Stand per Rückgängig erreichbar. Beim
+    /// Zusammenführen laufen abweichende Einträge durch den Import, Konflikte
+    /// werden also wie dort nachgefragt.
+    fn finish_reload(
+        &mut self,
+        kind: KeyJobKind,
+        plaintext: Result<Vec<u8>, String>,
+        current_time: f64,
+    ) {
+        let json_str =
+            plaintext.and_then(|plaintext| String::from_utf8(plaintext).map_err(|e| e.to_string()));
+        let result = json_str.and_then(|json_str| {
+            if kind == KeyJobKind::Merge {
+                let disk: AppData = serde_json::from_str(&json_str)
+                    .map_err(|e| format!("Daten nicht lesbar: {}", e))?;
+                let entries = disk
+                    .items
+                    .into_iter()
+                    .filter(|(key, value)| self.data.items.get(key) != Some(value))
+                    .map(|(key, value)| (key, serde_json::to_value(value).unwrap_or_default()))
+                    .collect();
+                // Der zusammengeführte Stand steht so noch nicht in der Datei
+                self.dirty = true;
+                self.start_import(entries, current_time);
+                Ok(())
+            } else {
+                self.finish_pending_deletes();
+                let snapshot = self.data.clone();
+                self.load_json(json_str)?;
+                self.undo_stack.push(snapshot);
+                self.redo_stack.clear();
+                self.dirty = false;
+                self.add_toast(
+                    "Neu geladen",
+                    egui::Color32::from_rgb(52, 144, 220),
+                    2.0,
+                    current_time,
+                );
+                Ok(())
+            }
+        });
+        if let Err(e) = result {
+            self.add_toast(
+                "Neu laden fehlgeschlagen",
+                egui::Color32::from_rgb(220, 53, 69),
+                3.0,
+                current_time,
             );
+            self.error_message = format!("❌ {}", e);
+        }
+    }
 
-            egui::Window::new(format!("toast_{}", i))
-                .title_bar(false)
-                .resizable(false)
-                .collapsible(false)
-                .anchor(
-                    egui::Align2::RIGHT_BOTTOM,
-                    egui::vec2(-16.0, -16.0 - (i as f32 * 50.0)),
-                )
-                .frame(
-                    egui::Frame::popup(&ctx.style())
-                        .fill(bg_color)
-                        .rounding(egui::Rounding::same(8.0))
-                        .shadow(egui::epaint::Shadow {
-                            offset: egui::vec2(0.0, 2.0),
-                            blur: 8.0,
-                            spread: 0.0,
-                            color: egui::Color32::from_black_alpha(50),
-                        }),
-                )
-                .show(ctx, |ui| {
-                    ui.horizontal(|ui| {
-                        ui.add_space(4.0);
-                        ui.colored_label(text_color, "●");
-                        ui.colored_label(text_color, &toast.text);
-                        ui.add_space(4.0);
-                    });
-                });
+    /// Lädt ein Backup mit dem eingegebenen Passwort; gespeichert wird erst auf Wunsch
+    fn restore_backup(&mut self, index: usize) -> Result<(), String> {
+        self.load_key_file()?;
+        let plaintext = self.vault.decrypt_backup(index, &self.password)?;
+        let json_str = String::from_utf8(plaintext).map_err(|e| e.to_string())?;
+        self.load_json(json_str)?;
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Liest die gewählte Schlüsseldatei erst, wenn sie gebraucht wird
+    fn load_key_file(&mut self) -> Result<(), String> {
+        self.vault.key_file = self
+            .key_file_path
+            .as_deref()
+            .map(read_key_file)
+            .transpose()?;
+        Ok(())
+    }
+
+    /// `--policy` hat Vorrang vor der gespeicherten Richtlinie
+    fn active_policy(&self) -> &PasswordPolicy {
+        self.policy_override
+            .as_ref()
+            .unwrap_or(&self.password_policy)
+    }
+
+    /// Prüft die Eingaben; Prüfen des alten und Ableiten des neuen Schlüssels
+    /// laufen als Hintergrund-Ableitung, übernommen wird in `finish_password_change`
+    fn change_password(&mut self) {
+        if self.key_job.is_some() {
+            return;
+        }
+        if self.password_change_new.is_empty() {
+            self.password_change_error = "Neues Passwort darf nicht leer sein".into();
+            return;
+        }
+        if let Err(e) = self.active_policy().check(&self.password_change_new) {
+            self.password_change_error = e;
+            return;
+        }
+        if self.password_change_new != self.password_change_repeat {
+            self.password_change_error = "Die neuen Passwörter stimmen nicht überein".into();
+            return;
+        }
+        let key_file = match self
+            .password_change_key_file
+            .as_deref()
+            .map(read_key_file)
+            .transpose()
+        {
+            Ok(key_file) => key_file,
+            Err(e) => {
+                self.password_change_error = e;
+                return;
+            }
+        };
+        let current_password = self.password_change_current.clone();
+        let new_password = self.password_change_new.clone();
+        self.finish_pending_deletes();
+        self.password_change_error.clear();
+        self.password_change_pending =
+            Some((new_password.clone(), self.password_change_key_file.clone()));
+        let data = self.data.clone();
+        let backup_count = self.backup_count;
+        self.start_key_job(KeyJobKind::ChangePassword, move |vault| {
+            if vault.verify_password(&current_password).is_err() {
+                return Err("Aktuelles Passwort ist falsch".into());
+            }
+            vault
+                .save_with_new_password(&new_password, key_file, &data, backup_count)
+                .map(|_| Vec::new())
+                .map_err(|e| trf("Speichern fehlgeschlagen: {}", &[&e]))
+        });
+    }
+
+    /// Übernimmt neues Passwort und Schlüsseldatei erst, wenn die Datei geschrieben
+    /// ist; auch dann, wenn der Dialog inzwischen geschlossen wurde
+    fn finish_password_change(&mut self, result: Result<Vec<u8>, String>, current_time: f64) {
+        let Some((new_password, key_file_path)) = self.password_change_pending.take() else {
+            return;
+        };
+        match result {
+            Ok(_) => {
+                self.password = new_password;
+                self.key_file_path = key_file_path;
+                self.key_file_required = self.vault.key_file.is_some();
+                self.dirty = false;
+                self.close_password_change_dialog();
+                self.add_toast(
+                    "Passwort geändert",
+                    egui::Color32::from_rgb(46, 160, 67),
+                    2.0,
+                    current_time,
+                );
+            }
+            Err(e) => self.password_change_error = e,
         }
     }
 
-    fn show_delete_confirm_dialog(&mut self, ctx: &egui::Context) {
-        if let Some(key) = self.delete_candidate.clone() {
-            egui::Window::new("Löschen bestätigen")
-                .collapsible(false)
-                .resizable(false)
-                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
-                .frame(
-                    egui::Frame::window(&ctx.style())
-                        .rounding(egui::Rounding::same(12.0))
-                        .shadow(egui::epaint::Shadow {
-                            offset: egui::vec2(0.0, 4.0),
-                            blur: 16.0,
-                            spread: 0.0,
-                            color: egui::Color32::from_black_alpha(100),
-                        }),
+    fn close_password_change_dialog(&mut self) {
+        self.password_change_open = false;
+        self.password_change_current.clear();
+        self.password_change_new.clear();
+        self.password_change_repeat.clear();
+        self.password_change_error.clear();
+    }
+
+    /// Übernimmt entschlüsseltes JSON. Ist es strukturell ungültig, bleibt der
+    /// Rohtext in `recovery_json` erhalten, damit er manuell repariert werden kann.
+    fn load_json(&mut self, json_str: String) -> Result<(), String> {
+        match serde_json::from_str(&json_str) {
+            Ok(data) => {
+                self.data = data;
+                self.data.fill_missing_meta();
+                self.selected_keys.clear();
+                self.item_delete_animations.clear();
+                self.recovery_json = None;
+                self.revalidate_all();
+                Ok(())
+            }
+            Err(e) => {
+                self.recovery_json = Some(json_str);
+                Err(format!("Daten nicht lesbar: {}", e))
+            }
+        }
+    }
+
+    /// Legt einen leeren Vault an, aber nur wenn beide Passworteingaben übereinstimmen
+    fn create_vault(&mut self, current_time: f64) {
+        if self.password != self.password_repeat {
+            self.error_message = "Die Passwörter stimmen nicht überein".into();
+            self.login_shake_time = current_time;
+            return;
+        }
+        if let Err(e) = self.active_policy().check(&self.password) {
+            self.error_message = e;
+            self.login_shake_time = current_time;
+            return;
+        }
+        if let Err(e) = self.load_key_file() {
+            self.error_message = e;
+            return;
+        }
+        self.data = AppData::default();
+        self.vault.header = FileHeader {
+            cipher: self.new_vault_cipher,
+            kdf: self.kdf_params,
+            ..FileHeader::current()
+        };
+        let password = self.password.clone();
+        let backup_count = self.backup_count;
+        self.start_key_job(KeyJobKind::Create, move |vault| {
+            vault
+                .save(&password, &AppData::default(), backup_count)
+                .map(|_| Vec::new())
+        });
+    }
+
+    fn try_login(&mut self) {
+        if self.login_wait(SystemTime::now()).is_some() {
+            return;
+        }
+        if let Err(e) = self.load_key_file() {
+            self.error_message = e;
+            return;
+        }
+        if !self.vault.exists() {
+            self.error_message = format!("Vault nicht gefunden: {}", self.vault.path.display());
+            return;
+        }
+        let password = self.password.clone();
+        self.start_key_job(KeyJobKind::Unlock, move |vault| vault.decrypt(&password));
+    }
+
+    fn start_key_job(
+        &mut self,
+        kind: KeyJobKind,
+        job: impl FnOnce(&mut Vault) -> Result<Vec<u8>, String> + Send + 'static,
+    ) {
+        let (sender, receiver) = mpsc::channel();
+        let mut vault = self.vault.clone();
+        std::thread::spawn(move || {
+            let result = job(&mut vault);
+            let _ = sender.send((vault, result));
+        });
+        // Befehle der Palette ließen sich sonst weiter per Tastatur auslösen
+        self.palette_open = false;
+        self.key_job = Some(KeyJob { kind, receiver });
+    }
+
+    /// Übernimmt das Ergebnis der Hintergrund-Ableitung, sobald es da ist
+    fn poll_key_job(&mut self, current_time: f64) {
+        let Some(job) = &self.key_job else {
+            return;
+        };
+        let kind = job.kind;
+        let (vault, result) = match job.receiver.try_recv() {
+            Ok(done) => done,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.key_job = None;
+                self.error_message = "Schlüsselableitung abgebrochen".into();
+                return;
+            }
+        };
+        self.key_job = None;
+        self.vault = vault;
+        match kind {
+            KeyJobKind::Unlock => self.finish_login(result, current_time),
+            KeyJobKind::Create => match result {
+                Ok(_) => {
+                    self.dirty = false;
+                    self.password_repeat.clear();
+                    self.enter_editor("Vault angelegt", current_time);
+                }
+                Err(e) => self.error_message = e,
+            },
+            KeyJobKind::Reload | KeyJobKind::Merge => {
+                self.finish_reload(kind, result, current_time)
+            }
+            KeyJobKind::Rekey => match result {
+                Ok(_) => {
+                    self.dirty = false;
+                    self.add_toast(
+                        "Vault neu verschlüsselt",
+                        egui::Color32::from_rgb(46, 160, 67),
+                        2.0,
+                        current_time,
+                    );
+                }
+                Err(e) => {
+                    self.add_toast(
+                        "Neu verschlüsseln fehlgeschlagen",
+                        egui::Color32::from_rgb(220, 53, 69),
+                        3.0,
+                        current_time,
+                    );
+                    self.error_message = format!("❌ {}", e);
+                }
+            },
+            KeyJobKind::ChangePassword => self.finish_password_change(result, current_time),
+        }
+    }
+
+    fn finish_login(&mut self, plaintext: Result<Vec<u8>, String>, current_time: f64) {
+        let loaded = plaintext.and_then(|plaintext| {
+            let json_str = String::from_utf8(plaintext).map_err(|e| e.to_string())?;
+            self.load_json(json_str)
+        });
+        match loaded {
+            Ok(_) => {
+                self.failed_logins = 0;
+                self.last_failed_login = None;
+                self.enter_editor("Erfolgreich entsperrt", current_time);
+                self.tighten_vault_permissions(current_time);
+            }
+            Err(e) => {
+                self.error_message = e;
+                self.vault.key = None;
+                // Passwort wird für das Speichern der reparierten Daten noch gebraucht
+                if self.recovery_json.is_none() {
+                    self.password.clear();
+                }
+                // Nur ein falsches Passwort lohnt einen neuen Versuch
+                if is_wrong_password(&self.error_message) {
+                    self.login_shake_time = current_time;
+                    self.record_failed_login(SystemTime::now());
+                }
+            }
+        }
+    }
+
+    /// Verbleibende Wartezeit bis zum nächsten erlaubten Entsperrversuch
+    fn login_wait(&self, now: SystemTime) -> Option<Duration> {
+        let last = self.last_failed_login?;
+        let until = last + login_delay(self.failed_logins);
+        until
+            .duration_since(now)
+            .ok()
+            .filter(|wait| !wait.is_zero())
+    }
+
+    fn record_failed_login(&mut self, now: SystemTime) {
+        self.failed_logins = self.failed_logins.saturating_add(1);
+        self.last_failed_login = Some(now);
+        self.save_settings();
+    }
+
+    fn enter_editor(&mut self, toast: &str, current_time: f64) {
+        remember_last_vault(&self.vault.path);
+        remember_recent_file(&mut self.recent_files, &self.vault.path);
+        self.save_settings();
+        self.set_all_revealed(false);
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_value_edit = None;
+        self.dirty = false;
+        self.screen = Screen::Editor;
+        self.error_message.clear();
+        self.screen_transition_progress = 0.0;
+        self.add_toast(
+            toast,
+            egui::Color32::from_rgb(46, 160, 67),
+            2.0,
+            current_time,
+        );
+    }
+
+    fn settings(&self) -> Settings {
+        Settings {
+            theme: self.theme,
+            toast_position: self.toast_position,
+            toast_duration_factor: self.toast_duration_factor,
+            language: self.language,
+            clipboard_clear_seconds: self.clipboard_clear_seconds,
+            panic_shortcut: self.panic_shortcut.clone(),
+            panic_minimize: self.panic_minimize,
+            reveal_on_hover: self.reveal_on_hover,
+            reuse_warnings: self.reuse_warnings,
+            auto_trim_values: self.auto_trim_values,
+            key_suggestions: self.key_suggestions,
+            recent_key_names: self.recent_key_names.clone(),
+            backup_count: self.backup_count,
+            backup_dir: self.backup_dir.clone(),
+            backup_dir_keep: self.backup_dir_keep,
+            backup_dir_interval_minutes: self.backup_dir_interval_minutes,
+            history_limit: self.history_limit,
+            kdf: self.kdf_params,
+            policy: self.password_policy.clone(),
+            auto_save_enabled: self.auto_save_enabled,
+            auto_save_interval: self.auto_save_interval,
+            undo_depth: self.undo_depth,
+            recent_files: self.recent_files.clone(),
+            failed_logins: self.failed_logins,
+            last_failed_login: self.last_failed_login,
+            window: self.window_geometry,
+        }
+    }
+
+    /// Übernimmt gespeicherte Einstellungen; Design und Sprache gelten sofort,
+    /// der Rest beim nächsten Kopieren, Speichern oder Undo-Schritt
+    fn apply_settings(&mut self, settings: Settings) {
+        self.theme = settings.theme;
+        self.toast_position = settings.toast_position;
+        self.toast_duration_factor = settings.toast_duration_factor.clamp(0.25, 3.0);
+        self.language = settings.language;
+        self.clipboard_clear_seconds = settings.clipboard_clear_seconds.clamp(0.0, 600.0);
+        self.panic_shortcut = settings.panic_shortcut;
+        self.panic_minimize = settings.panic_minimize;
+        self.reveal_on_hover = settings.reveal_on_hover;
+        self.reuse_warnings = settings.reuse_warnings;
+        self.auto_trim_values = settings.auto_trim_values;
+        self.key_suggestions = settings.key_suggestions;
+        self.recent_key_names = settings.recent_key_names;
+        self.recent_key_names.truncate(MAX_RECENT_KEY_NAMES);
+        self.backup_count = settings.backup_count.min(20);
+        self.backup_dir = settings.backup_dir;
+        self.backup_dir_keep = settings.backup_dir_keep.clamp(1, 500);
+        self.backup_dir_interval_minutes = settings.backup_dir_interval_minutes.min(24 * 60);
+        self.history_limit = settings.history_limit.min(100);
+        self.kdf_params = settings.kdf;
+        self.password_policy = settings.policy;
+        self.auto_save_enabled = settings.auto_save_enabled;
+        self.auto_save_interval = settings.auto_save_interval.clamp(5.0, 3600.0);
+        self.undo_depth = settings.undo_depth.min(1000);
+        self.recent_files = settings.recent_files;
+        self.failed_logins = settings.failed_logins;
+        self.last_failed_login = settings.last_failed_login;
+        self.window_geometry = settings.window;
+        self.window_restore = settings.window;
+    }
+
+    fn save_settings(&self) {
+        save_settings(&self.settings());
+    }
+
+    /// Der Text wird hier übersetzt, Aufrufer übergeben den deutschen Originaltext.
+    /// `duration` ist die Standarddauer, die Einstellung skaliert sie.
+    fn add_toast(&mut self, text: &str, color: egui::Color32, duration: f64, current_time: f64) {
+        let text = tr(text).to_string();
+        let duration = (duration * self.toast_duration_factor).max(MIN_TOAST_SECONDS);
+        // Eine Serie gleicher Meldungen (z.B. beim Speichern) nur einmal einreihen
+        if self.toast_queue.iter().any(|toast| toast.text == text) {
+            return;
+        }
+        let toast = ToastMessage::new(self.next_toast_id, text, color, duration, current_time);
+        self.next_toast_id += 1;
+        if self.toast_messages.len() < MAX_VISIBLE_TOASTS {
+            self.toast_messages.push(toast);
+        } else {
+            self.toast_queue.push_back(toast);
+        }
+    }
+
+    /// Schlüsselvorschlag unter dem Formular, solange der Schlüssel leer ist
+    /// und der Wert wie ein bekanntes Token aussieht
+    fn show_key_suggestion(&mut self, ui: &mut egui::Ui) {
+        if !self.key_suggestions
+            || self.new_value_type != ValueType::Text
+            || !self.new_key.trim().is_empty()
+            || self.key_suggestion_dismissed.as_ref() == Some(&self.new_value)
+        {
+            return;
+        }
+        let Some(suggestion) = suggest_key_for_value(&self.new_value) else {
+            return;
+        };
+        let name = next_free_key(&self.data.items, suggestion);
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(tr("💡 Vorschlag:"))
+                    .size(12.0)
+                    .color(egui::Color32::from_gray(140)),
+            );
+            if ui.small_button(&name).clicked() {
+                self.new_key = name;
+            }
+            if ui
+                .small_button("✕")
+                .on_hover_text(tr("Vorschlag ausblenden"))
+                .clicked()
+            {
+                self.key_suggestion_dismissed = Some(self.new_value.clone());
+            }
+        });
+    }
+
+    /// Fügt den Eintrag aus dem Formular hinzu. Existiert der Schlüssel schon und
+    /// ist `overwrite` nicht gesetzt, wird stattdessen nachgefragt.
+    fn add_new_entry(&mut self, current_time: f64, overwrite: bool) {
+        let Some(key) = normalize_key(&self.new_key) else {
+            self.new_key_error = "Schlüssel darf nicht leer sein".to_string();
+            return;
+        };
+        self.new_key_error.clear();
+        if !overwrite && self.data.items.contains_key(&key) {
+            self.overwrite_candidate = Some(key);
+            return;
+        }
+        self.overwrite_candidate = None;
+        let value = match self.new_value_type {
+            ValueType::Text if self.auto_trim_values => {
+                EntryValue::Text(self.new_value.trim().to_string())
+            }
+            ValueType::Text => EntryValue::Text(self.new_value.clone()),
+            ValueType::Number => EntryValue::Number(self.new_number),
+            ValueType::Bool => EntryValue::Bool(self.new_bool),
+            ValueType::Json => match serde_json::from_str::<serde_json::Value>(&self.new_value) {
+                Ok(json) => EntryValue::from(json),
+                Err(e) => {
+                    self.error_message = trf("❌ Ungültiges JSON: {}", &[&e]);
+                    self.add_toast(
+                        "Ungültiges JSON",
+                        egui::Color32::from_rgb(220, 53, 69),
+                        3.0,
+                        current_time,
+                    );
+                    return;
+                }
+            },
+        };
+        self.push_undo();
+        self.data.set_value(key.clone(), value);
+        match self.new_entry_visibility {
+            NewEntryVisibility::Masked => {
+                self.revealed_items.remove(&key);
+                self.reveal_deadlines.remove(&key);
+            }
+            NewEntryVisibility::RevealBriefly => {
+                self.revealed_items.insert(key.clone());
+                self.reveal_deadlines
+                    .insert(key.clone(), current_time + NEW_ENTRY_REVEAL_SECONDS);
+            }
+            NewEntryVisibility::Revealed => {
+                self.revealed_items.insert(key.clone());
+                self.reveal_deadlines.remove(&key);
+            }
+        }
+        if self.key_suggestions {
+            remember_key_name(&mut self.recent_key_names, &key);
+            self.save_settings();
+        }
+        self.new_key.clear();
+        self.new_value.clear();
+        self.new_number = 0.0;
+        self.new_bool = false;
+        self.key_suggestion_dismissed = None;
+        self.add_toast(
+            "Eintrag hinzugefügt",
+            egui::Color32::from_rgb(46, 160, 67),
+            2.0,
+            current_time,
+        );
+    }
+
+    fn import_json_file(&mut self, current_time: f64) {
+        let result = fs::read_to_string(&self.import_path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| {
+                parse_json_import(
+                    &text,
+                    self.import_shape,
+                    &self.import_key_field,
+                    &self.import_value_field,
                 )
-                .show(ctx, |ui| {
-                    ui.add_space(8.0);
-                    ui.label(format!("Eintrag \"{}\" wirklich löschen?", key));
-                    ui.add_space(12.0);
-                    ui.horizontal(|ui| {
-                        if ui
-                            .add(
-                                egui::Button::new("Löschen")
-                                    .fill(egui::Color32::from_rgb(220, 53, 69))
-                                    .rounding(egui::Rounding::same(6.0)),
-                            )
-                            .clicked()
-                        {
-                            self.item_delete_animations.insert(key.clone(), 0.0);
-                            self.data.items.remove(&key);
-                            self.delete_candidate = None;
-                            let current_time = ctx.input(|i| i.time);
-                            self.add_toast(
-                                "Eintrag gelöscht",
-                                egui::Color32::from_rgb(220, 53, 69),
-                                2.0,
-                                current_time,
-                            );
-                        }
-                        if ui
-                            .add(
-                                egui::Button::new("Abbrechen")
-                                    .fill(egui::Color32::from_rgb(108, 117, 125))
-                                    .rounding(egui::Rounding::same(6.0)),
-                            )
-                            .clicked()
-                        {
-                            self.delete_candidate = None;
-                        }
-                    });
-                    ui.add_space(4.0);
-                });
+            });
+        match result {
+            Ok(entries) => {
+                self.import_dialog_open = false;
+                self.import_error.clear();
+                self.start_import(entries, current_time);
+            }
+            Err(e) => self.import_error = e,
         }
     }
 
-    fn show_login_screen(&mut self, ctx: &egui::Context) {
-        let current_time = ctx.input(|i| i.time);
+    /// Importiert eine .env- oder CSV-Datei; beide liefern reine Textwerte
+    fn import_text_file(&mut self, format: PlaintextFormat, current_time: f64) {
+        let mut dialog = rfd::FileDialog::new();
+        if format == PlaintextFormat::Csv {
+            dialog = dialog.add_filter("CSV", &["csv"]);
+        }
+        let Some(path) = dialog.pick_file() else {
+            return;
+        };
+        match self.read_text_import(&path, format) {
+            Ok(entries) => self.start_import(entries, current_time),
+            Err(e) => {
+                self.add_toast(
+                    "Import fehlgeschlagen",
+                    egui::Color32::from_rgb(220, 53, 69),
+                    3.0,
+                    current_time,
+                );
+                self.error_message = format!("❌ {}", e);
+            }
+        }
+    }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.vertical_centered(|ui| {
-                ui.add_space(60.0);
+    /// Liest den CSV-Export eines Passwort-Managers und öffnet die Spaltenzuordnung
+    fn open_login_import(&mut self, current_time: f64) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("CSV", &["csv"])
+            .pick_file()
+        else {
+            return;
+        };
+        let table = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| logins::read_csv(&text, true));
+        match table {
+            Ok(table) => {
+                let columns = logins::guess_columns(&table.headers);
+                self.login_import = Some((table, columns));
+            }
+            Err(e) => {
+                self.add_toast(
+                    "Import fehlgeschlagen",
+                    egui::Color32::from_rgb(220, 53, 69),
+                    3.0,
+                    current_time,
+                );
+                self.error_message = format!("❌ {}", e);
+            }
+        }
+    }
 
-                // Animated title with glow effect
-                let title_color = egui::Color32::from_rgb(52, 144, 220);
-                ui.heading(
-                    egui::RichText::new("🔐 Encrypted JSON Editor")
-                        .size(28.0)
-                        .color(title_color),
+    /// Ersetzt den Wert eines Eintrags durch die gewählte Datei
+    fn attach_file(&mut self, key: &str, path: &Path, current_time: f64) {
+        match Attachment::from_file(path) {
+            Ok(attachment) => {
+                self.push_undo();
+                self.data
+                    .set_value(key.to_string(), EntryValue::Attachment(attachment));
+                self.revalidate(key);
+                self.add_toast(
+                    "Datei angehängt",
+                    egui::Color32::from_rgb(40, 167, 69),
+                    2.0,
+                    current_time,
                 );
-                ui.add_space(8.0);
-                ui.label(
-                    egui::RichText::new("Sichere Verwaltung deiner JSON-Daten")
-                        .size(14.0)
-                        .color(egui::Color32::from_gray(140)),
+            }
+            Err(e) => {
+                self.add_toast(
+                    "Anhang abgelehnt",
+                    egui::Color32::from_rgb(220, 53, 69),
+                    3.0,
+                    current_time,
                 );
-                ui.add_space(30.0);
+                self.error_message = format!("❌ {}", e);
+            }
+        }
+    }
 
-                // Login card with shake animation on error
-                let shake_offset = if current_time - self.login_shake_time < 0.5 {
-                    let shake_progress = (current_time - self.login_shake_time) * 20.0;
-                    (shake_progress.sin()
-                        * 3.0
-                        * (1.0 - (current_time - self.login_shake_time) * 2.0).max(0.0))
-                        as f32
-                } else {
-                    0.0
-                };
+    /// Schreibt den Anhang eines Eintrags unverschlüsselt in eine Datei
+    fn save_attachment(&mut self, key: &str, path: &Path, current_time: f64) {
+        let Some(EntryValue::Attachment(attachment)) = self.data.items.get(key) else {
+            return;
+        };
+        let result = attachment
+            .bytes()
+            .and_then(|bytes| fs::write(path, bytes).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => self.add_toast(
+                "Anhang gespeichert",
+                egui::Color32::from_rgb(40, 167, 69),
+                2.0,
+                current_time,
+            ),
+            Err(e) => {
+                self.add_toast(
+                    "Export fehlgeschlagen",
+                    egui::Color32::from_rgb(220, 53, 69),
+                    3.0,
+                    current_time,
+                );
+                self.error_message = format!("❌ {}", e);
+            }
+        }
+    }
+
+    /// Übernimmt die zugeordneten Zeilen; Zeilen ohne Titel werden gemeldet
+    fn finish_login_import(&mut self, current_time: f64) {
+        let Some((table, columns)) = self.login_import.take() else {
+            return;
+        };
+        let import = logins::map_rows(&table, &columns);
+        if !import.skipped_lines.is_empty() {
+            self.add_toast(
+                &trf(
+                    "{} Zeilen ohne Titel übersprungen",
+                    &[&import.skipped_lines.len()],
+                ),
+                egui::Color32::from_rgb(255, 193, 7),
+                4.0,
+                current_time,
+            );
+        }
+        self.pending_import_notes = import.notes.into_iter().collect();
+        self.start_import(import.entries, current_time);
+    }
+
+    fn read_text_import(
+        &self,
+        path: &Path,
+        format: PlaintextFormat,
+    ) -> Result<Vec<(String, serde_json::Value)>, String> {
+        let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let entries = match format {
+            PlaintextFormat::Csv => parse_csv(&text, self.csv_has_header)?,
+            _ => parse_env(&text)?,
+        };
+        Ok(entries
+            .into_iter()
+            .map(|(key, value)| (key, serde_json::Value::String(value)))
+            .collect())
+    }
+
+    /// Nimmt die erste ins Fenster gezogene Datei entgegen
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped = ctx.input(|i| {
+            i.raw
+                .dropped_files
+                .iter()
+                .find_map(|file| file.path.clone())
+        });
+        if let Some(path) = dropped {
+            self.open_dropped_file(path, ctx.input(|i| i.time));
+        }
+    }
+
+    /// Ein Vault wird geöffnet (neben einem entsperrten in einem neuen Tab), alles
+    /// andere nach Rückfrage in den entsperrten Vault importiert
+    fn open_dropped_file(&mut self, path: PathBuf, current_time: f64) {
+        let Some(kind) = DroppedFile::classify(&path) else {
+            self.add_toast(
+                "Nicht unterstützter Dateityp",
+                egui::Color32::from_rgb(220, 53, 69),
+                3.0,
+                current_time,
+            );
+            self.error_message = trf(
+                "❌ {} kann nicht geöffnet werden, erwartet wird .json, .env, .csv oder .enc",
+                &[&path.display()],
+            );
+            return;
+        };
+        if kind == DroppedFile::Vault {
+            // Ein entsperrter Vault bleibt offen, der neue kommt in einen eigenen Tab
+            if matches!(self.screen, Screen::Editor) {
+                self.open_in_new_tab(path, VaultPathSource::DroppedFile);
+                return;
+            }
+            match self.open_vault_file(path, VaultPathSource::DroppedFile) {
+                Ok(_) => self.error_message.clear(),
+                Err(e) => self.error_message = e,
+            }
+            return;
+        }
+        if !matches!(self.screen, Screen::Editor) || self.read_only {
+            self.add_toast(
+                "Import nur in einen entsperrten, bearbeitbaren Vault",
+                egui::Color32::from_rgb(220, 53, 69),
+                3.0,
+                current_time,
+            );
+            return;
+        }
+        match kind {
+            // Der JSON-Dialog fragt ohnehin nach Form und Feldern
+            DroppedFile::Import(PlaintextFormat::Json) => {
+                self.import_path = path.display().to_string();
+                self.import_error.clear();
+                self.import_dialog_open = true;
+            }
+            DroppedFile::Import(format) => match self.read_text_import(&path, format) {
+                Ok(entries) => self.dropped_import = Some((path, entries)),
+                Err(e) => {
+                    self.add_toast(
+                        "Import fehlgeschlagen",
+                        egui::Color32::from_rgb(220, 53, 69),
+                        3.0,
+                        current_time,
+                    );
+                    self.error_message = format!("❌ {}", e);
+                }
+            },
+            DroppedFile::Vault => {}
+        }
+    }
+
+    /// Bei Kollisionen mit bestehenden Schlüsseln wird erst nachgefragt
+    fn start_import(&mut self, entries: Vec<(String, serde_json::Value)>, current_time: f64) {
+        if entries
+            .iter()
+            .any(|(key, _)| self.data.items.contains_key(key))
+        {
+            self.pending_import = Some(entries);
+        } else {
+            self.finish_import(entries, ImportCollision::Overwrite, current_time);
+        }
+    }
+
+    fn finish_import(
+        &mut self,
+        entries: Vec<(String, serde_json::Value)>,
+        collision: ImportCollision,
+        current_time: f64,
+    ) {
+        self.pending_import = None;
+        let (imported, skipped) = self.merge_import(entries, collision);
+        self.pending_import_notes.clear();
+        self.restore_list_position();
+        self.revalidate_all();
+        let text = if skipped > 0 {
+            trf(
+                "{} Einträge importiert, {} übersprungen",
+                &[&imported, &skipped],
+            )
+        } else {
+            trf("{} Einträge importiert", &[&imported])
+        };
+        self.add_toast(
+            &text,
+            egui::Color32::from_rgb(46, 160, 67),
+            3.0,
+            current_time,
+        );
+    }
+
+    /// Übernimmt importierte Einträge; liefert (importiert, übersprungen)
+    fn merge_import(
+        &mut self,
+        entries: Vec<(String, serde_json::Value)>,
+        collision: ImportCollision,
+    ) -> (usize, usize) {
+        if entries.is_empty() {
+            return (0, 0);
+        }
+        self.push_undo();
+        let mut imported = 0;
+        let mut skipped = 0;
+        for (key, value) in entries {
+            let note = self.pending_import_notes.remove(&key);
+            let key = match collision {
+                _ if !self.data.items.contains_key(&key) => key,
+                ImportCollision::Skip => {
+                    skipped += 1;
+                    continue;
+                }
+                ImportCollision::Overwrite => key,
+                ImportCollision::KeepBoth => {
+                    unique_key(&key, |candidate| self.data.items.contains_key(candidate))
+                }
+            };
+            self.data.set_value(key.clone(), EntryValue::from(value));
+            if let (Some(note), Some(meta)) = (note, self.data.meta.get_mut(&key)) {
+                meta.note = note;
+            }
+            imported += 1;
+        }
+        (imported, skipped)
+    }
+
+    /// Verschlüsselt die ausgewählten Einträge mit dem Export-Passwort in einen
+    /// eigenen Vault unter `path`, wie beim Speichern nur mit eigenem Salt und
+    /// ohne Schlüsseldatei. Liefert die Anzahl der exportierten Einträge.
+    fn export_selection_to(&mut self, path: &Path) -> Result<usize, String> {
+        if path == self.vault.path {
+            return Err(tr("Der geöffnete Vault kann nicht das Ziel sein").to_string());
+        }
+        self.finish_pending_deletes();
+        let subset = self.data.subset(&self.selected_keys);
+        let mut target = Vault::new(path.to_path_buf());
+        target.header = FileHeader {
+            cipher: self.vault.header.cipher,
+            kdf: self.kdf_params,
+            ..FileHeader::current()
+        };
+        target.save_with_new_password(&self.share_password, None, &subset, 0)?;
+        Ok(subset.items.len())
+    }
+
+    fn close_share_export_dialog(&mut self) {
+        self.share_export_open = false;
+        self.share_password.clear();
+        self.share_password_repeat.clear();
+        self.share_error.clear();
+    }
+
+    /// Schreibt die entschlüsselten Daten unverschlüsselt in eine gewählte Datei
+    fn export_file(&mut self, format: PlaintextFormat, current_time: f64) {
+        let mut dialog = rfd::FileDialog::new().set_file_name(format.file_name());
+        match format {
+            PlaintextFormat::Json => dialog = dialog.add_filter("JSON", &["json"]),
+            PlaintextFormat::Csv => dialog = dialog.add_filter("CSV", &["csv"]),
+            PlaintextFormat::Env => {}
+        }
+        let Some(path) = dialog.save_file() else {
+            return;
+        };
+        let mut skipped = 0;
+        let content = match format {
+            PlaintextFormat::Json => {
+                let mut data = self.data.clone();
+                if !self.export_history {
+                    data.trim_history(0);
+                }
+                serde_json::to_string_pretty(&data).map_err(|e| e.to_string())
+            }
+            PlaintextFormat::Env => {
+                let (content, unsuitable) = format_env(&self.data.items);
+                skipped = unsuitable;
+                Ok(content)
+            }
+            PlaintextFormat::Csv => format_csv(&self.data.items).map(|(content, unsuitable)| {
+                skipped = unsuitable;
+                content
+            }),
+        };
+        let result =
+            content.and_then(|content| fs::write(&path, content).map_err(|e| e.to_string()));
+        match result {
+            Ok(_) if skipped > 0 => self.add_toast(
+                &trf(
+                    "Klartext-Export gespeichert, {} Einträge übersprungen (ungeeigneter Schlüssel, Anhang oder JSON)",
+                    &[&skipped],
+                ),
+                egui::Color32::from_rgb(255, 193, 7),
+                4.0,
+                current_time,
+            ),
+            Ok(_) => self.add_toast(
+                "Klartext-Export gespeichert",
+                egui::Color32::from_rgb(52, 144, 220),
+                2.0,
+                current_time,
+            ),
+            Err(e) => {
+                self.add_toast(
+                    "Export fehlgeschlagen",
+                    egui::Color32::from_rgb(220, 53, 69),
+                    3.0,
+                    current_time,
+                );
+                self.error_message = format!("❌ {}", e);
+            }
+        }
+    }
+
+    fn open_url(&mut self, url: &str, current_time: f64) {
+        if !is_openable_url(url) {
+            return;
+        }
+        if let Err(e) = webbrowser::open(url) {
+            self.add_toast(
+                "Browser konnte nicht geöffnet werden",
+                egui::Color32::from_rgb(220, 53, 69),
+                3.0,
+                current_time,
+            );
+            self.error_message = format!("❌ {}", e);
+        }
+    }
+
+    /// Schaltet den Lesemodus um; offene Bearbeitungen werden dabei verworfen
+    fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+        if read_only {
+            self.rename_key = None;
+            self.tag_input_key = None;
+            self.selected_keys.clear();
+        }
+    }
+
+    /// Legt direkt unter `key` eine Kopie mit Wert, Notiz, Tags und Markierung an
+    /// und startet das Umbenennen der Kopie
+    fn duplicate_entry(&mut self, key: &str, current_time: f64) -> Option<String> {
+        let index = self.data.items.get_index_of(key)?;
+        let base = format!("{} ({})", key, tr("Kopie"));
+        let new_key = if self.data.items.contains_key(&base) {
+            unique_key(&base, |candidate| self.data.items.contains_key(candidate))
+        } else {
+            base
+        };
+        self.push_undo();
+        let value = self.data.items[index].clone();
+        self.data
+            .items
+            .shift_insert(index + 1, new_key.clone(), value);
+        let now = SystemTime::now();
+        let mut meta = self
+            .data
+            .meta
+            .get(key)
+            .cloned()
+            .unwrap_or_else(|| EntryMeta::new(now));
+        meta.created = now;
+        meta.modified = now;
+        self.data.meta.insert(new_key.clone(), meta);
+        if self.data.critical.contains(key) {
+            self.data.critical.insert(new_key.clone());
+        }
+        self.data.touch();
+
+        self.rename_key = Some(new_key.clone());
+        self.rename_buffer = new_key.clone();
+        self.rename_focus_pending = true;
+        self.scroll_to_key = Some(new_key.clone());
+        self.add_toast(
+            "Eintrag dupliziert",
+            egui::Color32::from_rgb(40, 167, 69),
+            2.0,
+            current_time,
+        );
+        Some(new_key)
+    }
+
+    /// Benennt einen Eintrag um und behält dabei Position, Wert und Markierungen
+    fn rename_entry(&mut self, old_key: &str, new_key: &str, current_time: f64) -> bool {
+        let Some(new_key) = normalize_key(new_key) else {
+            self.add_toast(
+                "Schlüssel darf nicht leer sein",
+                egui::Color32::from_rgb(220, 53, 69),
+                3.0,
+                current_time,
+            );
+            return false;
+        };
+        let new_key = new_key.as_str();
+        if new_key == old_key {
+            return true;
+        }
+        if self.data.items.contains_key(new_key) {
+            self.add_toast(
+                &trf("Schlüssel \"{}\" existiert bereits", &[&new_key]),
+                egui::Color32::from_rgb(220, 53, 69),
+                3.0,
+                current_time,
+            );
+            return false;
+        }
+        if !self.data.items.contains_key(old_key) {
+            return false;
+        }
+        self.push_undo();
+        let Some((index, _, value)) = self.data.items.shift_remove_full(old_key) else {
+            return false;
+        };
+        self.data
+            .items
+            .shift_insert(index, new_key.to_string(), value);
+        self.data.touch();
+        if self.data.critical.remove(old_key) {
+            self.data.critical.insert(new_key.to_string());
+        }
+        if let Some(meta) = self.data.meta.remove(old_key) {
+            self.data.meta.insert(new_key.to_string(), meta);
+        }
+        if self.revealed_items.remove(old_key) {
+            self.revealed_items.insert(new_key.to_string());
+        }
+        if let Some(deadline) = self.reveal_deadlines.remove(old_key) {
+            self.reveal_deadlines.insert(new_key.to_string(), deadline);
+        }
+        if self.expanded_values.remove(old_key) {
+            self.expanded_values.insert(new_key.to_string());
+        }
+        if let Some(error) = self.validation_errors.remove(old_key) {
+            self.validation_errors.insert(new_key.to_string(), error);
+        }
+        self.item_hover_states.remove(old_key);
+        if self.last_focused_key.as_deref() == Some(old_key) {
+            self.last_focused_key = Some(new_key.to_string());
+        }
+        self.add_toast(
+            "Eintrag umbenannt",
+            egui::Color32::from_rgb(46, 160, 67),
+            2.0,
+            current_time,
+        );
+        true
+    }
+
+    /// Entfernt Einträge, deren Lösch-Animation noch läuft, sofort
+    fn finish_pending_deletes(&mut self) {
+        for (key, _) in std::mem::take(&mut self.item_delete_animations) {
+            self.data.remove_entry(&key);
+        }
+    }
+
+    /// Sichert den aktuellen Stand vor einer Änderung im Undo-Stack
+    fn push_undo(&mut self) {
+        self.last_value_edit = None;
+        self.push_undo_snapshot();
+    }
+
+    /// Wie `push_undo`, fasst aber schnell aufeinanderfolgende Änderungen am
+    /// selben Wert (z.B. Tippen) zu einem einzigen Undo-Schritt zusammen
+    fn push_undo_for_value_edit(&mut self, key: &str, current_time: f64) {
+        let coalesce = matches!(
+            &self.last_value_edit,
+            Some((last_key, last_time))
+                if last_key == key && current_time - last_time < UNDO_COALESCE_SECONDS
+        );
+        if !coalesce {
+            self.push_undo_snapshot();
+        }
+        self.last_value_edit = Some((key.to_string(), current_time));
+    }
+
+    fn push_undo_snapshot(&mut self) {
+        self.dirty = true;
+        self.undo_stack.push(self.data.clone());
+        if self.undo_stack.len() > self.undo_depth {
+            let excess = self.undo_stack.len() - self.undo_depth;
+            self.undo_stack.drain(..excess);
+        }
+        self.redo_stack.clear();
+    }
+
+    fn undo(&mut self, current_time: f64) {
+        self.finish_pending_deletes();
+        if let Some(previous) = self.undo_stack.pop() {
+            let current = std::mem::replace(&mut self.data, previous);
+            self.redo_stack.push(current);
+            self.dirty = true;
+            self.last_value_edit = None;
+            self.revalidate_all();
+            self.add_toast(
+                "Rückgängig gemacht",
+                egui::Color32::from_rgb(108, 117, 125),
+                1.5,
+                current_time,
+            );
+        }
+    }
+
+    fn redo(&mut self, current_time: f64) {
+        self.finish_pending_deletes();
+        if let Some(next) = self.redo_stack.pop() {
+            let current = std::mem::replace(&mut self.data, next);
+            self.undo_stack.push(current);
+            self.dirty = true;
+            self.last_value_edit = None;
+            self.revalidate_all();
+            self.add_toast(
+                "Wiederhergestellt",
+                egui::Color32::from_rgb(108, 117, 125),
+                1.5,
+                current_time,
+            );
+        }
+    }
+
+    /// Strg+Z / Strg+Y (bzw. Strg+Umschalt+Z). Hat ein Textfeld den Fokus,
+    /// bleibt die Tastenkombination dessen eigenem Undo überlassen.
+    fn handle_undo_shortcuts(&mut self, ctx: &egui::Context) {
+        if self.read_only || ctx.memory(|m| m.focused().is_some()) {
+            return;
+        }
+        let undo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+        let redo = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Y);
+        let redo_shift = egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+            egui::Key::Z,
+        );
+        let current_time = ctx.input(|i| i.time);
+        // Die Umschalt-Variante zuerst prüfen, sonst würde sie als Strg+Z konsumiert
+        if ctx.input_mut(|i| i.consume_shortcut(&redo_shift) || i.consume_shortcut(&redo)) {
+            self.redo(current_time);
+        } else if ctx.input_mut(|i| i.consume_shortcut(&undo)) {
+            self.undo(current_time);
+        }
+    }
+
+    /// Strg+S speichert, Strg+F springt in die Suche, Strg+P öffnet die Befehlspalette,
+    /// Escape schließt offene Dialoge. Keines davon kollidiert mit der Bearbeitung in
+    /// Textfeldern, daher immer aktiv.
+    fn handle_editor_shortcuts(&mut self, ctx: &egui::Context) {
+        let save = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::S);
+        let find = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::F);
+        let palette = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::P);
+        if ctx.input_mut(|i| i.consume_shortcut(&save)) {
+            self.save_requested = Some(false);
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&find)) {
+            self.search_focus_pending = true;
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&palette)) {
+            self.palette_open = !self.palette_open;
+            self.palette_query.clear();
+            self.palette_selected = 0;
+        }
+        // Ohne offenen Dialog bleibt Escape z.B. dem Umbenennen-Feld überlassen
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) && self.close_dialogs() {
+            ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape));
+        }
+    }
+
+    /// Schließt offene Dialoge wie ihr "Abbrechen"; liefert, ob einer offen war.
+    /// Die Wiederherstellung bleibt offen, sonst ginge der Rohtext verloren.
+    fn close_dialogs(&mut self) -> bool {
+        let open = self.delete_candidate.is_some()
+            || self.overwrite_candidate.is_some()
+            || self.close_confirm_open
+            || self.tab_close_prompt
+            || self.export_confirm.is_some()
+            || self.password_change_open
+            || self.import_dialog_open
+            || self.pending_import.is_some()
+            || self.dropped_import.is_some()
+            || self.login_import.is_some()
+            || self.settings_open
+            || self.help_open
+            || self.about_open
+            || self.json_source_open
+            || self.bulk_delete_open
+            || self.clear_all_open
+            || self.share_export_open
+            || self.validation_save_prompt
+            || self.external_change.is_some()
+            || self.palette_open;
+        self.delete_candidate = None;
+        self.delete_confirm_text.clear();
+        self.overwrite_candidate = None;
+        self.close_confirm_open = false;
+        self.tab_close_prompt = false;
+        self.export_confirm = None;
+        if self.password_change_open {
+            self.close_password_change_dialog();
+        }
+        self.import_dialog_open = false;
+        self.import_error.clear();
+        self.pending_import = None;
+        self.pending_import_notes.clear();
+        self.dropped_import = None;
+        self.login_import = None;
+        self.settings_open = false;
+        self.help_open = false;
+        self.about_open = false;
+        self.json_source_open = false;
+        self.bulk_delete_open = false;
+        self.clear_all_open = false;
+        self.close_share_export_dialog();
+        self.validation_save_prompt = false;
+        self.palette_open = false;
+        if let Some(modified) = self.external_change.take() {
+            self.external_change_dismissed = Some(modified);
+        }
+        open
+    }
+
+    /// Merkt sich die Fenstergeometrie fürs nächste Mal. Hat sich der Monitor seit dem
+    /// Speichern geändert (z.B. zweiter Bildschirm abgesteckt), wird das wiederhergestellte
+    /// Fenster im ersten Frame auf den aktuellen Monitor geholt.
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let viewport = ctx.input(|i| i.viewport().clone());
+        if let (Some(restored), Some(monitor)) = (self.window_restore, viewport.monitor_size) {
+            self.window_restore = None;
+            if restored.monitor != Some([monitor.x, monitor.y]) {
+                let clamped = restored.clamped_to(monitor);
+                ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(
+                    clamped.width,
+                    clamped.height,
+                )));
+                ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(
+                    clamped.x, clamped.y,
+                )));
+            }
+        }
+        if viewport.minimized == Some(true) || viewport.maximized == Some(true) {
+            return;
+        }
+        if let (Some(outer), Some(inner)) = (viewport.outer_rect, viewport.inner_rect) {
+            self.window_geometry = Some(WindowGeometry {
+                x: outer.min.x,
+                y: outer.min.y,
+                width: inner.width(),
+                height: inner.height(),
+                monitor: viewport.monitor_size.map(|m| [m.x, m.y]),
+            });
+        }
+    }
+
+    fn active_tab(&self) -> usize {
+        self.tabs.iter().position(Option::is_none).unwrap_or(0)
+    }
+
+    /// Nimmt dem Editor den Zustand des aktiven Vaults ab und lässt leere Felder zurück
+    fn take_session(&mut self) -> VaultSession {
+        self.finish_pending_deletes();
+        self.close_dialogs();
+        self.rename_key = None;
+        self.tag_input_key = None;
+        self.filtered_for = None;
+        self.error_message.clear();
+        VaultSession {
+            screen: std::mem::replace(&mut self.screen, Screen::PasswordInput),
+            vault: std::mem::replace(&mut self.vault, Vault::new(PathBuf::new())),
+            vault_path_source: self.vault_path_source,
+            password: std::mem::take(&mut self.password),
+            key_file_path: self.key_file_path.take(),
+            key_file_required: std::mem::take(&mut self.key_file_required),
+            data: std::mem::take(&mut self.data),
+            dirty: std::mem::take(&mut self.dirty),
+            undo_stack: std::mem::take(&mut self.undo_stack),
+            redo_stack: std::mem::take(&mut self.redo_stack),
+            last_value_edit: self.last_value_edit.take(),
+            validation_errors: std::mem::take(&mut self.validation_errors),
+            revealed_items: std::mem::take(&mut self.revealed_items),
+            reveal_deadlines: std::mem::take(&mut self.reveal_deadlines),
+            expanded_values: std::mem::take(&mut self.expanded_values),
+            selected_keys: std::mem::take(&mut self.selected_keys),
+            list_scroll_offset: std::mem::take(&mut self.list_scroll_offset),
+            last_focused_key: self.last_focused_key.take(),
+        }
+    }
+
+    fn put_session(&mut self, session: VaultSession) {
+        self.screen = session.screen;
+        self.vault = session.vault;
+        self.vault_path_source = session.vault_path_source;
+        self.password = session.password;
+        self.key_file_path = session.key_file_path;
+        self.key_file_required = session.key_file_required;
+        self.data = session.data;
+        self.dirty = session.dirty;
+        self.undo_stack = session.undo_stack;
+        self.redo_stack = session.redo_stack;
+        self.last_value_edit = session.last_value_edit;
+        self.validation_errors = session.validation_errors;
+        self.revealed_items = session.revealed_items;
+        self.reveal_deadlines = session.reveal_deadlines;
+        self.expanded_values = session.expanded_values;
+        self.selected_keys = session.selected_keys;
+        self.list_scroll_offset = session.list_scroll_offset;
+        self.last_focused_key = session.last_focused_key;
+        self.external_change = None;
+        self.restore_list_position();
+    }
+
+    fn switch_tab(&mut self, index: usize) {
+        let active = self.active_tab();
+        if index == active || index >= self.tabs.len() || self.key_job.is_some() {
+            return;
+        }
+        let Some(next) = self.tabs[index].take() else {
+            return;
+        };
+        self.tabs[active] = Some(self.take_session());
+        self.put_session(next);
+    }
+
+    /// Öffnet `path` in einem neuen Tab; ist er schon offen, wird nur dorthin gewechselt
+    fn open_in_new_tab(&mut self, path: PathBuf, source: VaultPathSource) {
+        if self.key_job.is_some() {
+            return;
+        }
+        let open = self.tabs.iter().position(|tab| match tab {
+            Some(session) => session.vault.path == path,
+            None => self.vault.path == path,
+        });
+        if let Some(index) = open {
+            self.switch_tab(index);
+            return;
+        }
+        let active = self.active_tab();
+        self.tabs[active] = Some(self.take_session());
+        self.tabs.push(None);
+        self.set_vault_path(path, source);
+    }
+
+    /// Der Nutzer hat ✕ geklickt; mit ungespeicherten Änderungen wird erst nachgefragt
+    fn request_close_tab(&mut self, index: usize) {
+        if self.tabs.len() < 2 {
+            return;
+        }
+        self.switch_tab(index);
+        if self.active_tab() != index {
+            return;
+        }
+        if self.dirty {
+            self.tab_close_prompt = true;
+        } else {
+            self.close_active_tab();
+        }
+    }
+
+    fn close_active_tab(&mut self) {
+        self.tab_close_prompt = false;
+        if self.tabs.len() < 2 {
+            return;
+        }
+        let active = self.active_tab();
+        // Entschlüsselte Daten und Passwort des geschlossenen Tabs verwerfen
+        drop(self.take_session());
+        self.tabs.remove(active);
+        let next = active.min(self.tabs.len() - 1);
+        if let Some(session) = self.tabs[next].take() {
+            self.put_session(session);
+        }
+    }
+
+    /// Ungespeicherte Änderungen im aktiven oder einem wartenden Tab
+    fn any_unsaved(&self) -> bool {
+        self.dirty || self.tabs.iter().flatten().any(|session| session.dirty)
+    }
+
+    /// Speichert die wartenden Tabs; der aktive läuft wie bisher über `encrypt_data`
+    fn save_parked_sessions(&mut self) -> Result<(), String> {
+        for session in self.tabs.iter_mut().flatten() {
+            if session.dirty {
+                if session.vault.external_change().is_some() {
+                    return Err(trf(
+                        "{} wurde inzwischen von außen geändert",
+                        &[&tab_label(&session.vault.path)],
+                    ));
+                }
+                session.data.trim_history(self.history_limit);
+                session
+                    .vault
+                    .save(&session.password, &session.data, self.backup_count)?;
+                session.dirty = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sperrt alle offenen Vaults, ohne die App zu beenden. Ungespeicherte Änderungen
+    /// werden vorher gespeichert; scheitert das, bleibt der Editor offen.
+    fn lock(&mut self, current_time: f64) {
+        let saved = if self.dirty {
+            self.encrypt_data()
+        } else {
+            Ok(())
+        };
+        if let Err(e) = saved.and_then(|()| self.save_parked_sessions()) {
+            self.add_toast(
+                "Sperren abgebrochen: Speichern fehlgeschlagen",
+                egui::Color32::from_rgb(220, 53, 69),
+                3.0,
+                current_time,
+            );
+            self.error_message = format!("❌ {}", e);
+            return;
+        }
+        self.clear_unlocked_state();
+        self.add_toast(
+            "🔒 Vault gesperrt",
+            egui::Color32::from_rgb(108, 117, 125),
+            2.0,
+            current_time,
+        );
+    }
+
+    /// Verwirft alles Entschlüsselte aller Tabs; danach liegt nichts davon mehr im Speicher
+    fn clear_unlocked_state(&mut self) {
+        // Ein laufender Job brächte sonst Schlüssel, Daten oder Passwort zurück;
+        // sein Ergebnis wird mit dem Empfänger verworfen
+        self.key_job = None;
+        self.password_change_pending = None;
+        // Wartende Tabs sperren mit, sonst stellte ein Klick in der Tableiste sie wieder her
+        for session in self.tabs.iter_mut().flatten() {
+            session.lock();
+        }
+        self.close_dialogs();
+        // Der HTTP-Zugang endet mit dem Sperren, nicht erst im nächsten Frame
+        self.server = None;
+        self.recovery_json = None;
+        self.item_delete_animations.clear();
+        self.data = AppData::default();
+        self.validation_errors.clear();
+        self.password.clear();
+        self.vault.key = None;
+        self.vault.key_file = None;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_value_edit = None;
+        self.set_all_revealed(false);
+        self.expanded_values.clear();
+        self.selected_keys.clear();
+        self.rename_key = None;
+        self.tag_input_key = None;
+        self.tag_input.clear();
+        self.new_key.clear();
+        self.new_value.clear();
+        self.search_query.clear();
+        self.search_query_cached.clear();
+        self.search_query_lower.clear();
+        self.tag_filter.clear();
+        self.key_cache.clear();
+        self.filtered_keys.clear();
+        self.filtered_for = None;
+        self.error_message.clear();
+        self.dirty = false;
+        self.screen = Screen::PasswordInput;
+    }
+
+    /// Panik-Sperre: leert sofort die Zwischenablage, sperrt alle Vaults und
+    /// minimiert auf Wunsch das Fenster. Anders als `lock` sperrt sie auch, wenn
+    /// das Speichern scheitert; ungespeicherte Änderungen landen dann in einer
+    /// Notkopie neben dem Vault, verschlüsselt mit dem vorhandenen Schlüssel.
+    fn panic_lock(&mut self, ctx: &egui::Context, current_time: f64) {
+        if self.panic_minimize {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+        }
+        self.clear_clipboard(current_time);
+        let now = SystemTime::now();
+        let mut rescued = Vec::new();
+        let mut lost = 0;
+        if self.dirty && self.encrypt_data().is_err() {
+            match self.vault.write_emergency_copy(&self.data, now) {
+                Ok(path) => rescued.push(path),
+                Err(_) => lost += 1,
+            }
+        }
+        // Bricht beim ersten Fehler ab; was danach noch offen ist, kommt in Notkopien
+        let _ = self.save_parked_sessions();
+        for session in self.tabs.iter().flatten().filter(|session| session.dirty) {
+            match session.vault.write_emergency_copy(&session.data, now) {
+                Ok(path) => rescued.push(path),
+                Err(_) => lost += 1,
+            }
+        }
+        self.clear_unlocked_state();
+        if lost > 0 {
+            self.add_toast(
+                &trf(
+                    "Gesperrt, aber {} Vault(s) ließen sich nicht sichern: Änderungen verworfen",
+                    &[&lost],
+                ),
+                egui::Color32::from_rgb(220, 53, 69),
+                6.0,
+                current_time,
+            );
+        } else if !rescued.is_empty() {
+            let names: Vec<String> = rescued
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            self.add_toast(
+                &trf(
+                    "Gesperrt; Speichern fehlgeschlagen, Änderungen liegen in {}",
+                    &[&names.join(", ")],
+                ),
+                egui::Color32::from_rgb(255, 193, 7),
+                6.0,
+                current_time,
+            );
+        } else {
+            self.add_toast(
+                "🔒 Vault gesperrt",
+                egui::Color32::from_rgb(108, 117, 125),
+                2.0,
+                current_time,
+            );
+        }
+    }
+
+    fn handle_panic_shortcut(&mut self, ctx: &egui::Context) {
+        if !matches!(self.screen, Screen::Editor) {
+            return;
+        }
+        let Some(shortcut) = parse_shortcut(&self.panic_shortcut) else {
+            return;
+        };
+        if ctx.input_mut(|i| i.consume_shortcut(&shortcut)) {
+            self.panic_lock(ctx, ctx.input(|i| i.time));
+        }
+    }
+
+    /// Startet den `--serve`-Server nach dem Entsperren, hält seine Einträge
+    /// aktuell und beendet ihn, sobald kein Vault mehr entsperrt ist
+    fn sync_server(&mut self, current_time: f64) {
+        let Some((addr, token)) = &self.serve_config else {
+            return;
+        };
+        if !matches!(self.screen, Screen::Editor) {
+            self.server = None;
+            return;
+        }
+        if self.server.is_some() && self.served_revision == self.data.revision {
+            return;
+        }
+        let entries = match serde_json::to_string(&self.data.items) {
+            Ok(entries) => entries,
+            Err(e) => {
+                self.error_message = format!("❌ {}", e);
+                return;
+            }
+        };
+        match &self.server {
+            Some(server) => server.update(entries),
+            None => match serve::Server::start(*addr, token.clone(), entries) {
+                Ok(server) => {
+                    eprintln!("HTTP-Zugang aktiv: http://{}/entries", server.local_addr());
+                    self.server = Some(server);
+                }
+                Err(e) => {
+                    // Nicht in jedem Frame erneut versuchen
+                    self.serve_config = None;
+                    self.add_toast(
+                        "HTTP-Zugang konnte nicht starten",
+                        egui::Color32::from_rgb(220, 53, 69),
+                        3.0,
+                        current_time,
+                    );
+                    self.error_message = format!("❌ {}", e);
+                }
+            },
+        }
+        self.served_revision = self.data.revision;
+    }
+
+    /// Verdeckt, solange der Wert weder aufgedeckt ist noch (falls eingeschaltet)
+    /// der Mauszeiger über seiner Zeile steht
+    fn value_masked(&self, key: &str) -> bool {
+        let hover_revealed = self.reveal_on_hover
+            && self
+                .item_hover_states
+                .get(key)
+                .is_some_and(|progress| *progress >= 0.5);
+        !self.revealed_items.contains(key) && !hover_revealed
+    }
+
+    /// Prüft den Wert von `key` gegen seine Regel und merkt sich eine Verletzung
+    fn revalidate(&mut self, key: &str) {
+        let result = match (self.data.items.get(key), self.data.meta.get(key)) {
+            (Some(value), Some(meta)) => meta.rule.check(value),
+            _ => Ok(()),
+        };
+        match result {
+            Ok(()) => {
+                self.validation_errors.remove(key);
+            }
+            Err(e) => {
+                self.validation_errors.insert(key.to_string(), e);
+            }
+        }
+    }
+
+    /// Nach Laden, Import, Undo und Redo, wenn sich beliebige Werte geändert haben können
+    fn revalidate_all(&mut self) {
+        self.validation_errors.clear();
+        let keys: Vec<String> = self
+            .data
+            .meta
+            .iter()
+            .filter(|(_, meta)| !meta.rule.is_none())
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            self.revalidate(&key);
+        }
+    }
+
+    /// Anzahl verletzter Regeln; Verletzungen gelöschter Einträge zählen nicht mehr
+    fn rule_violations(&mut self) -> usize {
+        let items = &self.data.items;
+        self.validation_errors
+            .retain(|key, _| items.contains_key(key));
+        self.validation_errors.len()
+    }
+
+    /// Speichert auf Wunsch des Nutzers. Verletzte Prüfregeln halten das Speichern auf,
+    /// bis sie behoben sind oder `ignore_rules` (»Trotzdem speichern«) gesetzt ist.
+    /// Sperren und Schließen speichern ohne Rückfrage, damit nichts verloren geht.
+    fn save(&mut self, current_time: f64, ignore_rules: bool) {
+        self.finish_pending_deletes();
+        if !ignore_rules && self.rule_violations() > 0 {
+            self.validation_save_prompt = true;
+            return;
+        }
+        self.validation_save_prompt = false;
+        if self.check_external_change() {
+            return;
+        }
+        self.tighten_vault_permissions(current_time);
+        match self.encrypt_data() {
+            Ok(_) => {
+                self.restore_list_position();
+                self.add_toast(
+                    "Erfolgreich gespeichert",
+                    egui::Color32::from_rgb(40, 167, 69),
+                    2.0,
+                    current_time,
+                );
+            }
+            Err(e) => {
+                self.add_toast(
+                    "Fehler beim Speichern",
+                    egui::Color32::from_rgb(220, 53, 69),
+                    3.0,
+                    current_time,
+                );
+                self.error_message = format!("❌ {}", e);
+            }
+        }
+    }
+
+    /// Schlüssel in Anzeigereihenfolge nach Suche und Tag-Filter. Neu berechnet
+    /// wird nur, wenn sich Daten, Suche, Filter oder Sortierung geändert haben.
+    fn visible_keys(&mut self, current_time: f64) -> Vec<String> {
+        self.refresh_search_cache(current_time);
+        if self.key_cache_revision != self.data.revision {
+            self.key_cache = self
+                .data
+                .items
+                .keys()
+                .map(|key| (key.clone(), key.to_lowercase()))
+                .collect();
+            self.key_cache_revision = self.data.revision;
+        }
+
+        let inputs = FilterInputs {
+            revision: self.data.revision,
+            query: self.search_query_lower.clone(),
+            regex: self.active_search_regex().is_some(),
+            fuzzy: self.search_fuzzy,
+            scope: self.search_scope,
+            tags: self.tag_filter.clone(),
+            sort: self.sort_mode,
+        };
+        if self.filtered_for.as_ref() != Some(&inputs) {
+            let filtering = !inputs.query.is_empty();
+            let mut keys: Vec<String> = self
+                .key_cache
+                .iter()
+                .filter(|(key, lower)| {
+                    !filtering
+                        || (self.search_scope != SearchScope::Values
+                            && match self.active_search_regex() {
+                                Some(regex) => regex.is_match(key),
+                                None if self.search_fuzzy => {
+                                    fuzzy_score(&inputs.query, key).is_some()
+                                }
+                                None => lower.contains(&inputs.query),
+                            })
+                        || self.value_matches_search(key)
+                })
+                .filter(|(key, _)| self.data.has_tags(key, &self.tag_filter))
+                .map(|(key, _)| key.clone())
+                .collect();
+            self.sort_keys(&mut keys);
+            if filtering && self.search_fuzzy {
+                // Stabil: bei gleicher Wertung bleibt die gewählte Sortierung
+                keys.sort_by_cached_key(|key| std::cmp::Reverse(self.search_score(key)));
+            }
+            self.filtered_keys = keys;
+            self.filtered_for = Some(inputs);
+        }
+        self.filtered_keys.clone()
+    }
+
+    /// Teilt die angezeigten Schlüssel in Favoriten und den Rest, jeweils in
+    /// der bisherigen Reihenfolge
+    fn split_favorites(&self, keys: Vec<String>) -> (Vec<String>, Vec<String>) {
+        keys.into_iter().partition(|key| self.data.is_favorite(key))
+    }
+
+    fn toggle_favorite(&mut self, key: &str) {
+        self.push_undo();
+        if let Some(meta) = self.data.meta.get_mut(key) {
+            meta.favorite = !meta.favorite;
+            self.data.touch();
+        }
+    }
+
+    /// `None` entfernt das Ablaufdatum
+    fn set_expiry(&mut self, key: &str, expires_at: Option<SystemTime>) {
+        self.push_undo();
+        if let Some(meta) = self.data.meta.get_mut(key) {
+            meta.expires_at = expires_at;
+            self.data.touch();
+        }
+    }
+
+    /// Inhalt des ⏰-Menüs einer Zeile: feste Fristen oder ein eigenes Datum
+    fn show_expiry_menu(&mut self, ui: &mut egui::Ui, key: &str) {
+        let current = self.data.expires_at(key);
+        if let Some(expires_at) = current {
+            ui.label(trf("Läuft ab am {}", &[&format_date(expires_at)]));
+            ui.separator();
+        }
+        let now = SystemTime::now();
+        let mut chosen = None;
+        for (label, days) in [("In 30 Tagen", 30), ("In 90 Tagen", 90), ("In 1 Jahr", 365)] {
+            if ui.button(tr(label)).clicked() {
+                chosen = Some(Some(now + Duration::from_secs(days * 86_400)));
+            }
+        }
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.expiry_input)
+                    .hint_text(tr("JJJJ-MM-TT"))
+                    .desired_width(100.0),
+            );
+            let parsed = parse_date(&self.expiry_input);
+            if ui
+                .add_enabled(parsed.is_some(), egui::Button::new(tr("Übernehmen")))
+                .clicked()
+            {
+                chosen = Some(parsed);
+            }
+        });
+        if current.is_some() && ui.button(tr("Kein Ablaufdatum")).clicked() {
+            chosen = Some(None);
+        }
+        if let Some(expires_at) = chosen {
+            self.set_expiry(key, expires_at);
+            self.expiry_input.clear();
+            ui.close_menu();
+        }
+    }
+
+    /// Aktualisiert `reused_values`, sobald sich die Daten geändert haben
+    fn refresh_reused_values(&mut self) {
+        if self.reused_revision != self.data.revision {
+            self.reused_values = find_reused_values(&self.data.items);
+            self.reused_revision = self.data.revision;
+        }
+    }
+
+    /// Sortiert die bereits gefilterten Schlüssel nach `sort_mode`
+    fn sort_keys(&self, keys: &mut [String]) {
+        match self.sort_mode {
+            SortMode::KeyAscending => keys.sort(),
+            SortMode::KeyDescending => keys.sort_by(|a, b| b.cmp(a)),
+            SortMode::ValueAscending => keys.sort_by_cached_key(|key| {
+                let value = self
+                    .data
+                    .items
+                    .get(key)
+                    .map(EntryValue::as_text)
+                    .unwrap_or_default();
+                (value.to_lowercase(), value, key.clone())
+            }),
+            SortMode::RecentlyModified => keys.sort_by_cached_key(|key| {
+                let modified = self.data.meta.get(key).map(|meta| meta.modified);
+                (std::cmp::Reverse(modified), key.clone())
+            }),
+            // Einträge ohne Ablaufdatum ans Ende
+            SortMode::ExpiresSoonest => keys.sort_by_cached_key(|key| {
+                let expires_at = self.data.expires_at(key);
+                (expires_at.is_none(), expires_at, key.clone())
+            }),
+            SortMode::Insertion => {}
+        }
+    }
+
+    /// Übernimmt die Suche erst, wenn seit dem letzten Tastendruck etwas Zeit vergangen ist
+    fn refresh_search_cache(&mut self, current_time: f64) {
+        if self.search_query_cached != self.search_query
+            && current_time >= self.search_debounce_until
+        {
+            self.search_query_cached = self.search_query.clone();
+            self.search_query_lower = self.search_query.to_lowercase();
+        }
+        let compiled_for = self
+            .search_regex_compiled
+            .as_ref()
+            .map(|(source, _)| source);
+        if self.search_regex
+            && !self.search_query_cached.is_empty()
+            && compiled_for != Some(&self.search_query_cached)
+        {
+            let compiled = regex::RegexBuilder::new(&self.search_query_cached)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| e.to_string());
+            self.search_regex_compiled = Some((self.search_query_cached.clone(), compiled));
+        }
+    }
+
+    /// Der übersetzte Ausdruck zur aktuellen Suche, nur im Regex-Modus und wenn
+    /// er gültig ist
+    fn active_search_regex(&self) -> Option<&regex::Regex> {
+        match &self.search_regex_compiled {
+            Some((source, Ok(regex)))
+                if self.search_regex && *source == self.search_query_cached =>
+            {
+                Some(regex)
+            }
+            _ => None,
+        }
+    }
+
+    /// Fehler des Ausdrucks zur aktuellen Suche; gesucht wird dann wörtlich
+    fn search_regex_error(&self) -> Option<&str> {
+        match &self.search_regex_compiled {
+            Some((source, Err(e))) if self.search_regex && *source == self.search_query_cached => {
+                Some(e)
+            }
+            _ => None,
+        }
+    }
+
+    fn search_matches(&self, text: &str) -> bool {
+        match self.active_search_regex() {
+            Some(regex) => regex.is_match(text),
+            None if self.search_fuzzy => fuzzy_score(&self.search_query_lower, text).is_some(),
+            None => text.to_lowercase().contains(&self.search_query_lower),
+        }
+    }
+
+    /// Beste unscharfe Wertung aus Schlüssel und Wert im gewählten Suchbereich
+    fn search_score(&self, key: &str) -> Option<i32> {
+        let key_score = (self.search_scope != SearchScope::Values)
+            .then(|| fuzzy_score(&self.search_query_lower, key))
+            .flatten();
+        let value_score = (self.search_scope != SearchScope::Keys)
+            .then(|| self.data.items.get(key))
+            .flatten()
+            .and_then(|value| fuzzy_score(&self.search_query_lower, &value.as_text()));
+        key_score.max(value_score)
+    }
+
+    fn key_matches_search(&self, key: &str) -> bool {
+        self.search_scope != SearchScope::Values && self.search_matches(key)
+    }
+
+    fn value_matches_search(&self, key: &str) -> bool {
+        self.search_scope != SearchScope::Keys
+            && self
+                .data
+                .items
+                .get(key)
+                .is_some_and(|value| self.search_matches(&value.as_text()))
+    }
+
+    fn set_all_revealed(&mut self, revealed: bool) {
+        self.reveal_deadlines.clear();
+        if revealed {
+            self.revealed_items = self.data.items.keys().cloned().collect();
+        } else {
+            self.revealed_items.clear();
+        }
+    }
+
+    fn toggle_reveal(&mut self, key: &str) {
+        if !self.revealed_items.remove(key) {
+            self.revealed_items.insert(key.to_string());
+        }
+        self.reveal_deadlines.remove(key);
+    }
+
+    /// Kopiert `text`; enthält er den Wert (`secret`), wird die Zwischenablage später geleert
+    fn copy_to_clipboard(
+        &mut self,
+        ui: &egui::Ui,
+        text: String,
+        secret: bool,
+        toast: &str,
+        current_time: f64,
+    ) {
+        ui.output_mut(|o| o.copied_text = text.clone());
+        if secret {
+            self.schedule_clipboard_clear(text, current_time);
+        }
+        self.add_toast(
+            toast,
+            egui::Color32::from_rgb(52, 144, 220),
+            1.5,
+            current_time,
+        );
+    }
+
+    fn schedule_clipboard_clear(&mut self, copied: String, current_time: f64) {
+        self.pending_clipboard_clear = if self.clipboard_clear_seconds > 0.0 {
+            Some((copied, current_time + self.clipboard_clear_seconds))
+        } else {
+            None
+        };
+    }
+
+    /// Leert die Zwischenablage, sofern sie noch den von uns kopierten Wert enthält
+    fn clear_clipboard(&mut self, current_time: f64) {
+        let Some((copied, _)) = self.pending_clipboard_clear.take() else {
+            return;
+        };
+        let Ok(mut clipboard) = arboard::Clipboard::new() else {
+            return;
+        };
+        if clipboard.get_text().is_ok_and(|text| text == copied)
+            && clipboard.set_text(String::new()).is_ok()
+        {
+            self.add_toast(
+                "Zwischenablage geleert",
+                egui::Color32::from_gray(160),
+                1.5,
+                current_time,
+            );
+        }
+    }
+
+    /// Ob ein offener Lösch-Dialog diesen Eintrag betrifft
+    fn delete_pending(&self, key: &str) -> bool {
+        self.delete_candidate.as_deref() == Some(key)
+            || (self.bulk_delete_open && self.selected_keys.contains(key))
+    }
+
+    /// Auto-Save nur bei ungespeicherten Änderungen und ohne offenen Dialog,
+    /// damit nicht mitten in eine Umbenennung oder Löschung gespeichert wird
+    fn auto_save_due(&self, current_time: f64) -> bool {
+        let dialog_open = self.rename_key.is_some()
+            || self.delete_candidate.is_some()
+            || self.bulk_delete_open
+            || self.clear_all_open
+            || self.overwrite_candidate.is_some()
+            || self.password_change_open
+            || self.import_dialog_open
+            || self.pending_import.is_some()
+            || self.login_import.is_some()
+            || self.recovery_json.is_some()
+            || self.close_confirm_open;
+        self.auto_save_enabled
+            && self.dirty
+            && self.key_job.is_none()
+            && matches!(self.screen, Screen::Editor)
+            && !dialog_open
+            && current_time - self.last_auto_save >= self.auto_save_interval
+    }
+
+    fn update_animations(&mut self, ctx: &egui::Context, dt: f32) {
+        // Update login button hover animation
+        self.login_button_hover = (self.login_button_hover + dt * 8.0).min(1.0);
+
+        // Update screen transition
+        if matches!(self.screen, Screen::Editor) {
+            self.screen_transition_progress = (self.screen_transition_progress + dt * 4.0).min(1.0);
+        }
+
+        // Update item hover states
+        for (_, hover_state) in self.item_hover_states.iter_mut() {
+            *hover_state = (*hover_state - dt * 6.0).max(0.0);
+        }
+
+        // Update item delete animations; finished rows are removed for good
+        let mut finished = Vec::new();
+        for (key, progress) in self.item_delete_animations.iter_mut() {
+            *progress = (*progress + dt * 4.0).min(1.0);
+            if *progress >= 1.0 {
+                finished.push(key.clone());
+            }
+        }
+        for key in finished {
+            self.item_delete_animations.remove(&key);
+            self.data.remove_entry(&key);
+        }
+        if !self.item_delete_animations.is_empty() {
+            ctx.request_repaint();
+        }
+
+        let current_time = ctx.input(|i| i.time);
+
+        if self
+            .jump_highlight
+            .as_ref()
+            .is_some_and(|(_, start)| current_time - start >= JUMP_HIGHLIGHT_SECONDS)
+        {
+            self.jump_highlight = None;
+        }
+
+        // Re-mask entries whose temporary reveal has expired
+        let revealed_items = &mut self.revealed_items;
+        self.reveal_deadlines.retain(|key, deadline| {
+            if current_time >= *deadline {
+                revealed_items.remove(key);
+                false
+            } else {
+                true
+            }
+        });
+
+        if self
+            .pending_clipboard_clear
+            .as_ref()
+            .is_some_and(|(_, deadline)| current_time >= *deadline)
+        {
+            self.clear_clipboard(current_time);
+        }
+
+        if matches!(self.screen, Screen::Editor) && self.key_job.is_none() {
+            if current_time - self.last_disk_check >= DISK_CHECK_SECONDS {
+                self.last_disk_check = current_time;
+                let change = self.vault.external_change();
+                if change.is_some() && change != self.external_change_dismissed {
+                    self.external_change = change;
+                }
+            }
+            ctx.request_repaint_after(Duration::from_secs_f64(DISK_CHECK_SECONDS));
+        }
+
+        if self.auto_save_due(current_time) && self.rule_violations() == 0 {
+            self.last_auto_save = current_time;
+            match self.encrypt_data() {
+                Ok(_) => self.add_toast(
+                    "Automatisch gespeichert",
+                    egui::Color32::from_rgb(40, 167, 69),
+                    1.5,
+                    current_time,
+                ),
+                Err(e) => {
+                    self.add_toast(
+                        "Automatisches Speichern fehlgeschlagen",
+                        egui::Color32::from_rgb(220, 53, 69),
+                        3.0,
+                        current_time,
+                    );
+                    self.error_message = format!("❌ {}", e);
+                }
+            }
+        }
+
+        // Update toast messages; queued ones start their timer once they get a slot
+        self.toast_messages
+            .retain_mut(|toast| toast.update(current_time));
+        while self.toast_messages.len() < MAX_VISIBLE_TOASTS {
+            let Some(mut toast) = self.toast_queue.pop_front() else {
+                break;
+            };
+            toast.start_time = current_time;
+            toast.update(current_time);
+            self.toast_messages.push(toast);
+        }
+
+        // Newest toast sits at the edge, older ones slide away by the real heights
+        let mut target = 0.0;
+        for toast in self.toast_messages.iter_mut().rev() {
+            toast.offset += (target - toast.offset) * (dt * 12.0).min(1.0);
+            target += toast.height + TOAST_SPACING;
+        }
+
+        ctx.request_repaint();
+    }
+
+    fn show_toasts(&mut self, ctx: &egui::Context) {
+        for toast in self.toast_messages.iter_mut() {
+            let (align, offset) = self.toast_position.anchor(toast.offset);
+            let alpha = (toast.fade_progress * 255.0) as u8;
+            let bg_color = egui::Color32::from_rgba_unmultiplied(40, 40, 40, alpha);
+            let text_color = egui::Color32::from_rgba_unmultiplied(
+                toast.color.r(),
+                toast.color.g(),
+                toast.color.b(),
+                alpha,
+            );
+
+            let response = egui::Window::new("toast")
+                .id(egui::Id::new(("toast", toast.id)))
+                .title_bar(false)
+                .resizable(false)
+                .collapsible(false)
+                .max_width(360.0)
+                .anchor(align, offset)
+                .frame(
+                    egui::Frame::popup(&ctx.style())
+                        .fill(bg_color)
+                        .rounding(egui::Rounding::same(8.0))
+                        .shadow(egui::epaint::Shadow {
+                            offset: egui::vec2(0.0, 2.0),
+                            blur: 8.0,
+                            spread: 0.0,
+                            color: egui::Color32::from_black_alpha(50),
+                        }),
+                )
+                .show(ctx, |ui| {
+                    ui.horizontal_top(|ui| {
+                        ui.add_space(4.0);
+                        ui.colored_label(text_color, "●");
+                        ui.add(
+                            egui::Label::new(egui::RichText::new(&toast.text).color(text_color))
+                                .wrap(),
+                        );
+                        ui.add_space(4.0);
+                    });
+                });
+            if let Some(response) = response {
+                toast.height = response.response.rect.height();
+            }
+        }
+    }
+
+    fn show_delete_confirm_dialog(&mut self, ctx: &egui::Context) {
+        if let Some(key) = self.delete_candidate.clone() {
+            egui::Window::new(tr("Löschen bestätigen"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+                .frame(
+                    egui::Frame::window(&ctx.style())
+                        .rounding(egui::Rounding::same(12.0))
+                        .shadow(egui::epaint::Shadow {
+                            offset: egui::vec2(0.0, 4.0),
+                            blur: 16.0,
+                            spread: 0.0,
+                            color: egui::Color32::from_black_alpha(100),
+                        }),
+                )
+                .show(ctx, |ui| {
+                    ui.add_space(8.0);
+                    ui.label(trf("Eintrag \"{}\" wirklich löschen?", &[&key]));
+                    let is_critical = self.data.critical.contains(&key);
+                    if is_critical {
+                        ui.add_space(8.0);
+                        ui.colored_label(
+                            egui::Color32::from_rgb(220, 53, 69),
+                            tr("Dieser Eintrag ist als kritisch markiert."),
+                        );
+                        ui.label(tr("Zur Bestätigung den Schlüssel exakt eintippen:"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.delete_confirm_text)
+                                .hint_text(key.as_str()),
+                        );
+                    }
+                    ui.add_space(12.0);
+                    ui.horizontal(|ui| {
+                        let can_delete = !is_critical || self.delete_confirm_text == key;
+                        if ui
+                            .add_enabled(
+                                can_delete,
+                                egui::Button::new(tr("Löschen"))
+                                    .fill(egui::Color32::from_rgb(220, 53, 69))
+                                    .rounding(egui::Rounding::same(6.0)),
+                            )
+                            .clicked()
+                        {
+                            self.delete_candidate = None;
+                            self.delete_confirm_text.clear();
+                            let current_time = ctx.input(|i| i.time);
+                            self.delete_entries(std::slice::from_ref(&key), current_time);
+                        }
+                        if ui
+                            .add(
+                                egui::Button::new(tr("Abbrechen"))
+                                    .fill(egui::Color32::from_rgb(108, 117, 125))
+                                    .rounding(egui::Rounding::same(6.0)),
+                            )
+                            .clicked()
+                        {
+                            self.delete_candidate = None;
+                            self.delete_confirm_text.clear();
+                        }
+                    });
+                    ui.add_space(4.0);
+                });
+        }
+    }
+
+    /// Löscht die Einträge als einen Undo-Schritt, mit Animation und einem Toast
+    fn delete_entries(&mut self, keys: &[String], current_time: f64) {
+        if keys.is_empty() {
+            return;
+        }
+        self.finish_pending_deletes();
+        self.push_undo();
+        for key in keys {
+            // Entfernt wird erst, wenn die Animation in `update_animations` fertig ist
+            self.item_delete_animations.insert(key.clone(), 0.0);
+            self.revealed_items.remove(key);
+            self.expanded_values.remove(key);
+            self.reveal_deadlines.remove(key);
+            self.selected_keys.remove(key);
+        }
+        let text = if keys.len() == 1 {
+            tr("Eintrag gelöscht").to_string()
+        } else {
+            trf("{} Einträge gelöscht", &[&keys.len()])
+        };
+        self.add_toast(
+            &text,
+            egui::Color32::from_rgb(220, 53, 69),
+            2.0,
+            current_time,
+        );
+    }
+
+    /// Leert den Vault. Mit `backup` wird vorher der aktuelle Stand gespeichert und
+    /// verschlüsselt in den Backup-Ordner (ohne Ordner: neben den Vault) kopiert;
+    /// scheitert das, bleibt alles unverändert. Rückgängig machen geht trotzdem.
+    fn clear_all_entries(&mut self, backup: bool, current_time: f64) {
+        if backup {
+            let dir = self
+                .backup_dir
+                .clone()
+                .or_else(|| self.vault.path.parent().map(Path::to_path_buf))
+                .unwrap_or_default();
+            let saved = if self.dirty {
+                self.encrypt_data()
+            } else {
+                Ok(())
+            };
+            let copied = saved.and_then(|_| {
+                self.vault
+                    .copy_to_backup_dir(&dir, self.backup_dir_keep, SystemTime::now())
+            });
+            if let Err(e) = copied {
+                self.add_toast(
+                    "Sicherung fehlgeschlagen",
+                    egui::Color32::from_rgb(220, 53, 69),
+                    3.0,
+                    current_time,
+                );
+                self.error_message = trf("❌ Sicherung fehlgeschlagen, nichts gelöscht: {}", &[&e]);
+                return;
+            }
+        }
+        self.finish_pending_deletes();
+        self.push_undo();
+        let count = self.data.items.len();
+        self.data.items.clear();
+        self.data.meta.clear();
+        self.data.critical.clear();
+        self.data.touch();
+        self.revealed_items.clear();
+        self.expanded_values.clear();
+        self.reveal_deadlines.clear();
+        self.selected_keys.clear();
+        self.revalidate_all();
+        self.add_toast(
+            &trf("{} Einträge gelöscht", &[&count]),
+            egui::Color32::from_rgb(220, 53, 69),
+            2.0,
+            current_time,
+        );
+    }
+
+    fn show_clear_all_dialog(&mut self, ctx: &egui::Context) {
+        if !self.clear_all_open {
+            return;
+        }
+        let confirm_word = tr("LÖSCHEN");
+        let vault_name = tab_label(&self.vault.path);
+        egui::Window::new(tr("Alle Einträge löschen"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.colored_label(
+                    egui::Color32::from_rgb(220, 53, 69),
+                    trf(
+                        "Alle {} Einträge aus \"{}\" samt Notizen, Tags und Verlauf löschen?",
+                        &[&self.data.items.len(), &vault_name],
+                    ),
+                );
+                ui.add_space(8.0);
+                ui.checkbox(
+                    &mut self.clear_all_backup,
+                    tr("Vorher verschlüsselte Sicherung anlegen"),
+                );
+                ui.label(
+                    egui::RichText::new(match &self.backup_dir {
+                        Some(dir) => trf("Im Backup-Ordner {}", &[&dir.display()]),
+                        None => tr("Neben dem Vault, mit Zeitstempel im Namen").to_string(),
+                    })
+                    .size(12.0)
+                    .color(egui::Color32::from_gray(140)),
+                );
+                ui.add_space(8.0);
+                ui.label(trf(
+                    "Zur Bestätigung \"{}\" oder den Vault-Namen \"{}\" eintippen:",
+                    &[&confirm_word, &vault_name],
+                ));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.delete_confirm_text)
+                        .hint_text(confirm_word),
+                );
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    let typed = self.delete_confirm_text.trim();
+                    let can_clear = typed == confirm_word || typed == vault_name;
+                    if ui
+                        .add_enabled(
+                            can_clear,
+                            egui::Button::new(tr("Alle löschen"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.clear_all_open = false;
+                        self.delete_confirm_text.clear();
+                        let current_time = ctx.input(|i| i.time);
+                        self.clear_all_entries(self.clear_all_backup, current_time);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.clear_all_open = false;
+                        self.delete_confirm_text.clear();
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_bulk_delete_dialog(&mut self, ctx: &egui::Context) {
+        if !self.bulk_delete_open {
+            return;
+        }
+        let keys: Vec<String> = self
+            .data
+            .items
+            .keys()
+            .filter(|key| self.selected_keys.contains(*key))
+            .cloned()
+            .collect();
+        let critical = keys
+            .iter()
+            .filter(|key| self.data.critical.contains(*key))
+            .count();
+        let confirm_word = tr("löschen");
+        egui::Window::new(tr("Ausgewählte löschen"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(trf("{} Einträge wirklich löschen?", &[&keys.len()]));
+                if critical > 0 {
+                    ui.add_space(8.0);
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 53, 69),
+                        trf("Davon sind {} als kritisch markiert.", &[&critical]),
+                    );
+                    ui.label(trf("Zur Bestätigung \"{}\" eintippen:", &[&confirm_word]));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.delete_confirm_text)
+                            .hint_text(confirm_word),
+                    );
+                }
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    let can_delete = !keys.is_empty()
+                        && (critical == 0 || self.delete_confirm_text == confirm_word);
+                    if ui
+                        .add_enabled(
+                            can_delete,
+                            egui::Button::new(tr("Löschen"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.bulk_delete_open = false;
+                        self.delete_confirm_text.clear();
+                        let current_time = ctx.input(|i| i.time);
+                        self.delete_entries(&keys, current_time);
+                        self.selected_keys.clear();
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.bulk_delete_open = false;
+                        self.delete_confirm_text.clear();
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_share_export_dialog(&mut self, ctx: &egui::Context) {
+        if !self.share_export_open {
+            return;
+        }
+        let keys: Vec<String> = self
+            .data
+            .items
+            .keys()
+            .filter(|key| self.selected_keys.contains(*key))
+            .cloned()
+            .collect();
+        egui::Window::new(tr("Auswahl als Vault teilen"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(trf(
+                    "Nur diese {} Einträge kommen in die Datei:",
+                    &[&keys.len()],
+                ));
+                egui::ScrollArea::vertical()
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        for key in &keys {
+                            ui.label(format!("• {}", key));
+                        }
+                    });
+                ui.add_space(8.0);
+                ui.label(tr("Passwort für die Datei (nicht dein Master-Passwort):"));
+                ui.add(egui::TextEdit::singleline(&mut self.share_password).password(true));
+                ui.label(tr("Passwort wiederholen:"));
+                ui.add(egui::TextEdit::singleline(&mut self.share_password_repeat).password(true));
+                ui.label(
+                    egui::RichText::new(tr(
+                        "Öffnen lässt sich die Datei mit dieser App und dem Passwort",
+                    ))
+                    .size(12.0)
+                    .color(egui::Color32::from_gray(140)),
+                );
+                if !self.share_error.is_empty() {
+                    ui.colored_label(egui::Color32::from_rgb(220, 53, 69), &self.share_error);
+                }
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    let can_export = !keys.is_empty()
+                        && !self.share_password.is_empty()
+                        && self.share_password == self.share_password_repeat;
+                    if ui
+                        .add_enabled(
+                            can_export,
+                            egui::Button::new(tr("Exportieren…"))
+                                .fill(egui::Color32::from_rgb(40, 167, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .set_file_name("geteilt.enc")
+                            .add_filter("Vault", &["enc"])
+                            .save_file()
+                        {
+                            let current_time = ctx.input(|i| i.time);
+                            match self.export_selection_to(&path) {
+                                Ok(count) => {
+                                    self.close_share_export_dialog();
+                                    self.add_toast(
+                                        &trf("{} Einträge als Vault exportiert", &[&count]),
+                                        egui::Color32::from_rgb(52, 144, 220),
+                                        2.0,
+                                        current_time,
+                                    );
+                                }
+                                Err(e) => {
+                                    self.share_error = trf("Export fehlgeschlagen: {}", &[&e])
+                                }
+                            }
+                        }
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.close_share_export_dialog();
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_overwrite_confirm_dialog(&mut self, ctx: &egui::Context) {
+        let Some(key) = self.overwrite_candidate.clone() else {
+            return;
+        };
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(tr("Überschreiben bestätigen"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(trf(
+                    "Schlüssel \"{}\" existiert bereits — überschreiben?",
+                    &[&key],
+                ));
+                if self.data.critical.contains(&key) {
+                    ui.add_space(8.0);
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 53, 69),
+                        tr("Dieser Eintrag ist als kritisch markiert."),
+                    );
+                }
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Überschreiben"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.add_new_entry(current_time, true);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.overwrite_candidate = None;
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_external_change_dialog(&mut self, ctx: &egui::Context) {
+        let Some(modified) = self.external_change else {
+            return;
+        };
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(tr("Datei von außen geändert"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(trf(
+                    "{} wurde von einem anderen Programm geändert ({}).",
+                    &[
+                        &tab_label(&self.vault.path),
+                        &format_relative_time(modified, SystemTime::now()),
+                    ],
+                ));
+                ui.label(tr("Speichern würde diese Änderungen überschreiben."));
+                ui.add_space(4.0);
+                for hint in [
+                    "Neu laden: eigene Änderungen verwerfen (Rückgängig möglich)",
+                    "Zusammenführen: abweichende Einträge aus der Datei übernehmen",
+                    "Überschreiben: den eigenen Stand speichern",
+                ] {
+                    ui.label(
+                        egui::RichText::new(tr(hint))
+                            .size(12.0)
+                            .color(egui::Color32::from_gray(140)),
+                    );
+                }
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Neu laden"))
+                                .fill(egui::Color32::from_rgb(52, 144, 220))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.reload_from_disk(KeyJobKind::Reload);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Zusammenführen"))
+                                .fill(egui::Color32::from_rgb(40, 167, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.reload_from_disk(KeyJobKind::Merge);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Überschreiben"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.external_change = None;
+                        self.vault.disk_modified = Some(modified);
+                        self.save(current_time, false);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Später"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.external_change = None;
+                        self.external_change_dismissed = Some(modified);
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_validation_save_dialog(&mut self, ctx: &egui::Context) {
+        if !self.validation_save_prompt {
+            return;
+        }
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(tr("Prüfregeln verletzt"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(trf(
+                    "{} Einträge verletzen ihre Prüfregel:",
+                    &[&self.validation_errors.len()],
+                ));
+                let mut violations: Vec<_> = self.validation_errors.iter().collect();
+                violations.sort();
+                for (key, error) in violations.iter().take(5) {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 53, 69),
+                        format!("• {}: {}", key, error),
+                    );
+                }
+                if violations.len() > 5 {
+                    ui.label(trf("… und {} weitere", &[&(violations.len() - 5)]));
+                }
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Trotzdem speichern"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.save(current_time, true);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.validation_save_prompt = false;
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    /// Fängt das Schließen des Fensters ab, solange ungespeicherte Änderungen bestehen
+    fn handle_close_request(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.viewport().close_requested())
+            && self.any_unsaved()
+            && !self.close_confirmed
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.close_confirm_open = true;
+        }
+    }
+
+    /// Tableiste über allen Bildschirmen, solange irgendein Vault entsperrt ist;
+    /// im Editor immer, damit sich ein weiterer öffnen lässt. Nach dem Sperren
+    /// bleibt sie verborgen, bis wieder ein Vault entsperrt wurde.
+    fn show_tab_bar(&mut self, ctx: &egui::Context) {
+        let unlocked = matches!(self.screen, Screen::Editor)
+            || self
+                .tabs
+                .iter()
+                .flatten()
+                .any(|session| matches!(session.screen, Screen::Editor));
+        if !unlocked {
+            return;
+        }
+        let active = self.active_tab();
+        let mut switch_to = None;
+        let mut close = None;
+        let mut open = None;
+        egui::TopBottomPanel::top("vault_tabs").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                for (index, tab) in self.tabs.iter().enumerate() {
+                    let (path, dirty) = match tab {
+                        Some(session) => (&session.vault.path, session.dirty),
+                        None => (&self.vault.path, self.dirty),
+                    };
+                    let mut label = tab_label(path);
+                    if dirty {
+                        label.push_str(" ●");
+                    }
+                    if ui
+                        .selectable_label(index == active, label)
+                        .on_hover_text(path.display().to_string())
+                        .clicked()
+                    {
+                        switch_to = Some(index);
+                    }
+                    if self.tabs.len() > 1
+                        && ui
+                            .small_button("✕")
+                            .on_hover_text(tr("Tab schließen"))
+                            .clicked()
+                    {
+                        close = Some(index);
+                    }
+                    ui.separator();
+                }
+                ui.menu_button("➕", |ui| {
+                    if ui.button(tr("📂 Vault öffnen…")).clicked() {
+                        ui.close_menu();
+                        open = rfd::FileDialog::new()
+                            .add_filter("Vault", &["enc", "vault"])
+                            .pick_file();
+                    }
+                    if ui.button(tr("✨ Neuer Vault…")).clicked() {
+                        ui.close_menu();
+                        open = rfd::FileDialog::new()
+                            .add_filter("Vault", &["enc"])
+                            .set_file_name(ENCRYPTED_FILE)
+                            .save_file();
+                    }
+                })
+                .response
+                .on_hover_text(tr("Weiteren Vault in neuem Tab öffnen"));
+            });
+        });
+        if let Some(index) = switch_to {
+            self.switch_tab(index);
+        }
+        if let Some(index) = close {
+            self.request_close_tab(index);
+        }
+        if let Some(path) = open {
+            self.open_in_new_tab(path, VaultPathSource::FileDialog);
+        }
+    }
+
+    fn show_tab_close_dialog(&mut self, ctx: &egui::Context) {
+        if !self.tab_close_prompt {
+            return;
+        }
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(tr("Tab schließen"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(trf(
+                    "\"{}\" hat ungespeicherte Änderungen. Vor dem Schließen speichern?",
+                    &[&tab_label(&self.vault.path)],
+                ));
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("💾 Speichern"))
+                                .fill(egui::Color32::from_rgb(40, 167, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        match self.encrypt_data() {
+                            Ok(_) => self.close_active_tab(),
+                            Err(e) => {
+                                self.add_toast(
+                                    "Fehler beim Speichern",
+                                    egui::Color32::from_rgb(220, 53, 69),
+                                    3.0,
+                                    current_time,
+                                );
+                                self.error_message = format!("❌ {}", e);
+                            }
+                        }
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Verwerfen"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.close_active_tab();
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.tab_close_prompt = false;
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_close_confirm_dialog(&mut self, ctx: &egui::Context) {
+        if !self.close_confirm_open {
+            return;
+        }
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(tr("Ungespeicherte Änderungen"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(tr(
+                    "Es gibt ungespeicherte Änderungen. Vor dem Beenden speichern?",
+                ));
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("💾 Speichern"))
+                                .fill(egui::Color32::from_rgb(40, 167, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        let saved = if self.dirty {
+                            self.encrypt_data()
+                        } else {
+                            Ok(())
+                        };
+                        match saved.and_then(|_| self.save_parked_sessions()) {
+                            Ok(_) => {
+                                self.close_confirm_open = false;
+                                self.close_confirmed = true;
+                                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                            }
+                            Err(e) => {
+                                self.add_toast(
+                                    "Fehler beim Speichern",
+                                    egui::Color32::from_rgb(220, 53, 69),
+                                    3.0,
+                                    current_time,
+                                );
+                                self.error_message = format!("❌ {}", e);
+                            }
+                        }
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Verwerfen"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.close_confirm_open = false;
+                        self.close_confirmed = true;
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.close_confirm_open = false;
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_export_confirm_dialog(&mut self, ctx: &egui::Context) {
+        let Some(format) = self.export_confirm else {
+            return;
+        };
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(trf("Klartext exportieren ({})", &[&format.label()]))
+            .id(egui::Id::new("export_confirm"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.colored_label(
+                    egui::Color32::from_rgb(220, 53, 69),
+                    tr("⚠ Alle Einträge werden UNVERSCHLÜSSELT gespeichert."),
+                );
+                ui.label(tr(
+                    "Jeder mit Zugriff auf die Datei kann die Geheimnisse lesen.",
+                ));
+                if format == PlaintextFormat::Json {
+                    ui.checkbox(&mut self.export_history, tr("Frühere Werte mitexportieren"));
+                }
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Trotzdem exportieren"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.export_confirm = None;
+                        self.export_file(format, current_time);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.export_confirm = None;
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_password_change_dialog(&mut self, ctx: &egui::Context) {
+        if !self.password_change_open {
+            return;
+        }
+        egui::Window::new(tr("Passwort ändern"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                egui::Grid::new("password_change_grid")
+                    .num_columns(2)
+                    .spacing([8.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label(tr("Aktuelles Passwort:"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.password_change_current)
+                                .password(true),
+                        );
+                        ui.end_row();
+                        ui.label(tr("Neues Passwort:"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.password_change_new)
+                                .password(true),
+                        );
+                        ui.end_row();
+                        ui.label(tr("Wiederholen:"));
+                        ui.add(
+                            egui::TextEdit::singleline(&mut self.password_change_repeat)
+                                .password(true),
+                        );
+                        ui.end_row();
+                        ui.label(tr("Schlüsseldatei:"));
+                        show_key_file_picker(ui, &mut self.password_change_key_file, false);
+                        ui.end_row();
+                    });
+                show_policy_hint(ui, self.active_policy(), &self.password_change_new);
+
+                if !self.password_change_error.is_empty() {
+                    ui.add_space(8.0);
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 53, 69),
+                        format!("❌ {}", tr(&self.password_change_error)),
+                    );
+                }
+
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            self.key_job.is_none(),
+                            egui::Button::new(tr("Ändern"))
+                                .fill(egui::Color32::from_rgb(40, 167, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.change_password();
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.close_password_change_dialog();
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    /// Der entschlüsselte Vault als JSON, so wie er gespeichert würde. Mit `redact`
+    /// bleiben nur Werte lesbar, die im Editor gerade aufgedeckt sind.
+    fn source_json(&self, redact: bool) -> String {
+        let mut data = self.data.clone();
+        if redact {
+            for (key, value) in data.items.iter_mut() {
+                if self.value_masked(key) {
+                    *value = EntryValue::Text("••••••".into());
+                    if let Some(meta) = data.meta.get_mut(key) {
+                        for (_, old) in meta.history.iter_mut() {
+                            *old = EntryValue::Text("••••••".into());
+                        }
+                    }
+                }
+            }
+        }
+        serde_json::to_string_pretty(&data).unwrap_or_default()
+    }
+
+    fn show_json_source_dialog(&mut self, ctx: &egui::Context) {
+        if !self.json_source_open {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new(tr("{ } JSON-Quelltext"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(true)
+            .default_size(egui::vec2(520.0, 480.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.colored_label(
+                    egui::Color32::from_rgb(220, 53, 69),
+                    tr("⚠ Klartext – nur lesen, Änderungen im Editor vornehmen"),
+                );
+                ui.checkbox(
+                    &mut self.json_source_redact,
+                    tr("Verdeckte Werte schwärzen"),
+                );
+                ui.add_space(4.0);
+                let source = self.source_json(self.json_source_redact);
+                egui::ScrollArea::both().show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut source.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            });
+        if !open {
+            self.json_source_open = false;
+        }
+    }
+
+    /// Pfeiltasten wählen, Enter führt aus; der Fokus bleibt im Suchfeld
+    fn show_command_palette(&mut self, ctx: &egui::Context) {
+        if !self.palette_open || !matches!(self.screen, Screen::Editor) {
+            return;
+        }
+        let current_time = ctx.input(|i| i.time);
+        let keys: Vec<String> = self.data.items.keys().cloned().collect();
+        let matches = palette_matches(&self.palette_query, &keys, self.read_only);
+        let (down, up, enter) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            )
+        });
+        if down {
+            self.palette_selected += 1;
+        }
+        if up {
+            self.palette_selected = self.palette_selected.saturating_sub(1);
+        }
+        self.palette_selected = self.palette_selected.min(matches.len().saturating_sub(1));
+        let mut chosen = enter
+            .then(|| matches.get(self.palette_selected).cloned())
+            .flatten();
+
+        egui::Window::new(tr("Befehlspalette"))
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .fixed_size(egui::vec2(420.0, 0.0))
+            .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 80.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.palette_query)
+                        .hint_text(tr("Schlüssel oder Befehl suchen..."))
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    self.palette_selected = 0;
+                }
+                ui.add_space(6.0);
+                if matches.is_empty() {
+                    ui.label(
+                        egui::RichText::new(tr("Keine Treffer"))
+                            .size(12.0)
+                            .color(egui::Color32::from_gray(140)),
+                    );
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| {
+                        for (index, item) in matches.iter().enumerate() {
+                            let label = match item {
+                                PaletteItem::Command(command) => format!("⚡ {}", command.label()),
+                                PaletteItem::Key(key) => format!("🔑 {}", key),
+                            };
+                            let selected = index == self.palette_selected;
+                            let row = ui.add_sized(
+                                [ui.available_width(), 22.0],
+                                egui::SelectableLabel::new(selected, label),
+                            );
+                            if selected && (up || down) {
+                                row.scroll_to_me(None);
+                            }
+                            if row.clicked() {
+                                chosen = Some(item.clone());
+                            }
+                        }
+                    });
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(tr("↑↓ auswählen · Enter ausführen · Esc schließen"))
+                        .size(12.0)
+                        .color(egui::Color32::from_gray(140)),
+                );
+            });
+        if let Some(item) = chosen {
+            self.run_palette_item(item, current_time);
+        }
+    }
+
+    fn show_help_dialog(&mut self, ctx: &egui::Context) {
+        if !self.help_open {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new(tr("❓ Tastenkürzel"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-16.0, 64.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                egui::Grid::new("shortcuts_grid")
+                    .num_columns(2)
+                    .spacing([16.0, 6.0])
+                    .show(ui, |ui| {
+                        for (keys, action) in [
+                            ("Strg+S", "Speichern"),
+                            ("Strg+F", "Suche fokussieren"),
+                            ("Strg+P", "Befehlspalette"),
+                            ("Strg+Z", "Rückgängig"),
+                            ("Strg+Y", "Wiederholen"),
+                            ("Escape", "Dialog schließen"),
+                            ("Enter", "Eingabe bestätigen"),
+                        ] {
+                            ui.strong(tr(keys));
+                            ui.label(tr(action));
+                            ui.end_row();
+                        }
+                        if parse_shortcut(&self.panic_shortcut).is_some() {
+                            ui.strong(&self.panic_shortcut);
+                            ui.label(tr("Sofort sperren und verdecken"));
+                            ui.end_row();
+                        }
+                    });
+            });
+        if !open {
+            self.help_open = false;
+        }
+    }
+
+    fn open_about(&mut self) {
+        self.about_file = self.vault.file_info();
+        self.about_open = true;
+    }
+
+    fn show_about_dialog(&mut self, ctx: &egui::Context) {
+        if !self.about_open {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new(tr("ℹ Über"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-16.0, 64.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.strong("Encrypted JSON Editor");
+                ui.add_space(4.0);
+                egui::Grid::new("about_build_grid")
+                    .num_columns(2)
+                    .spacing([16.0, 6.0])
+                    .show(ui, |ui| {
+                        for (label, value) in build_info() {
+                            ui.label(tr(label));
+                            ui.label(value);
+                            ui.end_row();
+                        }
+                    });
+                ui.add_space(8.0);
+                ui.separator();
+                ui.label(
+                    egui::RichText::new(tab_label(&self.vault.path))
+                        .strong()
+                        .size(13.0),
+                )
+                .on_hover_text(self.vault.path.display().to_string());
+                match &self.about_file {
+                    Some((version, header)) => {
+                        egui::Grid::new("about_file_grid")
+                            .num_columns(2)
+                            .spacing([16.0, 6.0])
+                            .show(ui, |ui| {
+                                ui.label(tr("Dateiformat"));
+                                if *version == 0 {
+                                    ui.label(tr("älteres Format ohne Kennung"));
+                                } else {
+                                    ui.label(version.to_string());
+                                }
+                                ui.end_row();
+                                ui.label(tr("Verschlüsselung"));
+                                ui.label(header.cipher.label());
+                                ui.end_row();
+                                ui.label(tr("Schlüsselableitung"));
+                                ui.label(header.kdf.describe());
+                                ui.end_row();
+                            });
+                    }
+                    None => {
+                        ui.label(
+                            egui::RichText::new(tr("Noch keine Datei gespeichert"))
+                                .size(12.0)
+                                .color(egui::Color32::from_gray(140)),
+                        );
+                    }
+                }
+            });
+        if !open {
+            self.about_open = false;
+        }
+    }
+
+    fn show_settings_dialog(&mut self, ctx: &egui::Context) {
+        if !self.settings_open {
+            return;
+        }
+        let mut open = true;
+        let before = self.settings();
+        egui::Window::new(tr("⚙ Einstellungen"))
+            .open(&mut open)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                egui::Grid::new("settings_grid")
+                    .num_columns(2)
+                    .spacing([12.0, 8.0])
+                    .show(ui, |ui| {
+                        ui.label(tr("Design:"));
+                        egui::ComboBox::from_id_source("theme")
+                            .selected_text(self.theme.label())
+                            .show_ui(ui, |ui| {
+                                for theme in [Theme::Dark, Theme::Light] {
+                                    ui.selectable_value(&mut self.theme, theme, theme.label());
+                                }
+                            });
+                        ui.end_row();
+                        ui.label(tr("Meldungen:"));
+                        ui.horizontal(|ui| {
+                            egui::ComboBox::from_id_source("toast_position")
+                                .selected_text(self.toast_position.label())
+                                .show_ui(ui, |ui| {
+                                    for position in ToastPosition::ALL {
+                                        ui.selectable_value(
+                                            &mut self.toast_position,
+                                            position,
+                                            position.label(),
+                                        );
+                                    }
+                                });
+                            ui.add(
+                                egui::DragValue::new(&mut self.toast_duration_factor)
+                                    .range(0.25..=3.0)
+                                    .speed(0.05)
+                                    .fixed_decimals(2)
+                                    .suffix("×"),
+                            )
+                            .on_hover_text(tr("Anzeigedauer im Verhältnis zum Standard"));
+                        });
+                        ui.end_row();
+                        ui.label(tr("Zwischenablage leeren nach:"));
+                        ui.add(
+                            egui::DragValue::new(&mut self.clipboard_clear_seconds)
+                                .range(0.0..=600.0)
+                                .speed(1.0)
+                                .suffix(" s"),
+                        )
+                        .on_hover_text(tr("0 = nie automatisch leeren"));
+                        ui.end_row();
+                        ui.label(tr("Verdeckte Werte:"));
+                        ui.checkbox(&mut self.reveal_on_hover, tr("Beim Überfahren anzeigen"))
+                            .on_hover_text(tr("Aus: Werte nur per 👁 aufdecken"));
+                        ui.end_row();
+                        ui.label(tr("Gleiche Werte:"));
+                        ui.checkbox(&mut self.reuse_warnings, tr("Mehrfach verwendete markieren"));
+                        ui.end_row();
+                        ui.label(tr("Leerzeichen:"));
+                        ui.checkbox(
+                            &mut self.auto_trim_values,
+                            tr("Beim Einfügen und Hinzufügen entfernen"),
+                        )
+                        .on_hover_text(tr(
+                            "Entfernt Leerzeichen und Zeilenumbrüche am Anfang und Ende",
+                        ));
+                        ui.end_row();
+                        ui.label(tr("Neue Einträge:"));
+                        if ui
+                            .checkbox(&mut self.key_suggestions, tr("Schlüssel vorschlagen"))
+                            .on_hover_text(tr(
+                                "Erkennt bekannte Token-Formate im Wert und merkt sich \
+                                 zuletzt verwendete Schlüsselnamen",
+                            ))
+                            .changed()
+                            && !self.key_suggestions
+                        {
+                            self.recent_key_names.clear();
+                        }
+                        ui.end_row();
+                        ui.label(tr("Anzahl Backups:"));
+                        ui.add(egui::DragValue::new(&mut self.backup_count).range(0..=20))
+                            .on_hover_text(tr("Rotierende .bak-Kopien vor jedem Speichern"));
+                        ui.end_row();
+                        ui.label(tr("Backup-Ordner:"));
+                        ui.vertical(|ui| {
+                            ui.horizontal(|ui| {
+                                let shown = self.backup_dir.as_ref().map_or_else(
+                                    || tr("Keiner").to_string(),
+                                    |dir| dir.display().to_string(),
+                                );
+                                ui.label(shown);
+                                if ui.button(tr("📂 Auswählen")).clicked() {
+                                    if let Some(dir) = rfd::FileDialog::new().pick_folder() {
+                                        self.backup_dir = Some(dir);
+                                    }
+                                }
+                                if self.backup_dir.is_some()
+                                    && ui
+                                        .small_button("✕")
+                                        .on_hover_text(tr("Keine Kopien mehr anlegen"))
+                                        .clicked()
+                                {
+                                    self.backup_dir = None;
+                                }
+                            });
+                            if self.backup_dir.is_some() {
+                                ui.horizontal(|ui| {
+                                    ui.label(tr("Behalten:"));
+                                    ui.add(
+                                        egui::DragValue::new(&mut self.backup_dir_keep)
+                                            .range(1..=500),
+                                    );
+                                    ui.label(tr("Höchstens alle"));
+                                    ui.add(
+                                        egui::DragValue::new(
+                                            &mut self.backup_dir_interval_minutes,
+                                        )
+                                        .range(0..=24 * 60)
+                                        .suffix(" min"),
+                                    )
+                                    .on_hover_text(tr("0 = bei jedem Speichern"));
+                                });
+                            }
+                            ui.label(
+                                egui::RichText::new(tr(
+                                    "Verschlüsselte Kopien mit Zeitstempel, z.B. in einem synchronisierten Ordner",
+                                ))
+                                .size(12.0)
+                                .color(egui::Color32::from_gray(140)),
+                            );
+                        });
+                        ui.end_row();
+                        ui.label(tr("Passwort-Richtlinie:"));
+                        ui.vertical(|ui| {
+                            let locked = self.policy_override.is_some();
+                            let policy = match &mut self.policy_override {
+                                Some(policy) => policy,
+                                None => &mut self.password_policy,
+                            };
+                            ui.add_enabled_ui(!locked, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label(tr("Mindestlänge:"));
+                                    ui.add(
+                                        egui::DragValue::new(&mut policy.min_length)
+                                            .range(0..=128),
+                                    )
+                                    .on_hover_text(tr("0 = keine Vorgabe"));
+                                    ui.checkbox(&mut policy.require_lowercase, "a-z");
+                                    ui.checkbox(&mut policy.require_uppercase, "A-Z");
+                                    ui.checkbox(&mut policy.require_digit, "0-9");
+                                    ui.checkbox(&mut policy.require_symbol, "#?!");
+                                });
+                            });
+                            ui.label(
+                                egui::RichText::new(tr(if locked {
+                                    "Durch --policy vorgegeben"
+                                } else {
+                                    "Gilt für neue Vaults und Passwortwechsel, nicht beim Entsperren"
+                                }))
+                                .size(12.0)
+                                .color(egui::Color32::from_gray(140)),
+                            );
+                        });
+                        ui.end_row();
+                        ui.label(tr("Verlauf je Eintrag:"));
+                        ui.add(egui::DragValue::new(&mut self.history_limit).range(0..=100))
+                            .on_hover_text(tr("Frühere Werte, gekürzt beim Speichern"));
+                        ui.end_row();
+                        ui.label(tr("Schlüsselableitung:"));
+                        ui.vertical(|ui| {
+                            let picked = show_kdf_editor(
+                                ui,
+                                "settings_kdf",
+                                &mut self.kdf_params,
+                                &mut self.kdf_custom_open,
+                            );
+                            let differs = matches!(self.screen, Screen::Editor)
+                                && !self.read_only
+                                && self.vault.header.kdf != self.kdf_params;
+                            if differs && picked {
+                                self.start_rekey();
+                            } else if differs {
+                                ui.label(
+                                    egui::RichText::new(trf(
+                                        "Dieser Vault nutzt noch: {}",
+                                        &[&KdfPreset::of(&self.vault.header.kdf).label()],
+                                    ))
+                                    .size(12.0)
+                                    .color(egui::Color32::from_gray(140)),
+                                );
+                                if ui
+                                    .add_enabled(
+                                        self.key_job.is_none(),
+                                        egui::Button::new(tr("🔑 Neu verschlüsseln"))
+                                            .fill(egui::Color32::from_rgb(52, 144, 220))
+                                            .rounding(egui::Rounding::same(6.0)),
+                                    )
+                                    .on_hover_text(tr(
+                                        "Leitet den Schlüssel mit diesen Werten neu ab und speichert",
+                                    ))
+                                    .clicked()
+                                {
+                                    self.start_rekey();
+                                }
+                            }
+                        });
+                        ui.end_row();
+                        ui.label(tr("Panik-Sperre:"));
+                        ui.horizontal(|ui| {
+                            let valid = self.panic_shortcut.trim().is_empty()
+                                || parse_shortcut(&self.panic_shortcut).is_some();
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.panic_shortcut)
+                                    .hint_text(tr("aus"))
+                                    .text_color_opt(
+                                        (!valid).then_some(egui::Color32::from_rgb(220, 53, 69)),
+                                    )
+                                    .desired_width(90.0),
+                            )
+                            .on_hover_text(tr(
+                                "Z.B. Ctrl+L oder Ctrl+Shift+F12; leer schaltet sie aus",
+                            ));
+                            ui.checkbox(&mut self.panic_minimize, tr("Fenster minimieren"));
+                        });
+                        ui.end_row();
+                        ui.checkbox(
+                            &mut self.auto_save_enabled,
+                            tr("Automatisch speichern alle:"),
+                        );
+                        ui.add_enabled(
+                            self.auto_save_enabled,
+                            egui::DragValue::new(&mut self.auto_save_interval)
+                                .range(5.0..=3600.0)
+                                .speed(1.0)
+                                .suffix(" s"),
+                        );
+                        ui.end_row();
+                        ui.label(tr("Sprache:"));
+                        let selected = self.language;
+                        egui::ComboBox::from_id_source("language")
+                            .selected_text(match selected {
+                                Some(language) => language.label(),
+                                None => tr("Systemsprache"),
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.language, None, tr("Systemsprache"));
+                                for language in Language::ALL {
+                                    ui.selectable_value(
+                                        &mut self.language,
+                                        Some(language),
+                                        language.label(),
+                                    );
+                                }
+                            });
+                        if self.language != selected {
+                            i18n::set_language(
+                                self.language.unwrap_or_else(Language::from_environment),
+                            );
+                        }
+                        ui.end_row();
+
+                        ui.label(tr("Undo-Schritte:"));
+                        if ui
+                            .add(egui::DragValue::new(&mut self.undo_depth).range(0..=1000))
+                            .changed()
+                            && self.undo_stack.len() > self.undo_depth
+                        {
+                            let excess = self.undo_stack.len() - self.undo_depth;
+                            self.undo_stack.drain(..excess);
+                        }
+                        ui.end_row();
+                    });
+                if matches!(self.screen, Screen::Editor)
+                    && !self.read_only
+                    && !self.data.items.is_empty()
+                {
+                    ui.separator();
+                    if ui
+                        .add(
+                            egui::Button::new(tr("🗑 Alle löschen…"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr("Leert diesen Vault nach einer getippten Bestätigung"))
+                        .clicked()
+                    {
+                        self.delete_confirm_text.clear();
+                        self.clear_all_open = true;
+                    }
+                }
+                ui.add_space(4.0);
+                ui.label(
+                    egui::RichText::new(tr(
+                        "Gespeichert unverschlüsselt in settings.json, ohne Passwort",
+                    ))
+                    .size(12.0)
+                    .color(egui::Color32::from_gray(140)),
+                );
+            });
+        if self.theme != before.theme {
+            ctx.set_visuals(self.theme.visuals());
+        }
+        if self.settings() != before {
+            self.save_settings();
+        }
+        if !open {
+            self.settings_open = false;
+        }
+    }
+
+    fn show_import_dialog(&mut self, ctx: &egui::Context) {
+        if !self.import_dialog_open {
+            return;
+        }
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(tr("JSON importieren"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    ui.label(tr("Datei:"));
+                    ui.add_sized(
+                        [260.0, 24.0],
+                        egui::TextEdit::singleline(&mut self.import_path)
+                            .hint_text(tr("/pfad/zur/datei.json")),
+                    );
+                    if ui.button(tr("📂 Auswählen")).clicked() {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .pick_file()
+                        {
+                            self.import_path = path.display().to_string();
+                        }
+                    }
+                });
+                ui.add_space(8.0);
+                egui::ComboBox::from_label(tr("Format"))
+                    .selected_text(self.import_shape.label())
+                    .show_ui(ui, |ui| {
+                        for shape in [
+                            ImportShape::Auto,
+                            ImportShape::Vault,
+                            ImportShape::Object,
+                            ImportShape::KeyValueArray,
+                        ] {
+                            ui.selectable_value(&mut self.import_shape, shape, shape.label());
+                        }
+                    });
+                if matches!(
+                    self.import_shape,
+                    ImportShape::Auto | ImportShape::KeyValueArray
+                ) {
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label(tr("Schlüsselfeld:"));
+                        ui.add_sized(
+                            [90.0, 24.0],
+                            egui::TextEdit::singleline(&mut self.import_key_field),
+                        );
+                        ui.label(tr("Wertfeld:"));
+                        ui.add_sized(
+                            [90.0, 24.0],
+                            egui::TextEdit::singleline(&mut self.import_value_field),
+                        );
+                    });
+                }
+
+                if !self.import_error.is_empty() {
+                    ui.add_space(8.0);
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 53, 69),
+                        format!("❌ {}", self.import_error),
+                    );
+                }
+
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    let can_import = !self.import_path.trim().is_empty();
+                    if ui
+                        .add_enabled(
+                            can_import,
+                            egui::Button::new(tr("Importieren"))
+                                .fill(egui::Color32::from_rgb(40, 167, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.import_json_file(current_time);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.import_dialog_open = false;
+                        self.import_error.clear();
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    /// Dunkelt das Fenster ab und fängt Klicks ab, solange die Ableitung läuft.
+    /// Tastatureingaben landen in keinem Textfeld, sonst gälten sie danach als gespeichert.
+    fn show_key_job_overlay(&self, ctx: &egui::Context) {
+        let Some(job) = &self.key_job else {
+            return;
+        };
+        ctx.memory_mut(|memory| memory.stop_text_input());
+        ctx.request_repaint();
+        let screen = ctx.screen_rect();
+        egui::Area::new(egui::Id::new("key_job_overlay"))
+            .order(egui::Order::Foreground)
+            .fixed_pos(screen.min)
+            .show(ctx, |ui| {
+                ui.allocate_rect(screen, egui::Sense::click());
+                ui.painter()
+                    .rect_filled(screen, 0.0, egui::Color32::from_black_alpha(120));
+                let text = tr(match job.kind {
+                    KeyJobKind::Unlock => "Entschlüssele...",
+                    KeyJobKind::Create | KeyJobKind::Rekey | KeyJobKind::ChangePassword => {
+                        "Verschlüssele..."
+                    }
+                    KeyJobKind::Reload | KeyJobKind::Merge => "Lade neu...",
+                });
+                ui.allocate_ui_at_rect(
+                    egui::Rect::from_center_size(screen.center(), egui::vec2(220.0, 80.0)),
+                    |ui| {
+                        egui::Frame::popup(ui.style())
+                            .rounding(egui::Rounding::same(12.0))
+                            .show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    ui.spinner();
+                                    ui.label(egui::RichText::new(text).size(16.0));
+                                });
+                            });
+                    },
+                );
+            });
+    }
+
+    fn show_dropped_import_dialog(&mut self, ctx: &egui::Context) {
+        let Some((path, entries)) = &self.dropped_import else {
+            return;
+        };
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let count = entries.len();
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(tr("Datei importieren"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(trf(
+                    "{} Einträge aus \"{}\" in diesen Vault importieren?",
+                    &[&count, &file_name],
+                ));
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Importieren"))
+                                .fill(egui::Color32::from_rgb(40, 167, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        if let Some((_, entries)) = self.dropped_import.take() {
+                            self.start_import(entries, current_time);
+                        }
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.dropped_import = None;
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_login_import_dialog(&mut self, ctx: &egui::Context) {
+        let Some((table, columns)) = &mut self.login_import else {
+            return;
+        };
+        let current_time = ctx.input(|i| i.time);
+        let mut choice = None;
+        egui::Window::new(tr("Passwort-Manager importieren"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(
+                    egui::RichText::new(tr(
+                        "Der Titel wird zum Schlüssel, Benutzername, Passwort und URL zum Wert, die Notizen zur Notiz.",
+                    ))
+                    .size(12.0)
+                    .color(egui::Color32::from_gray(140)),
+                );
+                ui.add_space(8.0);
+                egui::Grid::new("login_import_columns")
+                    .num_columns(2)
+                    .spacing([12.0, 6.0])
+                    .show(ui, |ui| {
+                        for field in logins::LoginField::ALL {
+                            let column = &mut columns[field as usize];
+                            ui.label(field.label());
+                            let selected = column
+                                .and_then(|index| table.headers.get(index))
+                                .map_or(tr("– nicht übernehmen –"), String::as_str);
+                            egui::ComboBox::from_id_source(("login_import_column", field as usize))
+                                .selected_text(selected)
+                                .width(180.0)
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(column, None, tr("– nicht übernehmen –"));
+                                    for (index, header) in table.headers.iter().enumerate() {
+                                        ui.selectable_value(column, Some(index), header);
+                                    }
+                                });
+                            ui.end_row();
+                        }
+                    });
+
+                let has_title = columns[logins::LoginField::Title as usize].is_some();
+                let skipped = logins::map_rows(table, columns).skipped_lines;
+                ui.add_space(8.0);
+                ui.label(trf("{} Zeilen gefunden", &[&table.rows.len()]));
+                if !has_title {
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 53, 69),
+                        tr("Ohne Titel-Spalte gibt es keine Schlüssel"),
+                    );
+                } else if !skipped.is_empty() {
+                    let lines: Vec<String> = skipped.iter().map(u64::to_string).collect();
+                    ui.colored_label(
+                        egui::Color32::from_rgb(255, 193, 7),
+                        trf(
+                            "Ohne Titel, werden übersprungen: Zeile {}",
+                            &[&lines.join(", ")],
+                        ),
+                    );
+                }
+
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(
+                            has_title,
+                            egui::Button::new(tr("Importieren"))
+                                .fill(egui::Color32::from_rgb(40, 167, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        choice = Some(true);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        choice = Some(false);
+                    }
+                });
+                ui.add_space(4.0);
+            });
+        match choice {
+            Some(true) => self.finish_login_import(current_time),
+            Some(false) => self.login_import = None,
+            None => {}
+        }
+    }
+
+    fn show_import_collision_dialog(&mut self, ctx: &egui::Context) {
+        let Some(entries) = &self.pending_import else {
+            return;
+        };
+        let collisions = entries
+            .iter()
+            .filter(|(key, _)| self.data.items.contains_key(key))
+            .count();
+        let total = entries.len();
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(tr("Schlüssel existieren bereits"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(trf(
+                    "{} von {} importierten Schlüsseln gibt es schon.",
+                    &[&collisions, &total],
+                ));
+                ui.add_space(12.0);
+                let mut choice = None;
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Überspringen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        choice = Some(ImportCollision::Skip);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Überschreiben"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        choice = Some(ImportCollision::Overwrite);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Beide behalten"))
+                                .fill(egui::Color32::from_rgb(52, 144, 220))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr("Importierte Schlüssel erhalten ein Suffix wie \" (2)\""))
+                        .clicked()
+                    {
+                        choice = Some(ImportCollision::KeepBoth);
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.pending_import = None;
+                        self.pending_import_notes.clear();
+                    }
+                });
+                if let Some(collision) = choice {
+                    if let Some(entries) = self.pending_import.take() {
+                        self.finish_import(entries, collision, current_time);
+                    }
+                }
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_recovery_dialog(&mut self, ctx: &egui::Context) {
+        let Some(mut raw_json) = self.recovery_json.clone() else {
+            return;
+        };
+        let current_time = ctx.input(|i| i.time);
+        egui::Window::new(tr("Daten wiederherstellen"))
+            .collapsible(false)
+            .resizable(true)
+            .default_size(egui::vec2(560.0, 420.0))
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .frame(
+                egui::Frame::window(&ctx.style())
+                    .rounding(egui::Rounding::same(12.0))
+                    .shadow(egui::epaint::Shadow {
+                        offset: egui::vec2(0.0, 4.0),
+                        blur: 16.0,
+                        spread: 0.0,
+                        color: egui::Color32::from_black_alpha(100),
+                    }),
+            )
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.label(
+                    "Der Vault wurde entschlüsselt, der Inhalt ist aber kein gültiges JSON. \
+                     Du kannst ihn hier reparieren oder als Klartext sichern.",
+                );
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 193, 7),
+                    tr("⚠ Der Inhalt wird unverschlüsselt angezeigt"),
+                );
+                ui.add_space(8.0);
+                egui::ScrollArea::vertical()
+                    .max_height(260.0)
+                    .show(ui, |ui| {
+                        if ui
+                            .add(
+                                egui::TextEdit::multiline(&mut raw_json)
+                                    .code_editor()
+                                    .desired_width(f32::INFINITY),
+                            )
+                            .changed()
+                        {
+                            self.recovery_json = Some(raw_json.clone());
+                        }
+                    });
+
+                if !self.recovery_error.is_empty() {
+                    ui.add_space(8.0);
+                    ui.colored_label(
+                        egui::Color32::from_rgb(220, 53, 69),
+                        format!("❌ {}", self.recovery_error),
+                    );
+                }
+
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Erneut laden"))
+                                .fill(egui::Color32::from_rgb(40, 167, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        match self.load_json(raw_json.clone()) {
+                            Ok(_) => {
+                                self.dirty = true;
+                                self.recovery_error.clear();
+                                self.error_message.clear();
+                                self.screen = Screen::Editor;
+                                self.screen_transition_progress = 0.0;
+                                self.add_toast(
+                                    "Daten wiederhergestellt – bitte speichern",
+                                    egui::Color32::from_rgb(46, 160, 67),
+                                    3.0,
+                                    current_time,
+                                );
+                            }
+                            Err(e) => self.recovery_error = e,
+                        }
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("💾 Als Datei sichern"))
+                                .fill(egui::Color32::from_rgb(52, 144, 220))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        if let Some(path) = rfd::FileDialog::new()
+                            .add_filter("JSON", &["json"])
+                            .set_file_name("vault-recovery.json")
+                            .save_file()
+                        {
+                            match fs::write(&path, &raw_json) {
+                                Ok(_) => self.add_toast(
+                                    "Rohdaten gesichert",
+                                    egui::Color32::from_rgb(52, 144, 220),
+                                    2.0,
+                                    current_time,
+                                ),
+                                Err(e) => self.recovery_error = e.to_string(),
+                            }
+                        }
+                    }
+                    if ui
+                        .add(
+                            egui::Button::new(tr("Abbrechen"))
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.recovery_json = None;
+                        self.recovery_error.clear();
+                        self.password.clear();
+                    }
+                });
+                ui.add_space(4.0);
+            });
+    }
+
+    fn show_login_screen(&mut self, ctx: &egui::Context) {
+        let current_time = ctx.input(|i| i.time);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(60.0);
+
+                // Animated title with glow effect
+                let title_color = egui::Color32::from_rgb(52, 144, 220);
+                ui.heading(
+                    egui::RichText::new("🔐 Encrypted JSON Editor")
+                        .size(28.0)
+                        .color(title_color),
+                );
+                ui.add_space(8.0);
+                ui.label(
+                    egui::RichText::new(tr("Sichere Verwaltung deiner JSON-Daten"))
+                        .size(14.0)
+                        .color(egui::Color32::from_gray(140)),
+                );
+                ui.add_space(30.0);
+
+                // Login card with shake animation on error
+                let shake_offset = if current_time - self.login_shake_time < 0.5 {
+                    let shake_progress = (current_time - self.login_shake_time) * 20.0;
+                    (shake_progress.sin()
+                        * 3.0
+                        * (1.0 - (current_time - self.login_shake_time) * 2.0).max(0.0))
+                        as f32
+                } else {
+                    0.0
+                };
+
+                ui.allocate_ui_with_layout(
+                    egui::vec2(400.0, 200.0),
+                    egui::Layout::top_down(egui::Align::Center),
+                    |ui| {
+                        ui.add_space(shake_offset.max(0.0));
+
+                        ui.vertical(|ui| {
+                            egui::Frame::group(ui.style())
+                                .rounding(egui::Rounding::same(16.0))
+                                .fill(card_fill(ui))
+                                .stroke(egui::Stroke::new(
+                                    1.0,
+                                    egui::Color32::from_rgb(222, 226, 230),
+                                ))
+                                .inner_margin(egui::Margin::symmetric(24.0, 20.0))
+                                .shadow(egui::epaint::Shadow {
+                                    offset: egui::vec2(0.0, 2.0),
+                                    blur: 12.0,
+                                    spread: 0.0,
+                                    color: egui::Color32::from_black_alpha(30),
+                                })
+                                .show(ui, |ui| {
+                                    ui.vertical(|ui| {
+                                        ui.label(tr("Passwort eingeben:"));
+                                        ui.add_space(8.0);
+
+                                        let password_field =
+                                            egui::TextEdit::singleline(&mut self.password)
+                                                .password(!self.show_password)
+                                                .hint_text(tr("Dein sicheres Passwort"))
+                                                .desired_width(ui.available_width());
+
+                                        let response = ui.add(password_field);
+
+                                        ui.add_space(8.0);
+                                        ui.horizontal(|ui| {
+                                            ui.checkbox(
+                                                &mut self.show_password,
+                                                tr("Passwort anzeigen"),
+                                            );
+                                        });
+                                        ui.horizontal_wrapped(|ui| {
+                                            ui.label(tr("🗝 Schlüsseldatei:"));
+                                            show_key_file_picker(
+                                                ui,
+                                                &mut self.key_file_path,
+                                                self.key_file_required,
+                                            );
+                                        });
+
+                                        ui.add_space(12.0);
+
+                                        let wait = self.login_wait(SystemTime::now());
+                                        let login_enabled = !self.password.trim().is_empty()
+                                            && wait.is_none()
+                                            && self.key_job.is_none();
+                                        let button_color = if login_enabled {
+                                            egui::Color32::from_rgb(40, 167, 69)
+                                        } else {
+                                            egui::Color32::from_rgb(108, 117, 125)
+                                        };
+                                        let button_text = match wait {
+                                            Some(wait) => {
+                                                ui.ctx().request_repaint_after(
+                                                    Duration::from_millis(250),
+                                                );
+                                                trf(
+                                                    "⏳ Noch {} s warten",
+                                                    &[&wait.as_secs_f64().ceil()],
+                                                )
+                                            }
+                                            None => tr("🚀 Entsperren").to_string(),
+                                        };
+
+                                        let button = egui::Button::new(button_text)
+                                            .fill(button_color)
+                                            .rounding(egui::Rounding::same(8.0))
+                                            .min_size(egui::vec2(ui.available_width(), 36.0));
+
+                                        if ui.add_enabled(login_enabled, button).clicked() {
+                                            self.try_login();
+                                        }
+
+                                        if response.lost_focus()
+                                            && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                                            && login_enabled
+                                        {
+                                            self.try_login();
+                                        }
+
+                                        if !self.error_message.is_empty() {
+                                            ui.add_space(12.0);
+                                            ui.colored_label(
+                                                egui::Color32::from_rgb(220, 53, 69),
+                                                format!("❌ {}", tr(&self.error_message)),
+                                            );
+                                        }
+                                        if self.failed_logins >= FREE_LOGIN_ATTEMPTS {
+                                            ui.label(
+                                                egui::RichText::new(trf(
+                                                    "{} Fehlversuche in Folge – jeder weitere verlängert die Wartezeit",
+                                                    &[&self.failed_logins],
+                                                ))
+                                                .size(12.0)
+                                                .color(egui::Color32::from_gray(140)),
+                                            );
+                                        }
+                                    });
+                                });
+                        });
+                    },
+                );
+
+                let backups = self.vault.available_backups(self.backup_count);
+                if !backups.is_empty() {
+                    ui.add_space(12.0);
+                    egui::CollapsingHeader::new(tr("🗂 Aus Backup wiederherstellen"))
+                        .id_source("restore_backup")
+                        .show(ui, |ui| {
+                            ui.label(
+                                egui::RichText::new(tr("Oben das Passwort des Backups eingeben"))
+                                    .size(12.0)
+                                    .color(egui::Color32::from_gray(140)),
+                            );
+                            for index in backups {
+                                ui.horizontal(|ui| {
+                                    let path = backup_path(&self.vault.path, index);
+                                    let name = path
+                                        .file_name()
+                                        .map(|n| n.to_string_lossy().into_owned())
+                                        .unwrap_or_default();
+                                    ui.label(name);
+                                    if ui
+                                        .add_enabled(
+                                            !self.password.is_empty()
+                                                && self.login_wait(SystemTime::now()).is_none(),
+                                            egui::Button::new(tr("Laden")),
+                                        )
+                                        .clicked()
+                                    {
+                                        match self.restore_backup(index) {
+                                            Ok(_) => {
+                                                self.set_all_revealed(false);
+                                                self.screen = Screen::Editor;
+                                                self.error_message.clear();
+                                                self.screen_transition_progress = 0.0;
+                                                self.add_toast(
+                                                    "Backup geladen – Speichern übernimmt es",
+                                                    egui::Color32::from_rgb(46, 160, 67),
+                                                    3.0,
+                                                    current_time,
+                                                );
+                                            }
+                                            Err(e) => {
+                                                if is_wrong_password(&e) {
+                                                    self.record_failed_login(SystemTime::now());
+                                                }
+                                                self.error_message = e;
+                                                self.login_shake_time = current_time;
+                                            }
+                                        }
+                                    }
+                                });
+                            }
+                        });
+                }
+
+                self.show_vault_location(ui);
+            });
+        });
+    }
+
+    /// Pfad des Vaults samt Herkunft, mit Wechsel zu einer bestehenden Datei
+    fn show_vault_location(&mut self, ui: &mut egui::Ui) {
+        ui.add_space(20.0);
+        ui.label(
+            egui::RichText::new(trf(
+                "Vault: {} ({})",
+                &[&self.vault.path.display(), &self.vault_path_source.label()],
+            ))
+            .size(12.0)
+            .color(egui::Color32::from_gray(120)),
+        );
+        self.recent_files.retain(|path| path.exists());
+        if !self.recent_files.is_empty() {
+            ui.add_space(8.0);
+            ui.label(
+                egui::RichText::new(tr("Zuletzt geöffnet:"))
+                    .size(12.0)
+                    .color(egui::Color32::from_gray(120)),
+            );
+            let current = fs::canonicalize(&self.vault.path).ok();
+            let mut chosen = None;
+            ui.horizontal_wrapped(|ui| {
+                for path in &self.recent_files {
+                    let name = path
+                        .file_name()
+                        .map(|name| name.to_string_lossy())
+                        .unwrap_or_else(|| path.to_string_lossy());
+                    if ui
+                        .selectable_label(current.as_ref() == Some(path), format!("🗄 {}", name))
+                        .on_hover_text(path.display().to_string())
+                        .clicked()
+                    {
+                        chosen = Some(path.clone());
+                    }
+                }
+            });
+            if let Some(path) = chosen {
+                self.set_vault_path(path, VaultPathSource::RecentFile);
+                self.error_message.clear();
+            }
+            ui.add_space(4.0);
+        }
+        if ui.button(tr("📂 Datei öffnen")).clicked() {
+            if let Some(path) = rfd::FileDialog::new()
+                .add_filter("Vault", &["enc", "vault"])
+                .add_filter("Alle Dateien", &["*"])
+                .pick_file()
+            {
+                match self.open_vault_file(path, VaultPathSource::FileDialog) {
+                    Ok(_) => self.error_message.clear(),
+                    Err(e) => self.error_message = e,
+                }
+            }
+        }
+        ui.label(
+            egui::RichText::new(tr(
+                "Deine Daten werden lokal mit AES-256 oder ChaCha20 verschlüsselt",
+            ))
+            .size(12.0)
+            .color(egui::Color32::from_gray(120)),
+        );
+    }
+
+    fn show_setup_screen(&mut self, ctx: &egui::Context) {
+        let current_time = ctx.input(|i| i.time);
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(60.0);
+                ui.heading(
+                    egui::RichText::new(tr("🔐 Neuen Vault anlegen"))
+                        .size(28.0)
+                        .color(egui::Color32::from_rgb(52, 144, 220)),
+                );
+                ui.add_space(8.0);
+                ui.label(
+                    egui::RichText::new(tr("Das Passwort lässt sich nicht wiederherstellen"))
+                        .size(14.0)
+                        .color(egui::Color32::from_gray(140)),
+                );
+                ui.add_space(30.0);
+
+                let shake_offset = if current_time - self.login_shake_time < 0.5 {
+                    ((current_time - self.login_shake_time) * 20.0).sin() as f32 * 3.0
+                } else {
+                    0.0
+                };
+
+                ui.allocate_ui_with_layout(
+                    egui::vec2(400.0, 260.0),
+                    egui::Layout::top_down(egui::Align::Center),
+                    |ui| {
+                        ui.add_space(shake_offset.max(0.0));
+                        egui::Frame::group(ui.style())
+                            .rounding(egui::Rounding::same(16.0))
+                            .fill(card_fill(ui))
+                            .stroke(egui::Stroke::new(
+                                1.0,
+                                egui::Color32::from_rgb(222, 226, 230),
+                            ))
+                            .inner_margin(egui::Margin::symmetric(24.0, 20.0))
+                            .shadow(egui::epaint::Shadow {
+                                offset: egui::vec2(0.0, 2.0),
+                                blur: 12.0,
+                                spread: 0.0,
+                                color: egui::Color32::from_black_alpha(30),
+                            })
+                            .show(ui, |ui| {
+                                ui.label(tr("Passwort wählen:"));
+                                ui.add_space(8.0);
+                                let response = ui.add(
+                                    egui::TextEdit::singleline(&mut self.password)
+                                        .password(!self.show_password)
+                                        .hint_text(tr("Neues Passwort"))
+                                        .desired_width(ui.available_width()),
+                                );
+                                if response.changed() {
+                                    self.password_score = password_score(&self.password);
+                                }
+                                if !self.password.is_empty() {
+                                    ui.add_space(6.0);
+                                    show_strength_bar(ui, self.password_score);
+                                }
+
+                                ui.add_space(8.0);
+                                ui.label(tr("Passwort wiederholen:"));
+                                let repeat_response = ui.add(
+                                    egui::TextEdit::singleline(&mut self.password_repeat)
+                                        .password(!self.show_password)
+                                        .hint_text(tr("Zur Bestätigung erneut eingeben"))
+                                        .desired_width(ui.available_width()),
+                                );
+                                let mismatch = !self.password_repeat.is_empty()
+                                    && self.password != self.password_repeat;
+                                if mismatch {
+                                    ui.colored_label(
+                                        egui::Color32::from_rgb(220, 53, 69),
+                                        tr("Die Passwörter stimmen nicht überein"),
+                                    );
+                                }
+                                show_policy_hint(ui, self.active_policy(), &self.password);
+
+                                ui.add_space(8.0);
+                                ui.checkbox(&mut self.show_password, tr("Passwort anzeigen"));
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.label(tr("🗝 Schlüsseldatei:"));
+                                    show_key_file_picker(ui, &mut self.key_file_path, false);
+                                })
+                                .response
+                                .on_hover_text(tr(
+                                    "Optional: Dann wird zum Entsperren neben dem Passwort auch diese Datei gebraucht",
+                                ));
+                                ui.horizontal(|ui| {
+                                    ui.label(tr("Verschlüsselung:"));
+                                    egui::ComboBox::from_id_source("new_vault_cipher")
+                                        .selected_text(self.new_vault_cipher.label())
+                                        .show_ui(ui, |ui| {
+                                            for cipher in [
+                                                CipherSuite::Aes256Gcm,
+                                                CipherSuite::ChaCha20Poly1305,
+                                            ] {
+                                                ui.selectable_value(
+                                                    &mut self.new_vault_cipher,
+                                                    cipher,
+                                                    cipher.label(),
+                                                );
+                                            }
+                                        });
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label(tr("Schlüsselableitung:"));
+                                    ui.vertical(|ui| {
+                                        show_kdf_editor(
+                                            ui,
+                                            "setup_kdf",
+                                            &mut self.kdf_params,
+                                            &mut self.kdf_custom_open,
+                                        );
+                                    });
+                                });
+
+                                ui.add_space(12.0);
+                                let create_enabled = !self.password.trim().is_empty()
+                                    && self.password == self.password_repeat
+                                    && self.key_job.is_none();
+                                let button = egui::Button::new(tr("✨ Vault anlegen"))
+                                    .fill(if create_enabled {
+                                        egui::Color32::from_rgb(40, 167, 69)
+                                    } else {
+                                        egui::Color32::from_rgb(108, 117, 125)
+                                    })
+                                    .rounding(egui::Rounding::same(8.0))
+                                    .min_size(egui::vec2(ui.available_width(), 36.0));
+                                if ui.add_enabled(create_enabled, button).clicked()
+                                    || (create_enabled
+                                        && repeat_response.lost_focus()
+                                        && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+                                {
+                                    self.create_vault(current_time);
+                                }
+
+                                if !self.error_message.is_empty() {
+                                    ui.add_space(12.0);
+                                    ui.colored_label(
+                                        egui::Color32::from_rgb(220, 53, 69),
+                                        format!("❌ {}", tr(&self.error_message)),
+                                    );
+                                }
+                            });
+                    },
+                );
+
+                self.show_vault_location(ui);
+            });
+        });
+    }
+
+    /// Zeigt eine Zeile; während des Löschens blendet sie aus und klappt zusammen
+    fn show_animated_row(&mut self, ui: &mut egui::Ui, key: &str, current_time: f64) {
+        let height_id = egui::Id::new(("row_height", key));
+        let Some(&progress) = self.item_delete_animations.get(key) else {
+            let top = ui.cursor().top();
+            self.show_item_row(ui, key.to_string(), current_time);
+            ui.add_space(4.0);
+            let height = ui.cursor().top() - top;
+            ui.data_mut(|d| d.insert_temp(height_id, height));
+            return;
+        };
+
+        let full_height = ui
+            .data(|d| d.get_temp::<f32>(height_id))
+            .unwrap_or(ENTRY_ROW_HEIGHT);
+        let remaining = ease_in_out(1.0 - progress);
+        let (rect, _) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), full_height * remaining),
+            egui::Sense::hover(),
+        );
+        let mut row_ui = ui.child_ui(
+            egui::Rect::from_min_size(rect.min, egui::vec2(rect.width(), full_height)),
+            *ui.layout(),
+            None,
+        );
+        row_ui.set_clip_rect(rect.intersect(ui.clip_rect()));
+        row_ui.set_opacity(remaining);
+        row_ui.disable();
+        self.show_item_row(&mut row_ui, key.to_string(), current_time);
+    }
+
+    fn show_item_row(&mut self, ui: &mut egui::Ui, key: String, current_time: f64) {
+        // Der Eintrag kann im selben Frame schon umbenannt oder gelöscht worden sein
+        let Some(mut value) = self.data.items.get(&key).cloned() else {
+            return;
+        };
+        let value_text = value.as_text();
+        let hover_state = self.item_hover_states.entry(key.clone()).or_insert(0.0);
+
+        // Eine angesprungene Zeile leuchtet wie beim Überfahren und blendet dann aus
+        let jump_glow = match &self.jump_highlight {
+            Some((target, start)) if *target == key => {
+                ui.ctx().request_repaint();
+                (1.0 - (current_time - start) / JUMP_HIGHLIGHT_SECONDS).clamp(0.0, 1.0) as f32
+            }
+            _ => 0.0,
+        };
+        let hover_progress = hover_state.max(jump_glow);
+        let bg_color = card_fill(ui);
+        // Solange der Löschdialog offen ist, bleibt die Zeile unverändert
+        let editable = !self.read_only && !self.delete_pending(&key);
+        let mut delete_requested = false;
+
+        let frame = egui::Frame::group(ui.style())
+            .rounding(egui::Rounding::same(10.0))
+            .fill(bg_color)
+            .stroke(egui::Stroke::new(
+                1.0 + hover_progress * 0.5,
+                egui::Color32::from_rgb(
+                    (222.0 * (1.0 - hover_progress) + 52.0 * hover_progress) as u8,
+                    (226.0 * (1.0 - hover_progress) + 144.0 * hover_progress) as u8,
+                    (230.0 * (1.0 - hover_progress) + 220.0 * hover_progress) as u8,
+                ),
+            ))
+            .inner_margin(egui::Margin::symmetric(14.0, 10.0))
+            .shadow(egui::epaint::Shadow {
+                offset: egui::vec2(0.0, 1.0 + hover_progress * 2.0),
+                blur: 4.0 + hover_progress * 4.0,
+                spread: 0.0,
+                color: egui::Color32::from_black_alpha((20.0 + hover_progress * 20.0) as u8),
+            })
+            .show(ui, |ui| {
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        let mut selected = self.selected_keys.contains(&key);
+                        if !self.read_only && ui.checkbox(&mut selected, "").changed() {
+                            if selected {
+                                self.selected_keys.insert(key.clone());
+                            } else {
+                                self.selected_keys.remove(&key);
+                            }
+                        }
+                        if self.rename_key.as_deref() == Some(key.as_str()) {
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut self.rename_buffer)
+                                    .desired_width(180.0),
+                            );
+                            if self.rename_focus_pending {
+                                response.request_focus();
+                                self.rename_focus_pending = false;
+                            }
+                            if self.scroll_to_key.as_deref() == Some(key.as_str()) {
+                                response.scroll_to_me(Some(egui::Align::Center));
+                                self.scroll_to_key = None;
+                            }
+                            if response.lost_focus() {
+                                let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+                                let new_key = self.rename_buffer.clone();
+                                if cancelled || self.rename_entry(&key, &new_key, current_time) {
+                                    self.rename_key = None;
+                                } else {
+                                    self.rename_focus_pending = true;
+                                }
+                            }
+                        } else if ui
+                            .add(
+                                egui::Label::new(if self.search_scope == SearchScope::Values {
+                                    highlight_match(ui, &key, "")
+                                } else if let Some(regex) = self.active_search_regex() {
+                                    highlight_range(
+                                        ui,
+                                        &key,
+                                        regex.find(&key).map(|found| found.range()),
+                                    )
+                                } else if self.search_fuzzy {
+                                    highlight_ranges(
+                                        ui,
+                                        &key,
+                                        &fuzzy_match(&self.search_query_lower, &key)
+                                            .map(|(_, ranges)| ranges)
+                                            .unwrap_or_default(),
+                                    )
+                                } else {
+                                    highlight_match(ui, &key, &self.search_query_lower)
+                                })
+                                .sense(egui::Sense::click()),
+                            )
+                            .on_hover_text(tr("Doppelklick zum Umbenennen"))
+                            .double_clicked()
+                            && !self.read_only
+                        {
+                            self.rename_key = Some(key.clone());
+                            self.rename_buffer = key.clone();
+                            self.rename_focus_pending = true;
+                        }
+                        if self.data.critical.contains(&key) {
+                            ui.colored_label(egui::Color32::from_rgb(220, 53, 69), tr("kritisch"));
+                        }
+                        if let Some(meta) = self.data.meta.get(&key) {
+                            let now = SystemTime::now();
+                            ui.label(
+                                egui::RichText::new(trf(
+                                    "geändert {}",
+                                    &[&format_relative_time(meta.modified, now)],
+                                ))
+                                .size(11.0)
+                                .color(egui::Color32::from_gray(140)),
+                            )
+                            .on_hover_text(trf(
+                                "Erstellt {}\nGeändert {}",
+                                &[
+                                    &format_relative_time(meta.created, now),
+                                    &format_relative_time(meta.modified, now),
+                                ],
+                            ));
+                        }
+                        if !self.search_query_lower.is_empty()
+                            && !self.key_matches_search(&key)
+                            && self.value_matches_search(&key)
+                        {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 193, 7),
+                                tr("🔍 Treffer im Wert"),
+                            );
+                        }
+                        if has_control_chars(&value_text) {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 193, 7),
+                                tr("⚠ Steuerzeichen"),
+                            )
+                            .on_hover_text(tr("Der Wert enthält nicht druckbare Zeichen"));
+                            ui.add_visible_ui(!self.read_only, |ui| {
+                                ui.menu_button(tr("🧹 bereinigen"), |ui| {
+                                    let clean: Option<fn(&str) -> String> =
+                                        if ui.button(tr("Steuerzeichen entfernen")).clicked() {
+                                            Some(strip_control_chars)
+                                        } else if ui
+                                            .button(tr("Als Escape-Sequenz (\\u{..})"))
+                                            .clicked()
+                                        {
+                                            Some(escape_control_chars)
+                                        } else {
+                                            None
+                                        };
+                                    if let Some(clean) = clean {
+                                        match &mut value {
+                                            EntryValue::Text(text) => *text = clean(text),
+                                            EntryValue::Json(json) => map_json_strings(json, clean),
+                                            EntryValue::Number(_)
+                                            | EntryValue::Bool(_)
+                                            | EntryValue::Attachment(_) => {}
+                                        }
+                                        self.push_undo();
+                                        self.data.set_value(key.clone(), value.clone());
+                                        ui.close_menu();
+                                    }
+                                });
+                            });
+                        }
+                        if let Some(others) =
+                            self.reused_values.get(&key).filter(|_| self.reuse_warnings)
+                        {
+                            ui.colored_label(
+                                egui::Color32::from_rgb(255, 193, 7),
+                                tr("⚠ mehrfach"),
+                            )
+                            .on_hover_text(trf(
+                                "Derselbe Wert steht auch unter: {}",
+                                &[&others.join(", ")],
+                            ));
+                        }
+                        if let Some(expires_at) = self.data.expires_at(&key) {
+                            let badge = match expiry_state(expires_at, SystemTime::now()) {
+                                Some(Expiry::Expired) => Some((
+                                    egui::Color32::from_rgb(220, 53, 69),
+                                    tr("⏰ abgelaufen").to_string(),
+                                )),
+                                Some(Expiry::Soon(days)) => Some((
+                                    egui::Color32::from_rgb(255, 193, 7),
+                                    trf("⏰ läuft in {} Tagen ab", &[&days]),
+                                )),
+                                None => None,
+                            };
+                            if let Some((color, text)) = badge {
+                                ui.colored_label(color, text).on_hover_text(trf(
+                                    "Läuft ab am {}",
+                                    &[&format_date(expires_at)],
+                                ));
+                            }
+                        }
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if !self.read_only {
+                                if ui
+                                    .add(
+                                        egui::Button::new("❌")
+                                            .fill(egui::Color32::from_rgb(255, 240, 240))
+                                            .stroke(egui::Stroke::new(
+                                                1.0,
+                                                egui::Color32::from_rgb(220, 53, 69),
+                                            ))
+                                            .rounding(egui::Rounding::same(6.0)),
+                                    )
+                                    .on_hover_text(tr("Eintrag löschen"))
+                                    .clicked()
+                                {
+                                    delete_requested = true;
+                                }
+
+                                ui.add_space(4.0);
+
+                                if ui
+                                    .add(
+                                        egui::Button::new("📑")
+                                            .fill(egui::Color32::from_rgb(240, 248, 255))
+                                            .stroke(egui::Stroke::new(
+                                                1.0,
+                                                egui::Color32::from_rgb(52, 144, 220),
+                                            ))
+                                            .rounding(egui::Rounding::same(6.0)),
+                                    )
+                                    .on_hover_text(tr("Eintrag duplizieren"))
+                                    .clicked()
+                                {
+                                    self.duplicate_entry(&key, current_time);
+                                }
+
+                                ui.add_space(4.0);
+
+                                let critical = self.data.critical.contains(&key);
+                                if ui
+                                    .add(
+                                        egui::Button::new("❗")
+                                            .fill(if critical {
+                                                egui::Color32::from_rgb(220, 53, 69)
+                                            } else {
+                                                egui::Color32::from_rgb(255, 240, 240)
+                                            })
+                                            .stroke(egui::Stroke::new(
+                                                1.0,
+                                                egui::Color32::from_rgb(220, 53, 69),
+                                            ))
+                                            .rounding(egui::Rounding::same(6.0)),
+                                    )
+                                    .on_hover_text(tr(if critical {
+                                        "Markierung \"kritisch\" entfernen"
+                                    } else {
+                                        "Als kritisch markieren"
+                                    }))
+                                    .clicked()
+                                {
+                                    self.push_undo();
+                                    if !self.data.critical.remove(&key) {
+                                        self.data.critical.insert(key.clone());
+                                    }
+                                }
+
+                                ui.add_space(4.0);
+
+                                let favorite = self.data.is_favorite(&key);
+                                if ui
+                                    .add(
+                                        egui::Button::new("⭐")
+                                            .fill(if favorite {
+                                                egui::Color32::from_rgb(255, 193, 7)
+                                            } else {
+                                                egui::Color32::from_rgb(255, 250, 235)
+                                            })
+                                            .stroke(egui::Stroke::new(
+                                                1.0,
+                                                egui::Color32::from_rgb(255, 193, 7),
+                                            ))
+                                            .rounding(egui::Rounding::same(6.0)),
+                                    )
+                                    .on_hover_text(tr(if favorite {
+                                        "Nicht mehr anheften"
+                                    } else {
+                                        "Als Favorit oben anheften"
+                                    }))
+                                    .clicked()
+                                {
+                                    self.toggle_favorite(&key);
+                                }
+
+                                ui.add_space(4.0);
+
+                                let expiry_color = if self.data.expires_at(&key).is_some() {
+                                    egui::Color32::from_rgb(255, 193, 7)
+                                } else {
+                                    egui::Color32::from_gray(140)
+                                };
+                                ui.menu_button(
+                                    egui::RichText::new("⏰").color(expiry_color),
+                                    |ui| self.show_expiry_menu(ui, &key),
+                                )
+                                .response
+                                .on_hover_text(tr("Ablaufdatum"));
+
+                                ui.add_space(4.0);
+
+                                if ui
+                                    .add(
+                                        egui::Button::new("📎")
+                                            .fill(egui::Color32::from_rgb(240, 248, 255))
+                                            .stroke(egui::Stroke::new(
+                                                1.0,
+                                                egui::Color32::from_rgb(52, 144, 220),
+                                            ))
+                                            .rounding(egui::Rounding::same(6.0)),
+                                    )
+                                    .on_hover_text(trf(
+                                        "Datei anhängen (ersetzt den Wert, höchstens {})",
+                                        &[&format_size(MAX_ATTACHMENT_BYTES as u64)],
+                                    ))
+                                    .clicked()
+                                {
+                                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                                        self.attach_file(&key, &path, current_time);
+                                    }
+                                }
+
+                                ui.add_space(4.0);
+                            }
+
+                            if let EntryValue::Attachment(attachment) = &value {
+                                if ui
+                                    .add(
+                                        egui::Button::new("💾")
+                                            .fill(egui::Color32::from_rgb(240, 248, 255))
+                                            .stroke(egui::Stroke::new(
+                                                1.0,
+                                                egui::Color32::from_rgb(52, 144, 220),
+                                            ))
+                                            .rounding(egui::Rounding::same(6.0)),
+                                    )
+                                    .on_hover_text(tr("Anhang speichern unter..."))
+                                    .clicked()
+                                {
+                                    if let Some(path) = rfd::FileDialog::new()
+                                        .set_file_name(&attachment.filename)
+                                        .save_file()
+                                    {
+                                        self.save_attachment(&key, &path, current_time);
+                                    }
+                                }
+
+                                ui.add_space(4.0);
+                            }
+
+                            let revealed = self.revealed_items.contains(&key);
+                            if ui
+                                .add(
+                                    egui::Button::new("👁")
+                                        .fill(if revealed {
+                                            egui::Color32::from_rgb(52, 144, 220)
+                                        } else {
+                                            egui::Color32::from_rgb(240, 248, 255)
+                                        })
+                                        .stroke(egui::Stroke::new(
+                                            1.0,
+                                            egui::Color32::from_rgb(52, 144, 220),
+                                        ))
+                                        .rounding(egui::Rounding::same(6.0)),
+                                )
+                                .on_hover_text(tr(if revealed {
+                                    "Wert verbergen"
+                                } else {
+                                    "Wert anzeigen"
+                                }))
+                                .clicked()
+                            {
+                                self.toggle_reveal(&key);
+                            }
+
+                            ui.add_space(4.0);
+
+                            if ui
+                                .add(
+                                    egui::Button::new("📋")
+                                        .fill(egui::Color32::from_rgb(240, 248, 255))
+                                        .stroke(egui::Stroke::new(
+                                            1.0,
+                                            egui::Color32::from_rgb(52, 144, 220),
+                                        ))
+                                        .rounding(egui::Rounding::same(6.0)),
+                                )
+                                .on_hover_text(tr("Wert kopieren"))
+                                .clicked()
+                            {
+                                self.copy_to_clipboard(
+                                    ui,
+                                    value_text.clone(),
+                                    true,
+                                    "In Zwischenablage kopiert",
+                                    current_time,
+                                );
+                            }
+                            ui.menu_button("▾", |ui| {
+                                if ui.button(tr("Schlüssel kopieren")).clicked() {
+                                    self.copy_to_clipboard(
+                                        ui,
+                                        key.clone(),
+                                        false,
+                                        "Schlüssel kopiert",
+                                        current_time,
+                                    );
+                                    ui.close_menu();
+                                }
+                                if ui.button(tr("Wert kopieren")).clicked() {
+                                    self.copy_to_clipboard(
+                                        ui,
+                                        value_text.clone(),
+                                        true,
+                                        "Wert kopiert",
+                                        current_time,
+                                    );
+                                    ui.close_menu();
+                                }
+                                if ui.button(tr("Als JSON kopieren")).clicked() {
+                                    self.copy_to_clipboard(
+                                        ui,
+                                        entry_json_snippet(&key, &value),
+                                        true,
+                                        "Als JSON kopiert",
+                                        current_time,
+                                    );
+                                    ui.close_menu();
+                                }
+                            })
+                            .response
+                            .on_hover_text(tr("Weitere Kopieroptionen"));
+
+                            if let EntryValue::Text(text) = &value {
+                                if !text.contains('\n') {
+                                    ui.add_space(4.0);
+                                    let expanded = self.expanded_values.contains(&key);
+                                    if ui
+                                        .selectable_label(expanded, "↕")
+                                        .on_hover_text(tr("Mehrzeilig bearbeiten"))
+                                        .clicked()
+                                        && !self.expanded_values.remove(&key)
+                                    {
+                                        self.expanded_values.insert(key.clone());
+                                    }
+                                }
+                            }
+
+                            if matches!(value, EntryValue::Text(_)) && !self.read_only {
+                                ui.add_space(4.0);
+                                let mut generated = None;
+                                ui.menu_button("🎲", |ui| {
+                                    generated = show_generator_menu(ui, &mut self.generator);
+                                })
+                                .response
+                                .on_hover_text(tr("Neues Passwort erzeugen"));
+                                if let Some(password) = generated {
+                                    self.push_undo();
+                                    self.data.set_value(key.clone(), EntryValue::Text(password));
+                                    self.revalidate(&key);
+                                }
+                            }
+
+                            if !self.read_only {
+                                ui.add_space(4.0);
+                                let rule = self
+                                    .data
+                                    .meta
+                                    .get(&key)
+                                    .map(|meta| meta.rule.clone())
+                                    .unwrap_or_default();
+                                let mut chosen = None;
+                                ui.menu_button(if rule.is_none() { "✔" } else { "✅" }, |ui| {
+                                    if let ValidationRule::Regex(pattern) = &rule {
+                                        if self.rule_pattern.is_empty() {
+                                            self.rule_pattern = pattern.clone();
+                                        }
+                                    }
+                                    chosen = show_rule_menu(ui, &rule, &mut self.rule_pattern);
+                                })
+                                .response
+                                .on_hover_text(trf("Prüfregel: {}", &[&rule.label()]));
+                                if let Some(rule) = chosen {
+                                    self.push_undo();
+                                    if let Some(meta) = self.data.meta.get_mut(&key) {
+                                        meta.rule = rule;
+                                        self.data.touch();
+                                    }
+                                    self.rule_pattern.clear();
+                                    self.revalidate(&key);
+                                }
+                            }
+
+                            let history = self
+                                .data
+                                .meta
+                                .get(&key)
+                                .map(|meta| meta.history.clone())
+                                .unwrap_or_default();
+                            if !history.is_empty() {
+                                ui.add_space(4.0);
+                                let masked = self.value_masked(&key);
+                                let mut restore = None;
+                                ui.menu_button("🕘", |ui| {
+                                    let now = SystemTime::now();
+                                    let shown = history.len().min(self.history_limit.max(1));
+                                    for (replaced, old) in history.iter().rev().take(shown) {
+                                        ui.horizontal(|ui| {
+                                            ui.label(
+                                                egui::RichText::new(format_relative_time(
+                                                    *replaced, now,
+                                                ))
+                                                .color(egui::Color32::from_gray(140)),
+                                            );
+                                            ui.label(if masked {
+                                                "••••••".to_string()
+                                            } else {
+                                                old.as_text()
+                                            });
+                                            if !self.read_only
+                                                && ui.small_button(tr("Wiederherstellen")).clicked()
+                                            {
+                                                restore = Some(old.clone());
+                                                ui.close_menu();
+                                            }
+                                        });
+                                    }
+                                })
+                                .response
+                                .on_hover_text(tr("Frühere Werte"));
+                                if let Some(old) = restore {
+                                    self.push_undo();
+                                    self.data.set_value(key.clone(), old);
+                                    self.revalidate(&key);
+                                }
+                            }
+
+                            if matches!(value, EntryValue::Text(_)) && is_openable_url(&value_text)
+                            {
+                                ui.add_space(4.0);
+
+                                if ui
+                                    .add(
+                                        egui::Button::new(tr("🔗 öffnen"))
+                                            .fill(egui::Color32::from_rgb(240, 248, 255))
+                                            .stroke(egui::Stroke::new(
+                                                1.0,
+                                                egui::Color32::from_rgb(52, 144, 220),
+                                            ))
+                                            .rounding(egui::Rounding::same(6.0)),
+                                    )
+                                    .on_hover_text(tr("Im Browser öffnen"))
+                                    .clicked()
+                                {
+                                    self.open_url(value_text.trim(), current_time);
+                                }
+                            }
+                        });
+                    });
+
+                    ui.add_space(4.0);
+                    let masked = self.value_masked(&key);
+                    let multiline = self.expanded_values.contains(&key);
+                    let violation = self.validation_errors.get(&key).cloned();
+                    let changed = ui
+                        .scope(|ui| {
+                            if violation.is_some() {
+                                let red =
+                                    egui::Stroke::new(1.5, egui::Color32::from_rgb(220, 53, 69));
+                                let visuals = ui.visuals_mut();
+                                visuals.widgets.inactive.bg_stroke = red;
+                                visuals.widgets.hovered.bg_stroke = red;
+                                visuals.selection.stroke = red;
+                            }
+                            if self.value_focus_pending.as_deref() == Some(key.as_str()) {
+                                self.value_focus_pending = None;
+                                ui.memory_mut(|m| m.request_focus(entry_value_id(&key)));
+                            }
+                            show_entry_value(ui, &key, &mut value, masked, multiline, editable)
+                        })
+                        .inner;
+                    if changed {
+                        let pasted = ui
+                            .input(|i| i.events.iter().any(|e| matches!(e, egui::Event::Paste(_))));
+                        if self.auto_trim_values && pasted {
+                            if let EntryValue::Text(text) = &mut value {
+                                *text = text.trim().to_string();
+                            }
+                        }
+                        self.push_undo_for_value_edit(&key, current_time);
+                        self.data.set_value(key.clone(), value);
+                        self.revalidate(&key);
+                    }
+                    if let Some(EntryValue::Text(text)) = self.data.items.get(&key) {
+                        if has_outer_whitespace(text) && !self.read_only {
+                            let trimmed = text.trim().to_string();
+                            ui.horizontal(|ui| {
+                                show_whitespace_hint(ui);
+                                if ui.small_button(tr("✂ Entfernen")).clicked() {
+                                    self.push_undo();
+                                    self.data.set_value(key.clone(), EntryValue::Text(trimmed));
+                                    self.revalidate(&key);
+                                }
+                            });
+                        }
+                    }
+                    if let Some(violation) = violation {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(220, 53, 69),
+                            format!("⚠ {}", violation),
+                        );
+                    }
+
+                    self.show_tag_chips(ui, &key, editable);
+
+                    let mut note = self.data.meta.get(&key).map(|m| m.note.clone());
+                    if let Some(note) = note.as_mut() {
+                        let header = if note.is_empty() {
+                            tr("📝 Notiz").to_string()
+                        } else {
+                            trf("📝 Notiz: {}", &[&note.lines().next().unwrap_or_default()])
+                        };
+                        egui::CollapsingHeader::new(header)
+                            .id_source(("entry_note", &key))
+                            .show(ui, |ui| {
+                                if ui
+                                    .add(
+                                        egui::TextEdit::multiline(note)
+                                            .interactive(editable)
+                                            .hint_text(tr("Wofür ist dieser Eintrag?"))
+                                            .desired_rows(2)
+                                            .desired_width(ui.available_width()),
+                                    )
+                                    .changed()
+                                {
+                                    self.push_undo_for_value_edit(&key, current_time);
+                                    if let Some(meta) = self.data.meta.get_mut(&key) {
+                                        meta.note = note.clone();
+                                        meta.modified = SystemTime::now();
+                                    }
+                                }
+                            });
+                    }
+                });
+            });
+
+        // Erst nach dem Übernehmen der Eingaben dieses Frames, sonst ginge eine
+        // gleichzeitige Änderung am Wert verloren
+        if delete_requested {
+            ui.memory_mut(|m| m.surrender_focus(entry_value_id(&key)));
+            self.delete_candidate = Some(key.clone());
+            self.delete_confirm_text.clear();
+        }
+
+        // Beim Umbenennen richtet schon das Eingabefeld aus
+        if self.scroll_to_key.as_deref() == Some(key.as_str()) {
+            frame.response.scroll_to_me(Some(egui::Align::Center));
+            self.scroll_to_key = None;
+        }
+
+        let clicked_here = ui.rect_contains_pointer(frame.response.rect)
+            && ui.input(|i| i.pointer.primary_clicked());
+        if clicked_here || ui.memory(|m| m.has_focus(entry_value_id(&key))) {
+            self.last_focused_key = Some(key.clone());
+        }
+
+        // Rises faster than `update_animations` lets it decay, so it settles at 1.0
+        if ui.rect_contains_pointer(frame.response.rect) {
+            let dt = ui.input(|i| i.stable_dt);
+            if let Some(hover_state) = self.item_hover_states.get_mut(&key) {
+                *hover_state = (*hover_state + dt * 12.0).min(1.0);
+            }
+        }
+    }
+
+    /// Kompakte Tabelle: Schlüssel und Wert direkt bearbeitbar, Tab springt zur
+    /// nächsten Zelle. Umbenannt wird beim Verlassen der Schlüsselzelle über
+    /// denselben Weg wie in der Kartenansicht (`rename_key`, `rename_entry`).
+    fn show_entry_grid(&mut self, ui: &mut egui::Ui, keys: &[String], current_time: f64) {
+        let mut scroll_area = egui::ScrollArea::vertical()
+            .id_source("entry_grid")
+            .auto_shrink([false; 2]);
+        if let Some(target) = self.scroll_to_key.take() {
+            if let Some(index) = keys.iter().position(|key| *key == target) {
+                let stride = GRID_ROW_HEIGHT + ui.spacing().item_spacing.y;
+                scroll_area = scroll_area.vertical_scroll_offset(index as f32 * stride);
+            }
+        } else if let Some(offset) = self.list_scroll_restore.take() {
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        let editable = !self.read_only;
+        let output = scroll_area.show_rows(ui, GRID_ROW_HEIGHT, keys.len(), |ui, range| {
+            egui::Grid::new("entry_grid_rows")
+                .num_columns(3)
+                .striped(true)
+                .min_row_height(GRID_ROW_HEIGHT)
+                .show(ui, |ui| {
+                    for key in &keys[range] {
+                        self.show_grid_row(ui, key, editable, current_time);
+                        ui.end_row();
+                    }
+                });
+        });
+        self.list_scroll_offset = output.state.offset.y;
+    }
+
+    fn show_grid_row(&mut self, ui: &mut egui::Ui, key: &str, editable: bool, current_time: f64) {
+        let Some(mut value) = self.data.items.get(key).cloned() else {
+            return;
+        };
+        let editing = self.rename_key.as_deref() == Some(key);
+        let mut key_text = if editing {
+            self.rename_buffer.clone()
+        } else {
+            key.to_string()
+        };
+        let response = ui.add(
+            egui::TextEdit::singleline(&mut key_text)
+                .id(egui::Id::new(("grid_key", key)))
+                .interactive(editable)
+                .desired_width(180.0),
+        );
+        if response.gained_focus() || response.changed() {
+            self.rename_key = Some(key.to_string());
+            self.rename_buffer = key_text;
+        }
+        if response.lost_focus() && self.rename_key.as_deref() == Some(key) {
+            let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+            let new_key = std::mem::take(&mut self.rename_buffer);
+            self.rename_key = None;
+            // Bei Kollision bleibt der alte Schlüssel, `rename_entry` meldet den Grund
+            if !cancelled {
+                self.rename_entry(key, &new_key, current_time);
+            }
+        }
+
+        let masked = self.value_masked(key);
+        if self.value_focus_pending.as_deref() == Some(key) {
+            self.value_focus_pending = None;
+            ui.memory_mut(|m| m.request_focus(entry_value_id(key)));
+        }
+        let editable_value = editable && !self.delete_pending(key);
+        if show_entry_value(ui, key, &mut value, masked, false, editable_value) {
+            self.push_undo_for_value_edit(key, current_time);
+            self.data.set_value(key.to_string(), value);
+            self.revalidate(key);
+        }
+        if response.has_focus() || ui.memory(|m| m.has_focus(entry_value_id(key))) {
+            self.last_focused_key = Some(key.to_string());
+        }
+
+        if editable
+            && ui
+                .add(
+                    egui::Button::new("❌")
+                        .fill(egui::Color32::from_rgb(255, 240, 240))
+                        .stroke(egui::Stroke::new(1.0, egui::Color32::from_rgb(220, 53, 69)))
+                        .rounding(egui::Rounding::same(6.0)),
+                )
+                .on_hover_text(tr("Eintrag löschen"))
+                .clicked()
+        {
+            self.delete_candidate = Some(key.to_string());
+            self.delete_confirm_text.clear();
+        }
+    }
+
+    /// Tags eines Eintrags als Chips; ✕ entfernt, ➕ fügt hinzu
+    fn show_tag_chips(&mut self, ui: &mut egui::Ui, key: &str, editable: bool) {
+        let tags = self
+            .data
+            .meta
+            .get(key)
+            .map(|meta| meta.tags.clone())
+            .unwrap_or_default();
+        if !editable {
+            if !tags.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    for tag in &tags {
+                        ui.label(
+                            egui::RichText::new(format!("🏷 {}", tag))
+                                .size(11.0)
+                                .color(egui::Color32::from_rgb(52, 144, 220)),
+                        );
+                    }
+                });
+            }
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            for tag in &tags {
+                if ui
+                    .add(
+                        egui::Button::new(
+                            egui::RichText::new(format!("🏷 {} ✕", tag))
+                                .size(11.0)
+                                .color(egui::Color32::WHITE),
+                        )
+                        .fill(egui::Color32::from_rgb(52, 144, 220))
+                        .rounding(egui::Rounding::same(10.0)),
+                    )
+                    .on_hover_text(tr("Tag entfernen"))
+                    .clicked()
+                {
+                    self.push_undo();
+                    if let Some(meta) = self.data.meta.get_mut(key) {
+                        meta.tags.retain(|t| t != tag);
+                        self.data.touch();
+                    }
+                }
+            }
+
+            if self.tag_input_key.as_deref() == Some(key) {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.tag_input)
+                        .hint_text("Tag")
+                        .desired_width(100.0),
+                );
+                response.request_focus();
+                if response.lost_focus() {
+                    let tag = self.tag_input.trim().to_string();
+                    let confirmed = ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if confirmed && !tag.is_empty() && !tags.contains(&tag) {
+                        self.push_undo();
+                        if let Some(meta) = self.data.meta.get_mut(key) {
+                            meta.tags.push(tag);
+                            self.data.touch();
+                        }
+                    }
+                    self.tag_input_key = None;
+                    self.tag_input.clear();
+                }
+            } else if ui
+                .add(
+                    egui::Button::new(egui::RichText::new(tr("➕ Tag")).size(11.0))
+                        .rounding(egui::Rounding::same(10.0)),
+                )
+                .clicked()
+            {
+                self.tag_input_key = Some(key.to_string());
+                self.tag_input.clear();
+            }
+        });
+    }
+
+    /// Filterleiste mit allen Tags und ihrer Anzahl; gewählte Tags müssen alle passen
+    fn show_tag_filter_bar(&mut self, ui: &mut egui::Ui) {
+        let counts = self.data.tag_counts();
+        self.tag_filter.retain(|tag| counts.contains_key(tag));
+        if counts.is_empty() {
+            return;
+        }
+        ui.horizontal_wrapped(|ui| {
+            ui.label(tr("🏷 Filter:"));
+            for (tag, count) in &counts {
+                let selected = self.tag_filter.contains(tag);
+                if ui
+                    .selectable_label(selected, format!("{} ({})", tag, count))
+                    .clicked()
+                {
+                    if selected {
+                        self.tag_filter.remove(tag);
+                    } else {
+                        self.tag_filter.insert(tag.clone());
+                    }
+                }
+            }
+            if !self.tag_filter.is_empty() && ui.small_button(tr("✖ Filter aufheben")).clicked() {
+                self.tag_filter.clear();
+            }
+        });
+    }
+
+    fn show_editor_screen(&mut self, ctx: &egui::Context) {
+        let current_time = ctx.input(|i| i.time);
+        if self.key_job.is_none() {
+            self.handle_undo_shortcuts(ctx);
+            self.handle_editor_shortcuts(ctx);
+        }
+
+        // Animated slide-in effect
+        let slide_progress = ease_in_out(self.screen_transition_progress);
+        let slide_offset = (1.0 - slide_progress) * 50.0;
+
+        // Top Bar with gradient background
+        egui::TopBottomPanel::top("top_bar")
+            .frame(
+                egui::Frame::none()
+                    .fill(egui::Color32::from_rgb(52, 58, 64))
+                    .inner_margin(egui::Margin::symmetric(16.0, 12.0)),
+            )
+            .show(ctx, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    ui.strong(
+                        egui::RichText::new(if self.dirty {
+                            "🔐 Encrypted JSON Editor ●"
+                        } else {
+                            "🔐 Encrypted JSON Editor"
+                        })
+                        .size(16.0)
+                        .color(egui::Color32::WHITE),
+                    )
+                    .on_hover_text(tr(if self.dirty {
+                        "Ungespeicherte Änderungen"
+                    } else {
+                        "Alles gespeichert"
+                    }));
+                    ui.separator();
+                    ui.add_space(8.0);
+
+                    // Save button with success animation
+                    if ui
+                        .add(
+                            egui::Button::new(tr(if self.dirty {
+                                "💾 Speichern ●"
+                            } else {
+                                "💾 Speichern"
+                            }))
+                            .fill(if self.dirty {
+                                egui::Color32::from_rgb(253, 126, 20)
+                            } else {
+                                egui::Color32::from_rgb(40, 167, 69)
+                            })
+                            .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.save_requested = Some(false);
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new(tr("🚪 Speichern & Beenden"))
+                                .fill(egui::Color32::from_rgb(220, 53, 69))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        let _ = self.encrypt_data();
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new("🔒")
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr("Vault sperren"))
+                        .clicked()
+                    {
+                        self.lock(current_time);
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new(tr(if self.read_only {
+                                "🔒 Nur lesen"
+                            } else {
+                                "✏ Bearbeiten"
+                            }))
+                            .fill(if self.read_only {
+                                egui::Color32::from_rgb(52, 144, 220)
+                            } else {
+                                egui::Color32::from_rgb(108, 117, 125)
+                            })
+                            .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr(if self.read_only {
+                            "Nur ansehen und kopieren; klicken zum Bearbeiten"
+                        } else {
+                            "In den Lesemodus wechseln"
+                        }))
+                        .clicked()
+                    {
+                        self.set_read_only(!self.read_only);
+                    }
+
+                    if !self.read_only {
+                        if ui
+                            .add_enabled(
+                                !self.undo_stack.is_empty(),
+                                egui::Button::new("↶")
+                                    .fill(egui::Color32::from_rgb(108, 117, 125))
+                                    .rounding(egui::Rounding::same(6.0)),
+                            )
+                            .on_hover_text(tr("Rückgängig (Strg+Z)"))
+                            .clicked()
+                        {
+                            self.undo(current_time);
+                        }
+
+                        if ui
+                            .add_enabled(
+                                !self.redo_stack.is_empty(),
+                                egui::Button::new("↷")
+                                    .fill(egui::Color32::from_rgb(108, 117, 125))
+                                    .rounding(egui::Rounding::same(6.0)),
+                            )
+                            .on_hover_text(tr("Wiederholen (Strg+Y)"))
+                            .clicked()
+                        {
+                            self.redo(current_time);
+                        }
+
+                        if ui
+                            .add(
+                                egui::Button::new(tr("🔑 Passwort ändern"))
+                                    .fill(egui::Color32::from_rgb(108, 117, 125))
+                                    .rounding(egui::Rounding::same(6.0)),
+                            )
+                            .clicked()
+                        {
+                            self.close_password_change_dialog();
+                            self.password_change_key_file = self
+                                .key_file_required
+                                .then(|| self.key_file_path.clone())
+                                .flatten();
+                            self.password_change_open = true;
+                        }
+                    }
+                    let all_revealed = !self.data.items.is_empty()
+                        && self
+                            .data
+                            .items
+                            .keys()
+                            .all(|key| self.revealed_items.contains(key));
+                    if ui
+                        .add(
+                            egui::Button::new(tr(if all_revealed {
+                                "🙈 Alle verbergen"
+                            } else {
+                                "👁 Alle anzeigen"
+                            }))
+                            .fill(egui::Color32::from_rgb(108, 117, 125))
+                            .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.set_all_revealed(!all_revealed);
+                    }
+
+                    let notes_fill = if self.show_notes {
+                        egui::Color32::from_rgb(52, 144, 220)
+                    } else {
+                        egui::Color32::from_rgb(108, 117, 125)
+                    };
+                    if ui
+                        .add(
+                            egui::Button::new(tr("📓 Notizen"))
+                                .fill(notes_fill)
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .clicked()
+                    {
+                        self.show_notes = !self.show_notes;
+                    }
+
+                    if !self.read_only {
+                        ui.menu_button("📥 Import", |ui| {
+                            if ui.button("JSON...").clicked() {
+                                self.import_dialog_open = true;
+                                self.import_error.clear();
+                                ui.close_menu();
+                            }
+                            if ui.button(".env...").clicked() {
+                                ui.close_menu();
+                                self.import_text_file(PlaintextFormat::Env, current_time);
+                            }
+                            ui.separator();
+                            ui.checkbox(&mut self.csv_has_header, tr("CSV mit Kopfzeile"));
+                            if ui.button("CSV...").clicked() {
+                                ui.close_menu();
+                                self.import_text_file(PlaintextFormat::Csv, current_time);
+                            }
+                            ui.separator();
+                            if ui.button(tr("🔑 Passwort-Manager (CSV)...")).clicked() {
+                                ui.close_menu();
+                                self.open_login_import(current_time);
+                            }
+                        });
+                    }
+                    ui.menu_button("📤 Export", |ui| {
+                        for format in [
+                            PlaintextFormat::Json,
+                            PlaintextFormat::Env,
+                            PlaintextFormat::Csv,
+                        ] {
+                            if ui.button(format!("{}...", format.label())).clicked() {
+                                self.export_confirm = Some(format);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+
+                    let (theme_icon, theme_hint) = match self.theme {
+                        Theme::Dark => ("☀", "Helles Design"),
+                        Theme::Light => ("🌙", "Dunkles Design"),
+                    };
+                    if ui
+                        .add(
+                            egui::Button::new(theme_icon)
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr(theme_hint))
+                        .clicked()
+                    {
+                        self.theme = match self.theme {
+                            Theme::Dark => Theme::Light,
+                            Theme::Light => Theme::Dark,
+                        };
+                        ui.ctx().set_visuals(self.theme.visuals());
+                        self.save_settings();
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new("▦")
+                                .fill(if self.grid_view {
+                                    egui::Color32::from_rgb(52, 144, 220)
+                                } else {
+                                    egui::Color32::from_rgb(108, 117, 125)
+                                })
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr(if self.grid_view {
+                            "Kartenansicht"
+                        } else {
+                            "Tabellenansicht"
+                        }))
+                        .clicked()
+                    {
+                        self.grid_view = !self.grid_view;
+                        self.rename_key = None;
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new("{ }")
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr("JSON-Quelltext anzeigen"))
+                        .clicked()
+                    {
+                        self.json_source_open = !self.json_source_open;
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new("⚙")
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr("Einstellungen"))
+                        .clicked()
+                    {
+                        self.settings_open = true;
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new("❓")
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr("Tastenkürzel"))
+                        .clicked()
+                    {
+                        self.help_open = !self.help_open;
+                    }
+
+                    if ui
+                        .add(
+                            egui::Button::new("ℹ")
+                                .fill(egui::Color32::from_rgb(108, 117, 125))
+                                .rounding(egui::Rounding::same(6.0)),
+                        )
+                        .on_hover_text(tr("Über"))
+                        .clicked()
+                    {
+                        if self.about_open {
+                            self.about_open = false;
+                        } else {
+                            self.open_about();
+                        }
+                    }
+
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        let search = ui.add_sized(
+                            [250.0, 28.0],
+                            egui::TextEdit::singleline(&mut self.search_query)
+                                .hint_text(tr("🔍 Einträge durchsuchen...")),
+                        );
+                        if self.search_focus_pending {
+                            search.request_focus();
+                            self.search_focus_pending = false;
+                        }
+                        if search.changed() {
+                            self.search_debounce_until = current_time + SEARCH_DEBOUNCE_SECONDS;
+                            ui.ctx()
+                                .request_repaint_after(std::time::Duration::from_secs_f64(
+                                    SEARCH_DEBOUNCE_SECONDS,
+                                ));
+                        }
+                        if ui
+                            .selectable_label(self.search_regex, ".*")
+                            .on_hover_text(tr("Suche als regulären Ausdruck"))
+                            .clicked()
+                        {
+                            self.search_regex = !self.search_regex;
+                            self.search_fuzzy &= !self.search_regex;
+                        }
+                        if ui
+                            .selectable_label(self.search_fuzzy, "≈")
+                            .on_hover_text(tr(
+                                "Unscharfe Suche: Tippfehler und Lücken erlaubt, beste Treffer zuerst",
+                            ))
+                            .clicked()
+                        {
+                            self.search_fuzzy = !self.search_fuzzy;
+                            self.search_regex &= !self.search_fuzzy;
+                        }
+                        if let Some(e) = self.search_regex_error() {
+                            ui.label(
+                                egui::RichText::new(tr("ungültiger Ausdruck, suche wörtlich"))
+                                    .size(12.0)
+                                    .color(egui::Color32::from_rgb(220, 53, 69)),
+                            )
+                            .on_hover_text(e);
+                        }
+                        egui::ComboBox::from_id_source("search_scope")
+                            .width(130.0)
+                            .selected_text(self.search_scope.label())
+                            .show_ui(ui, |ui| {
+                                for scope in
+                                    [SearchScope::Keys, SearchScope::Values, SearchScope::Both]
+                                {
+                                    ui.selectable_value(
+                                        &mut self.search_scope,
+                                        scope,
+                                        scope.label(),
+                                    );
+                                }
+                            });
+                        if let Some((_, deadline)) = &self.pending_clipboard_clear {
+                            ui.label(
+                                egui::RichText::new(trf(
+                                    "📋 wird in {} s geleert",
+                                    &[&(deadline - current_time).max(0.0).ceil()],
+                                ))
+                                .size(12.0)
+                                .color(egui::Color32::from_gray(170)),
+                            );
+                        }
+                    });
+                });
+            });
+
+        // Encrypted scratchpad
+        if self.show_notes {
+            egui::SidePanel::right("notes_panel")
+                .resizable(true)
+                .default_width(280.0)
+                .show(ctx, |ui| {
+                    ui.add_space(8.0);
+                    ui.strong(tr("📓 Notizen"));
+                    ui.label(
+                        egui::RichText::new(tr("Wird zusammen mit den Einträgen verschlüsselt"))
+                            .size(12.0)
+                            .color(egui::Color32::from_gray(120)),
+                    );
+                    ui.add_space(8.0);
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        if ui
+                            .add_sized(
+                                ui.available_size(),
+                                egui::TextEdit::multiline(&mut self.data.notes)
+                                    .interactive(!self.read_only)
+                                    .hint_text(tr("z.B. Rotationsplan, offene Aufgaben...")),
+                            )
+                            .changed()
+                        {
+                            self.dirty = true;
+                        }
+                    });
+                });
+        }
+
+        // Main content with slide animation
+        egui::CentralPanel::default().show(ctx, |ui| {
+            let rect = ui.available_rect_before_wrap();
+            let offset_rect = rect.translate(egui::vec2(0.0, slide_offset));
+
+            ui.allocate_ui_at_rect(offset_rect, |ui| {
+                ui.add_space(8.0);
+
+                if !self.read_only {
+                    // Add new entry card
+                    egui::Frame::group(ui.style())
+                        .rounding(egui::Rounding::same(12.0))
+                        .fill(card_fill(ui))
+                        .stroke(egui::Stroke::new(
+                            1.5,
+                            egui::Color32::from_rgb(52, 144, 220),
+                        ))
+                        .inner_margin(egui::Margin::symmetric(16.0, 12.0))
+                        .shadow(egui::epaint::Shadow {
+                            offset: egui::vec2(0.0, 2.0),
+                            blur: 8.0,
+                            spread: 0.0,
+                            color: egui::Color32::from_black_alpha(20),
+                        })
+                        .show(ui, |ui| {
+                            ui.horizontal_wrapped(|ui| {
+                                ui.strong(tr("➕ Neuen Eintrag hinzufügen"));
+                            });
+                            ui.add_space(8.0);
+                            ui.horizontal_wrapped(|ui| {
+                                ui.label(tr("Schlüssel:"));
+                                let key_response = ui.add_sized(
+                                    [180.0, 28.0],
+                                    egui::TextEdit::singleline(&mut self.new_key)
+                                        .hint_text(tr("z.B. api_key")),
+                                );
+                                if self.new_key_focus_pending {
+                                    key_response.request_focus();
+                                    self.new_key_focus_pending = false;
+                                }
+                                if key_response.changed() {
+                                    self.new_key_error.clear();
+                                }
+                                if self.key_suggestions && !self.recent_key_names.is_empty() {
+                                    ui.menu_button("🕘", |ui| {
+                                        for recent in &self.recent_key_names {
+                                            let name = next_free_key(&self.data.items, recent);
+                                            if ui.button(&name).clicked() {
+                                                self.new_key = name;
+                                                self.new_key_error.clear();
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text(tr("Zuletzt verwendete Schlüssel"));
+                                }
+
+                                ui.add_space(8.0);
+                                egui::ComboBox::from_id_source("new_value_type")
+                                    .width(80.0)
+                                    .selected_text(self.new_value_type.label())
+                                    .show_ui(ui, |ui| {
+                                        for value_type in [
+                                            ValueType::Text,
+                                            ValueType::Number,
+                                            ValueType::Bool,
+                                            ValueType::Json,
+                                        ] {
+                                            ui.selectable_value(
+                                                &mut self.new_value_type,
+                                                value_type,
+                                                value_type.label(),
+                                            );
+                                        }
+                                    });
+
+                                ui.label(tr("Wert:"));
+                                let multiline = self.new_value_multiline;
+                                let value_height = if multiline { 80.0 } else { 28.0 };
+                                let value_response = match self.new_value_type {
+                                    ValueType::Text => {
+                                        let response = ui.add_sized(
+                                            [250.0, value_height],
+                                            text_edit(&mut self.new_value, multiline)
+                                                .hint_text(tr("z.B. sk-1234567890abcdef")),
+                                        );
+                                        ui.menu_button("🎲", |ui| {
+                                            if let Some(password) =
+                                                show_generator_menu(ui, &mut self.generator)
+                                            {
+                                                self.new_value = password;
+                                            }
+                                        })
+                                        .response
+                                        .on_hover_text(tr("Passwort erzeugen"));
+                                        response
+                                    }
+                                    ValueType::Number => ui
+                                        .add(egui::DragValue::new(&mut self.new_number).speed(0.1)),
+                                    ValueType::Bool => ui.checkbox(&mut self.new_bool, ""),
+                                    ValueType::Json => ui.add_sized(
+                                        [250.0, value_height],
+                                        text_edit(&mut self.new_value, multiline).hint_text(tr(
+                                            "z.B. {\"host\": \"db\", \"port\": 5432}",
+                                        )),
+                                    ),
+                                };
+                                if matches!(self.new_value_type, ValueType::Text | ValueType::Json)
+                                {
+                                    ui.checkbox(&mut self.new_value_multiline, tr("Mehrzeilig"));
+                                }
+
+                                ui.add_space(8.0);
+                                if ui
+                                    .add(
+                                        egui::Button::new(tr("Hinzufügen"))
+                                            .fill(egui::Color32::from_rgb(40, 167, 69))
+                                            .rounding(egui::Rounding::same(6.0))
+                                            .min_size(egui::vec2(80.0, 28.0)),
+                                    )
+                                    .clicked()
+                                {
+                                    self.add_new_entry(current_time, false);
+                                }
+
+                                // Enter key support
+                                if (key_response.lost_focus() || value_response.lost_focus())
+                                    && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                                {
+                                    self.add_new_entry(current_time, false);
+                                }
+                            });
+                            if self.new_value_type == ValueType::Text
+                                && has_outer_whitespace(&self.new_value)
+                            {
+                                show_whitespace_hint(ui);
+                            }
+                            self.show_key_suggestion(ui);
+                            if !self.new_key_error.is_empty() {
+                                ui.colored_label(
+                                    egui::Color32::from_rgb(220, 53, 69),
+                                    format!("⚠ {}", tr(&self.new_key_error)),
+                                );
+                            } else if normalize_key(&self.new_key)
+                                .is_some_and(|key| key != self.new_key)
+                            {
+                                ui.label(
+                                    egui::RichText::new(tr(
+                                        "Leerzeichen am Anfang und Ende werden entfernt",
+                                    ))
+                                    .size(12.0)
+                                    .color(egui::Color32::from_gray(140)),
+                                );
+                            }
+                            ui.add_space(4.0);
+                            ui.horizontal(|ui| {
+                                egui::ComboBox::from_id_source("new_entry_visibility")
+                                    .selected_text(self.new_entry_visibility.label())
+                                    .show_ui(ui, |ui| {
+                                        for visibility in [
+                                            NewEntryVisibility::Masked,
+                                            NewEntryVisibility::RevealBriefly,
+                                            NewEntryVisibility::Revealed,
+                                        ] {
+                                            ui.selectable_value(
+                                                &mut self.new_entry_visibility,
+                                                visibility,
+                                                visibility.label(),
+                                            );
+                                        }
+                                    });
+                                ui.label(
+                                    egui::RichText::new(tr("Sichtbarkeit neuer Einträge"))
+                                        .size(12.0)
+                                        .color(egui::Color32::from_gray(140)),
+                                );
+                            });
+                        });
+
+                    ui.add_space(12.0);
+                    ui.separator();
+                }
+                let keys = self.visible_keys(current_time);
+                let items = &self.data.items;
+                self.selected_keys.retain(|key| items.contains_key(key));
+                ui.horizontal(|ui| {
+                    if !self.read_only {
+                        let mut all_selected = !keys.is_empty()
+                            && keys.iter().all(|key| self.selected_keys.contains(key));
+                        if ui
+                            .checkbox(&mut all_selected, tr("Alle auswählen"))
+                            .on_hover_text(tr("Gilt für die aktuell angezeigten Einträge"))
+                            .changed()
+                        {
+                            if all_selected {
+                                self.selected_keys.extend(keys.iter().cloned());
+                            } else {
+                                for key in &keys {
+                                    self.selected_keys.remove(key);
+                                }
+                            }
+                        }
+                        if !self.selected_keys.is_empty()
+                            && ui
+                                .add(
+                                    egui::Button::new(trf(
+                                        "🗑 Ausgewählte löschen ({})",
+                                        &[&self.selected_keys.len()],
+                                    ))
+                                    .fill(egui::Color32::from_rgb(220, 53, 69))
+                                    .rounding(egui::Rounding::same(6.0)),
+                                )
+                                .clicked()
+                        {
+                            self.delete_confirm_text.clear();
+                            self.bulk_delete_open = true;
+                        }
+                        if !self.selected_keys.is_empty()
+                            && ui
+                                .add(
+                                    egui::Button::new(trf(
+                                        "📦 Als Vault teilen ({})",
+                                        &[&self.selected_keys.len()],
+                                    ))
+                                    .fill(egui::Color32::from_rgb(52, 144, 220))
+                                    .rounding(egui::Rounding::same(6.0)),
+                                )
+                                .on_hover_text(tr(
+                                    "Ausgewählte Einträge mit eigenem Passwort in eine neue Datei",
+                                ))
+                                .clicked()
+                        {
+                            self.share_export_open = true;
+                        }
+                        ui.separator();
+                    }
+                    let sort_before = self.sort_mode;
+                    let by_score = self.search_fuzzy && !self.search_query_lower.is_empty();
+                    ui.add_enabled_ui(!by_score, |ui| {
+                        egui::ComboBox::from_label(tr("Sortierung"))
+                            .selected_text(if by_score {
+                                tr("Nach Übereinstimmung")
+                            } else {
+                                self.sort_mode.label()
+                            })
+                            .show_ui(ui, |ui| {
+                                for mode in SortMode::ALL {
+                                    ui.selectable_value(&mut self.sort_mode, mode, mode.label());
+                                }
+                            });
+                    });
+                    if self.sort_mode != sort_before {
+                        self.restore_list_position();
+                    }
+                    let (expired, expiring) = self.data.expiry_counts(SystemTime::now());
+                    if expired > 0 {
+                        ui.label(
+                            egui::RichText::new(trf("⏰ {} Einträge abgelaufen", &[&expired]))
+                                .size(12.0)
+                                .color(egui::Color32::from_rgb(220, 53, 69)),
+                        );
+                    }
+                    if expiring > 0 {
+                        ui.label(
+                            egui::RichText::new(trf("⏰ {} laufen bald ab", &[&expiring]))
+                                .size(12.0)
+                                .color(egui::Color32::from_rgb(255, 193, 7)),
+                        );
+                    }
+                    if self.reuse_warnings {
+                        self.refresh_reused_values();
+                        let reused = count_reused_values(&self.data.items, &self.reused_values);
+                        if reused > 0 {
+                            ui.label(
+                                egui::RichText::new(trf(
+                                    "⚠ {} Werte werden mehrfach verwendet",
+                                    &[&reused],
+                                ))
+                                .size(12.0)
+                                .color(egui::Color32::from_rgb(255, 193, 7)),
+                            );
+                        }
+                    }
+                });
+                self.show_tag_filter_bar(ui);
+                ui.add_space(8.0);
+
+                // Items list with animations
+                if self.data.items.is_empty() {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(40.0);
+                        ui.label(
+                            egui::RichText::new(tr("📝 Noch keine Einträge vorhanden"))
+                                .size(16.0)
+                                .color(egui::Color32::from_gray(120)),
+                        );
+                        ui.add_space(8.0);
+                        ui.label(
+                            egui::RichText::new(tr("Füge oben deinen ersten Eintrag hinzu"))
+                                .size(14.0)
+                                .color(egui::Color32::from_gray(100)),
+                        );
+                        ui.add_space(40.0);
+                    });
+                } else if self.grid_view {
+                    self.show_entry_grid(ui, &keys, current_time);
+                } else {
+                    let (favorites, keys) = self.split_favorites(keys);
+                    if !favorites.is_empty() {
+                        ui.label(
+                            egui::RichText::new(tr("⭐ Favoriten"))
+                                .size(12.0)
+                                .color(egui::Color32::from_gray(140)),
+                        );
+                        // Favoriten sind wenige, daher ohne virtuelle Liste; viele
+                        // dürfen aber nicht die übrige Liste verdrängen
+                        egui::ScrollArea::vertical()
+                            .id_source("favorites")
+                            .max_height(ui.available_height() * 0.4)
+                            .auto_shrink([false, true])
+                            .show(ui, |ui| {
+                                for key in &favorites {
+                                    self.show_animated_row(ui, key, current_time);
+                                }
+                            });
+                        ui.separator();
+                    }
+                    // Nur die sichtbaren Zeilen werden aufgebaut; höhere Zeilen
+                    // (Notiz offen, mehrzeilig) verschieben nur die Schätzung
+                    // Die Zeile erst in den sichtbaren Bereich holen; genau
+                    // ausgerichtet wird sie dann beim Zeichnen
+                    let mut scroll_area = egui::ScrollArea::vertical().auto_shrink([false; 2]);
+                    if let Some(target) = &self.scroll_to_key {
+                        match keys.iter().position(|key| key == target) {
+                            Some(index) => {
+                                let stride = ENTRY_ROW_HEIGHT + ui.spacing().item_spacing.y;
+                                scroll_area =
+                                    scroll_area.vertical_scroll_offset(index as f32 * stride);
+                            }
+                            None => self.scroll_to_key = None,
+                        }
+                    } else if let Some(offset) = self.list_scroll_restore.take() {
+                        scroll_area = scroll_area.vertical_scroll_offset(offset);
+                    }
+                    let output =
+                        scroll_area.show_rows(ui, ENTRY_ROW_HEIGHT, keys.len(), |ui, range| {
+                            for key in &keys[range] {
+                                self.show_animated_row(ui, key, current_time);
+                            }
+                        });
+                    self.list_scroll_offset = output.state.offset.y;
+                }
+
+                // Error display
+                if !self.error_message.is_empty() {
+                    ui.add_space(8.0);
+                    ui.colored_label(egui::Color32::from_rgb(220, 53, 69), &self.error_message);
+                }
+            });
+        });
+
+        // Erst jetzt: ein fokussiertes Wertfeld hat die Eingaben dieses Frames
+        // (z.B. den letzten Tastendruck vor Strg+S) bereits in `data` übernommen
+        if let Some(ignore_rules) = self.save_requested.take() {
+            if self.key_job.is_none() {
+                self.save(current_time, ignore_rules);
+            }
+        }
+    }
+}
+
+impl eframe::App for App {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        let dt = ctx.input(|i| i.stable_dt);
+        self.update_animations(ctx, dt);
+        self.track_window_geometry(ctx);
+        self.handle_close_request(ctx);
+        self.handle_dropped_files(ctx);
+        self.poll_key_job(ctx.input(|i| i.time));
+        self.handle_panic_shortcut(ctx);
+        self.sync_server(ctx.input(|i| i.time));
+        self.show_tab_bar(ctx);
+
+        if self.dirty != self.title_shows_dirty {
+            self.title_shows_dirty = self.dirty;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Title(if self.dirty {
+                "● Encrypted JSON Editor".to_string()
+            } else {
+                "Encrypted JSON Editor".to_string()
+            }));
+        }
+
+        match self.screen {
+            Screen::Setup => {
+                self.show_setup_screen(ctx);
+            }
+            Screen::PasswordInput => {
+                self.show_login_screen(ctx);
+            }
+            Screen::Editor => {
+                self.show_editor_screen(ctx);
+            }
+        }
+
+        // Show overlays
+        self.show_key_job_overlay(ctx);
+        self.show_toasts(ctx);
+        self.show_delete_confirm_dialog(ctx);
+        self.show_validation_save_dialog(ctx);
+        self.show_external_change_dialog(ctx);
+        self.show_bulk_delete_dialog(ctx);
+        self.show_clear_all_dialog(ctx);
+        self.show_share_export_dialog(ctx);
+        self.show_overwrite_confirm_dialog(ctx);
+        self.show_close_confirm_dialog(ctx);
+        self.show_tab_close_dialog(ctx);
+        self.show_export_confirm_dialog(ctx);
+        self.show_dropped_import_dialog(ctx);
+        self.show_import_collision_dialog(ctx);
+        self.show_import_dialog(ctx);
+        self.show_login_import_dialog(ctx);
+        self.show_settings_dialog(ctx);
+        self.show_help_dialog(ctx);
+        self.show_about_dialog(ctx);
+        self.show_command_palette(ctx);
+        self.show_json_source_dialog(ctx);
+        self.show_password_change_dialog(ctx);
+        self.show_recovery_dialog(ctx);
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.save_settings();
+    }
+}
+
+fn main() -> eframe::Result<()> {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    if cli::is_pipe_mode(&args) {
+        std::process::exit(cli::run_pipe(&args));
+    }
+    if let Some(command) = args.first().filter(|arg| cli::is_command(arg)) {
+        std::process::exit(cli::run(command, &args[1..]));
+    }
+    let read_only = args.iter().any(|arg| arg == "--read-only");
+    args.retain(|arg| arg != "--read-only");
+    let mut serve_config = None;
+    if let Some(index) = args.iter().position(|arg| arg == "--serve") {
+        args.remove(index);
+        let addr = match args.get(index).map(|arg| serve::parse_serve_addr(arg)) {
+            Some(Ok(addr)) => addr,
+            Some(Err(e)) => {
+                eprintln!("Fehler: {}", e);
+                std::process::exit(cli::EXIT_USAGE);
+            }
+            None => {
+                eprintln!("Fehler: --serve braucht eine Adresse wie 127.0.0.1:8787");
+                print_usage();
+                std::process::exit(cli::EXIT_USAGE);
+            }
+        };
+        args.remove(index);
+        let token = serve::new_token();
+        eprintln!(
+            "HTTP-Zugang nach dem Entsperren auf http://{}/entries, Token: {}",
+            addr, token
+        );
+        serve_config = Some((addr, token));
+    }
+    let mut policy_override = None;
+    if let Some(index) = args.iter().position(|arg| arg == "--policy") {
+        args.remove(index);
+        if index >= args.len() {
+            eprintln!("Fehler: --policy braucht Vorgaben wie min=12,upper,digit");
+            print_usage();
+            std::process::exit(cli::EXIT_USAGE);
+        }
+        match PasswordPolicy::parse(&args.remove(index)) {
+            Ok(policy) => policy_override = Some(policy),
+            Err(e) => {
+                eprintln!("Fehler: {}", e);
+                std::process::exit(cli::EXIT_USAGE);
+            }
+        }
+    }
+
+    let cli_arg = args.into_iter().next();
+    if let Some(arg) = &cli_arg {
+        if arg == "-h" || arg == "--help" {
+            print_usage();
+            return Ok(());
+        }
+        if arg == "-V" || arg == "--version" {
+            print_version();
+            return Ok(());
+        }
+        if let Err(e) = check_vault_arg(arg) {
+            eprintln!("Fehler: {}", e);
+            print_usage();
+            std::process::exit(cli::EXIT_USAGE);
+        }
+    }
+
+    let settings = load_settings();
+    i18n::set_language(settings.language.unwrap_or_else(Language::from_environment));
+
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(DEFAULT_WINDOW_SIZE)
+        .with_min_inner_size(MIN_WINDOW_SIZE)
+        .with_icon(eframe::icon_data::from_png_bytes(&[]).unwrap_or_default());
+    if let Some(window) = settings.window {
+        viewport = viewport
+            .with_inner_size([
+                window.width.max(MIN_WINDOW_SIZE[0]),
+                window.height.max(MIN_WINDOW_SIZE[1]),
+            ])
+            .with_position([window.x, window.y]);
+    }
+    let options = eframe::NativeOptions {
+        viewport,
+        ..Default::default()
+    };
+    let (vault_path, source) = resolve_vault_path(cli_arg);
+    eprintln!(
+        "Vault-Pfad: {} (Quelle: {})",
+        vault_path.display(),
+        source.label()
+    );
+
+    eframe::run_native(
+        "Encrypted JSON Editor",
+        options,
+        Box::new(move |cc| {
+            let mut app = App::default();
+            app.set_vault_path(vault_path, source);
+            app.set_read_only(read_only);
+            app.serve_config = serve_config;
+            app.policy_override = policy_override;
+            app.apply_settings(settings);
+            cc.egui_ctx.set_visuals(app.theme.visuals());
+            Ok(Box::new(app))
+        }),
+    )
+}
+
+fn project_dirs() -> Option<directories::ProjectDirs> {
+    directories::ProjectDirs::from("", "", "encrypted-json-editor")
+}
+
+/// Bestimmt den Vault-Pfad in fester Reihenfolge:
+/// Kommandozeile > Umgebungsvariable > zuletzt verwendet > Datenverzeichnis > Arbeitsverzeichnis.
+/// Ein bestehender Legacy-Vault im Arbeitsverzeichnis wird dem (noch leeren)
+/// Datenverzeichnis vorgezogen, damit alte Installationen ihre Daten behalten.
+fn resolve_vault_path(cli_arg: Option<String>) -> (PathBuf, VaultPathSource) {
+    if let Some(path) = cli_arg.filter(|p| !p.trim().is_empty()) {
+        return (PathBuf::from(path), VaultPathSource::CliArgument);
+    }
+    if let Some(path) = std::env::var_os(VAULT_PATH_ENV).filter(|p| !p.is_empty()) {
+        return (PathBuf::from(path), VaultPathSource::Environment);
+    }
+    let dirs = project_dirs();
+    if let Some(path) = dirs
+        .as_ref()
+        .and_then(|d| fs::read_to_string(d.config_dir().join(LAST_VAULT_FILE)).ok())
+        .map(|p| PathBuf::from(p.trim()))
+        .filter(|p| p.exists())
+    {
+        return (path, VaultPathSource::LastUsed);
+    }
+    let legacy = PathBuf::from(ENCRYPTED_FILE);
+    match dirs.map(|d| d.data_dir().join(ENCRYPTED_FILE)) {
+        Some(path) if path.exists() || !legacy.exists() => (path, VaultPathSource::DataDir),
+        _ => (legacy, VaultPathSource::WorkingDirectory),
+    }
+}
+
+/// Prüft das Kommandozeilenargument, bevor das Fenster aufgeht
+fn check_vault_arg(arg: &str) -> Result<PathBuf, String> {
+    if arg.starts_with('-') {
+        return Err(format!("Unbekannte Option: {}", arg));
+    }
+    let path = PathBuf::from(arg);
+    if path.is_dir() {
+        return Err(format!("{} ist ein Verzeichnis, keine Vault-Datei", arg));
+    }
+    Ok(path)
+}
+
+/// Version und Standardwerte für neue Vaults, für `--version` und den Über-Dialog
+fn build_info() -> Vec<(&'static str, String)> {
+    vec![
+        ("Version", env!("CARGO_PKG_VERSION").to_string()),
+        ("Dateiformat", FORMAT_VERSION.to_string()),
+        (
+            "Verschlüsselung",
+            CipherSuite::default().label().to_string(),
+        ),
+        ("Schlüsselableitung", KdfParams::default().describe()),
+    ]
+}
+
+fn print_version() {
+    println!("encrypted-json-editor {}", env!("CARGO_PKG_VERSION"));
+    for (label, value) in build_info().into_iter().skip(1) {
+        println!("  {}: {}", label, value);
+    }
+}
+
+fn print_usage() {
+    eprintln!(
+        "Verwendung: encrypted-json-editor [--read-only] [--serve 127.0.0.1:PORT] [--policy REGELN] [VAULT-DATEI]"
+    );
+    eprintln!(
+        "            encrypted-json-editor <BEFEHL> [--vault VAULT-DATEI] [--key-file DATEI]"
+    );
+    eprintln!("            encrypted-json-editor --decrypt|--encrypt --stdin [--key-file DATEI]");
+    eprintln!();
+    eprintln!("  VAULT-DATEI  Pfad zur verschlüsselten Datei (wird bei Bedarf angelegt).");
+    eprintln!("               Ältere Vaults lesen ihr Salt aus <VAULT-DATEI>.salt.");
+    eprintln!("  --read-only  Vault im Lesemodus öffnen (nur ansehen und kopieren).");
+    eprintln!("  --version    Version, Dateiformat und Standard-Verschlüsselung ausgeben.");
+    eprintln!("  --serve ADR  Einträge nach dem Entsperren als JSON über HTTP anbieten,");
+    eprintln!("               nur auf Loopback und mit dem beim Start ausgegebenen Token:");
+    eprintln!("               curl -H 'Authorization: Bearer TOKEN' http://ADR/entries");
+    eprintln!("  --policy REGELN  Passwort-Richtlinie für neue Vaults und Passwortwechsel,");
+    eprintln!("               z.B. min=12,lower,upper,digit,symbol (statt der Einstellungen).");
+    eprintln!();
+    eprintln!("Befehle (ohne Oberfläche, Passwort aus $VAULT_PASSWORD oder Eingabe):");
+    eprintln!("  list                 Alle Schlüssel ausgeben");
+    eprintln!("  get <KEY>            Wert ausgeben");
+    eprintln!("  set <KEY> <WERT>     Wert als Text setzen und speichern");
+    eprintln!("  remove <KEY>         Eintrag löschen und speichern");
+    eprintln!("  --decrypt --stdin    Vault von stdin lesen, JSON nach stdout");
+    eprintln!("  --encrypt --stdin    Vault-JSON von stdin lesen, Vault nach stdout");
+    eprintln!();
+    eprintln!(
+        "Exit-Codes: 0 ok, {} Fehler, {} falscher Aufruf, {} Schlüssel nicht gefunden",
+        cli::EXIT_ERROR,
+        cli::EXIT_USAGE,
+        cli::EXIT_NOT_FOUND
+    );
+    eprintln!();
+    eprintln!(
+        "Ohne Argument: ${} > zuletzt verwendet > Datenverzeichnis > ./{}",
+        VAULT_PATH_ENV, ENCRYPTED_FILE
+    );
+}
+
+/// Wartezeit nach `failed` Fehlversuchen: erst keine, dann ab 5 s jeweils doppelt so lang
+fn login_delay(failed: u32) -> Duration {
+    if failed < FREE_LOGIN_ATTEMPTS {
+        return Duration::ZERO;
+    }
+    let doublings = (failed - FREE_LOGIN_ATTEMPTS).min(16);
+    Duration::from_secs((5u64 << doublings).min(MAX_LOGIN_DELAY_SECONDS))
+}
+
+/// Richtlinie unter den Passwortfeldern; rot, solange die Eingabe sie verletzt
+fn show_policy_hint(ui: &mut egui::Ui, policy: &PasswordPolicy, password: &str) {
+    if !policy.is_active() {
+        return;
+    }
+    let color = if password.is_empty() || policy.check(password).is_ok() {
+        egui::Color32::from_gray(140)
+    } else {
+        egui::Color32::from_rgb(220, 53, 69)
+    };
+    ui.label(
+        egui::RichText::new(policy.describe())
+            .size(12.0)
+            .color(color),
+    );
+}
+
+/// Auswahl einer Schlüsseldatei mit Dateidialog; ✖ entfernt sie wieder
+fn show_key_file_picker(ui: &mut egui::Ui, path: &mut Option<PathBuf>, required: bool) {
+    match path {
+        Some(current) => {
+            let name = current
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            ui.label(egui::RichText::new(name).strong())
+                .on_hover_text(current.display().to_string());
+        }
+        None if required => {
+            ui.colored_label(
+                egui::Color32::from_rgb(220, 53, 69),
+                tr(ERR_KEY_FILE_REQUIRED),
+            );
+        }
+        None => {
+            ui.weak(tr("keine"));
+        }
+    }
+    if ui.small_button(tr("Wählen…")).clicked() {
+        if let Some(picked) = rfd::FileDialog::new().pick_file() {
+            *path = Some(picked);
+        }
+    }
+    if path.is_some()
+        && ui
+            .small_button("✖")
+            .on_hover_text(tr("Keine Schlüsseldatei"))
+            .clicked()
+    {
+        *path = None;
+    }
+}
+
+/// Setzt `vault_path` an den Anfang der Liste, ohne Duplikate und höchstens `MAX_RECENT_FILES`
+fn remember_recent_file(recent_files: &mut Vec<PathBuf>, vault_path: &Path) {
+    let path = fs::canonicalize(vault_path).unwrap_or_else(|_| vault_path.to_path_buf());
+    recent_files.retain(|recent| *recent != path);
+    recent_files.insert(0, path);
+    recent_files.truncate(MAX_RECENT_FILES);
+}
+
+fn remember_last_vault(vault_path: &Path) {
+    let Some(dirs) = project_dirs() else {
+        return;
+    };
+    let path = fs::canonicalize(vault_path).unwrap_or_else(|_| vault_path.to_path_buf());
+    if fs::create_dir_all(dirs.config_dir()).is_ok() {
+        let _ = fs::write(
+            dirs.config_dir().join(LAST_VAULT_FILE),
+            path.display().to_string(),
+        );
+    }
+}
+
+/// Schätzt die Passwortstärke von 0 (sehr schwach) bis 4 (sehr stark)
+fn password_score(password: &str) -> u8 {
+    u8::from(zxcvbn::zxcvbn(password, &[]).score())
+}
+
+/// Farbiger Balken mit Bewertung; schwache Passwörter werden nur angemahnt
+fn show_strength_bar(ui: &mut egui::Ui, score: u8) {
+    let (label, color) = match score {
+        0 => ("Sehr schwach", egui::Color32::from_rgb(220, 53, 69)),
+        1 => ("Schwach", egui::Color32::from_rgb(253, 126, 20)),
+        2 => ("Mittel", egui::Color32::from_rgb(255, 193, 7)),
+        3 => ("Stark", egui::Color32::from_rgb(40, 167, 69)),
+        _ => ("Sehr stark", egui::Color32::from_rgb(52, 144, 220)),
+    };
+
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(ui.available_width(), 6.0), egui::Sense::hover());
+    let rounding = egui::Rounding::same(3.0);
+    ui.painter()
+        .rect_filled(rect, rounding, egui::Color32::from_gray(70));
+    let mut filled = rect;
+    filled.set_width(rect.width() * (score as f32 + 1.0) / 5.0);
+    ui.painter().rect_filled(filled, rounding, color);
+
+    ui.add_space(4.0);
+    ui.label(
+        egui::RichText::new(trf("Passwortstärke: {}", &[&tr(label)]))
+            .color(color)
+            .size(12.0),
+    );
+    if score < 3 {
+        ui.label(
+            egui::RichText::new(tr(
+                "⚠ Leicht zu erraten – ein längeres Passwort ist sicherer",
+            ))
+            .color(egui::Color32::from_gray(160))
+            .size(12.0),
+        );
+    }
+}
+
+/// "gerade eben", "vor 5 Minuten", "vor 3 Tagen", ...
+fn format_relative_time(then: SystemTime, now: SystemTime) -> String {
+    format_relative_time_in(i18n::language(), then, now)
+}
+
+fn format_relative_time_in(language: Language, then: SystemTime, now: SystemTime) -> String {
+    let seconds = now.duration_since(then).unwrap_or(Duration::ZERO).as_secs();
+    // (Einheit in Sekunden, deutsch Singular/Plural, englisch)
+    let (unit, singular, plural, english) = match seconds {
+        0..=59 => return i18n::translate(language, "gerade eben").to_string(),
+        60..=3_599 => (60, "Minute", "Minuten", "minute"),
+        3_600..=86_399 => (3_600, "Stunde", "Stunden", "hour"),
+        86_400..=2_591_999 => (86_400, "Tag", "Tagen", "day"),
+        2_592_000..=31_535_999 => (2_592_000, "Monat", "Monaten", "month"),
+        _ => (31_536_000, "Jahr", "Jahren", "year"),
+    };
+    let amount = seconds / unit;
+    match (language, amount) {
+        (Language::English, 1) if english == "hour" => "an hour ago".to_string(),
+        (Language::English, 1) => format!("a {} ago", english),
+        (Language::English, _) => format!("{} {}s ago", amount, english),
+        (Language::German, 1) if matches!(singular, "Minute" | "Stunde") => {
+            format!("vor einer {}", singular)
+        }
+        (Language::German, 1) => format!("vor einem {}", singular),
+        (Language::German, _) => format!("vor {} {}", amount, plural),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Expiry {
+    /// Läuft in so vielen angefangenen Tagen ab
+    Soon(u64),
+    Expired,
+}
+
+/// `None`, solange das Ablaufdatum mehr als `EXPIRY_WARNING_DAYS` entfernt ist
+fn expiry_state(expires_at: SystemTime, now: SystemTime) -> Option<Expiry> {
+    match expires_at.duration_since(now) {
+        Err(_) => Some(Expiry::Expired),
+        Ok(left) => {
+            let days = left.as_secs().div_ceil(86_400);
+            (days <= EXPIRY_WARNING_DAYS).then_some(Expiry::Soon(days))
+        }
+    }
+}
+
+fn load_settings() -> Settings {
+    project_dirs()
+        .and_then(|d| fs::read_to_string(d.config_dir().join(SETTINGS_FILE)).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_settings(settings: &Settings) {
+    // Tests sollen die echten Einstellungen nicht überschreiben
+    if cfg!(test) {
+        return;
+    }
+    let Some(dirs) = project_dirs() else {
+        return;
+    };
+    if let Ok(json) = serde_json::to_string_pretty(settings) {
+        if fs::create_dir_all(dirs.config_dir()).is_ok() {
+            let _ = fs::write(dirs.config_dir().join(SETTINGS_FILE), json);
+        }
+    }
+}
+
+/// Hintergrund für Karten und Zeilen passend zum aktiven Design
+fn card_fill(ui: &egui::Ui) -> egui::Color32 {
+    if ui.visuals().dark_mode {
+        egui::Color32::DARK_GRAY
+    } else {
+        egui::Color32::from_gray(248)
+    }
+}
+
+/// Einfaches Ease-In-Out (Smoothstep) Helferlein
+fn ease_in_out(t: f32) -> f32 {
+    // clamp zwischen 0 und 1
+    let t = t.clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn has_control_chars(value: &str) -> bool {
+    value.chars().any(char::is_control)
+}
+
+fn strip_control_chars(value: &str) -> String {
+    value.chars().filter(|c| !c.is_control()).collect()
+}
+
+/// Ersetzt Steuerzeichen durch sichtbare Escape-Sequenzen wie `\u{1b}`
+fn escape_control_chars(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_control() {
+                c.escape_default().to_string()
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Verschlüsselt mit zufälliger Nonce; Ergebnis ist `nonce || ciphertext`
+/// Prüft, ob ein Wert als http(s)-Link im Browser geöffnet werden darf.
+/// Andere Schemata (file:, javascript:, ...) werden bewusst abgelehnt.
+fn is_openable_url(value: &str) -> bool {
+    let value = value.trim();
+    let lower = value.to_ascii_lowercase();
+    let rest = if let Some(rest) = lower.strip_prefix("https://") {
+        rest
+    } else if let Some(rest) = lower.strip_prefix("http://") {
+        rest
+    } else {
+        return false;
+    };
+    !rest.is_empty() && !value.chars().any(|c| c.is_whitespace() || c.is_control())
+}
+
+/// Liest Einträge aus einer JSON-Datei, entweder als flaches Objekt
+/// (`{"key": "value"}`) oder als Liste von Objekten (`[{"key": .., "value": ..}]`).
+/// Verschachtelte Werte bleiben als JSON-Baum erhalten.
+fn parse_json_import(
+    text: &str,
+    shape: ImportShape,
+    key_field: &str,
+    value_field: &str,
+) -> Result<Vec<(String, serde_json::Value)>, String> {
+    let json: serde_json::Value = serde_json::from_str(text).map_err(|e| {
+        format!(
+            "Ungültiges JSON in Zeile {}, Spalte {}: {}",
+            e.line(),
+            e.column(),
+            e
+        )
+    })?;
+
+    match (shape, json) {
+        (ImportShape::Auto, serde_json::Value::Object(map)) if is_vault_export(&map) => {
+            vault_export_items(map)
+        }
+        (ImportShape::Vault, serde_json::Value::Object(map)) => vault_export_items(map),
+        (ImportShape::Auto | ImportShape::Object, serde_json::Value::Object(map)) => {
+            Ok(map.into_iter().collect())
+        }
+        (ImportShape::Auto | ImportShape::KeyValueArray, serde_json::Value::Array(list)) => list
+            .iter()
+            .enumerate()
+            .map(|(i, element)| {
+                let object = element
+                    .as_object()
+                    .ok_or_else(|| format!("Element {}: kein Objekt", i + 1))?;
+                let key = object
+                    .get(key_field)
+                    .ok_or_else(|| format!("Element {}: Feld \"{}\" fehlt", i + 1, key_field))?;
+                let key = key.as_str().ok_or_else(|| {
+                    format!("Element {}: Feld \"{}\" ist kein Text", i + 1, key_field)
+                })?;
+                let value = object
+                    .get(value_field)
+                    .ok_or_else(|| format!("Element {}: Feld \"{}\" fehlt", i + 1, value_field))?;
+                Ok((key.to_string(), value.clone()))
+            })
+            .collect(),
+        (ImportShape::Vault | ImportShape::Object, _) => {
+            Err("Erwartet wurde ein JSON-Objekt".into())
+        }
+        (ImportShape::KeyValueArray, _) => Err("Erwartet wurde eine JSON-Liste".into()),
+        (ImportShape::Auto, _) => {
+            Err("Nicht unterstütztes Format: erwartet Objekt oder Liste".into())
+        }
+    }
+}
+
+/// Liest `KEY=VALUE`-Zeilen einer .env-Datei. Leerzeilen und `#`-Kommentare werden
+/// übersprungen, ein vorangestelltes `export ` ist erlaubt. In doppelten Anführungszeichen
+/// gelten Escapes (`\n`, `\"`, ...) und der Wert darf über mehrere Zeilen gehen, in
+/// einfachen Anführungszeichen bleibt er wörtlich. Fehler nennen die Zeilennummer.
+fn parse_env(text: &str) -> Result<Vec<(String, String)>, String> {
+    let mut entries = Vec::new();
+    let mut lines = text.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line_no = index + 1;
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or_else(|| format!("Zeile {}: \"=\" fehlt", line_no))?;
+        let key = key.trim();
+        if !is_env_key(key) {
+            return Err(format!(
+                "Zeile {}: ungültiger Schlüssel \"{}\"",
+                line_no, key
+            ));
+        }
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let mut raw = rest[1..].to_string();
+                let end = loop {
+                    if let Some(end) = find_closing_quote(&raw, quote) {
+                        break end;
+                    }
+                    let (_, next) = lines.next().ok_or_else(|| {
+                        format!("Zeile {}: schließendes {} fehlt", line_no, quote)
+                    })?;
+                    raw.push('\n');
+                    raw.push_str(next);
+                };
+                let trailing = raw[end + 1..].trim();
+                if !trailing.is_empty() && !trailing.starts_with('#') {
+                    return Err(format!(
+                        "Zeile {}: Text nach dem schließenden {}",
+                        line_no, quote
+                    ));
+                }
+                raw.truncate(end);
+                if quote == '"' {
+                    unescape_env(&raw)
+                } else {
+                    raw
+                }
+            }
+            _ => {
+                // Ohne Anführungszeichen beginnt ` #` einen Kommentar
+                let value = rest.find(" #").map_or(rest, |i| &rest[..i]);
+                value.trim_end().to_string()
+            }
+        };
+        entries.push((key.to_string(), value));
+    }
+    Ok(entries)
+}
+
+/// Schreibt Einträge als .env. Werte mit Sonderzeichen werden in doppelte
+/// Anführungszeichen gesetzt und escaped; Schlüssel, die sich nicht als
+/// Variablenname eignen, sowie Anhänge und JSON werden übersprungen und gezählt.
+fn format_env(items: &IndexMap<String, EntryValue>) -> (String, usize) {
+    let mut out = String::new();
+    let mut skipped = 0;
+    for (key, value) in items {
+        let Some(value) = value.as_flat_text().filter(|_| is_env_key(key)) else {
+            skipped += 1;
+            continue;
+        };
+        let plain = value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@+,%".contains(c));
+        if plain {
+            out.push_str(&format!("{}={}\n", key, value));
+        } else {
+            out.push_str(&format!("{}=\"{}\"\n", key, escape_env(&value)));
+        }
+    }
+    (out, skipped)
+}
+
+/// Liest zweispaltiges CSV (Schlüssel, Wert). Felder in Anführungszeichen dürfen
+/// Kommas und Zeilenumbrüche enthalten; fehlerhafte Zeilen werden mit Nummer gemeldet.
+fn parse_csv(text: &str, has_header: bool) -> Result<Vec<(String, String)>, String> {
+    let mut entries = Vec::new();
+    for (line, record) in logins::read_csv(text, has_header)?.rows {
+        if record.len() != 2 {
+            return Err(format!(
+                "Zeile {}: erwartet 2 Spalten (Schlüssel, Wert), gefunden {}",
+                line,
+                record.len()
+            ));
+        }
+        if record[0].is_empty() {
+            return Err(format!("Zeile {}: leerer Schlüssel", line));
+        }
+        let [key, value]: [String; 2] = record.try_into().expect("zwei Spalten");
+        entries.push((key, value));
+    }
+    Ok(entries)
+}
+
+/// Schreibt zweispaltiges CSV (Schlüssel, Wert). Anhänge und JSON passen nicht
+/// in eine Textspalte und werden wie bei .env übersprungen und gezählt.
+fn format_csv(items: &IndexMap<String, EntryValue>) -> Result<(String, usize), String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["key", "value"])
+        .map_err(|e| e.to_string())?;
+    let mut skipped = 0;
+    for (key, value) in items {
+        let Some(value) = value.as_flat_text() else {
+            skipped += 1;
+            continue;
+        };
+        writer
+            .write_record([key.as_str(), value.as_str()])
+            .map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    let text = String::from_utf8(bytes).map_err(|e| e.to_string())?;
+    Ok((text, skipped))
+}
+
+fn is_env_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+/// Position des schließenden Anführungszeichens; in `"..."` zählt `\"` nicht
+fn find_closing_quote(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' && quote == '"' {
+            escaped = true;
+        } else if c == quote {
+            return Some(i);
+        }
+    }
+    None
+}
+
+fn escape_env(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '$' => out.push_str("\\$"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn unescape_env(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some(c @ ('\\' | '"' | '$')) => out.push(c),
+            Some(c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Erkennt einen eigenen Export: nur die Felder von `AppData`, `items` als Objekt
+fn is_vault_export(map: &serde_json::Map<String, serde_json::Value>) -> bool {
+    map.get("items").is_some_and(serde_json::Value::is_object)
+        && map
+            .keys()
+            .all(|key| matches!(key.as_str(), "items" | "notes" | "critical" | "meta"))
+}
+
+fn vault_export_items(
+    mut map: serde_json::Map<String, serde_json::Value>,
+) -> Result<Vec<(String, serde_json::Value)>, String> {
+    match map.remove("items") {
+        Some(serde_json::Value::Object(items)) => Ok(items.into_iter().collect()),
+        _ => Err("Kein gültiger Vault-Export: Feld \"items\" fehlt".into()),
+    }
+}
+
+/// `"key": value` zum Einfügen in eine JSON-Datei
+fn entry_json_snippet(key: &str, value: &EntryValue) -> String {
+    format!(
+        "{}: {}",
+        serde_json::Value::String(key.to_string()),
+        serde_json::to_string(value).unwrap_or_default()
+    )
+}
+
+/// Dateiname ohne Endung als Beschriftung eines Tabs
+fn tab_label(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Leerzeichen oder Zeilenumbrüche am Rand, wie sie beim Kopieren von API-Keys
+/// gern mitkommen
+fn has_outer_whitespace(text: &str) -> bool {
+    text.trim() != text
+}
+
+fn show_whitespace_hint(ui: &mut egui::Ui) {
+    ui.label(
+        egui::RichText::new(tr("␣ Leerzeichen oder Zeilenumbruch am Anfang/Ende"))
+            .size(12.0)
+            .color(egui::Color32::from_rgb(255, 193, 7)),
+    );
+}
+
+/// Schlüssel werden ohne führende und folgende Leerzeichen gespeichert; was dann
+/// leer bleibt, ist kein gültiger Schlüssel. Groß- und Kleinschreibung zählt wie
+/// in JSON-Objekten, `API` und `api` sind also zwei verschiedene Einträge.
+pub(crate) fn normalize_key(key: &str) -> Option<String> {
+    let key = key.trim();
+    (!key.is_empty()).then(|| key.to_string())
+}
+
+/// Schlüsselname für Werte in bekannten Token-Formaten; spezifischere Präfixe zuerst
+fn suggest_key_for_value(value: &str) -> Option<&'static str> {
+    let value = value.trim();
+    const PREFIXES: &[(&str, &str)] = &[
+        ("sk-ant-", "anthropic_api_key"),
+        ("sk_live_", "stripe_secret_key"),
+        ("sk_test_", "stripe_test_secret_key"),
+        ("pk_live_", "stripe_publishable_key"),
+        ("sk-", "openai_api_key"),
+        ("ghp_", "github_token"),
+        ("github_pat_", "github_token"),
+        ("glpat-", "gitlab_token"),
+        ("xoxb-", "slack_bot_token"),
+        ("xoxp-", "slack_user_token"),
+        ("https://hooks.slack.com/", "slack_webhook_url"),
+        ("AKIA", "aws_access_key_id"),
+        ("AIza", "google_api_key"),
+        ("SG.", "sendgrid_api_key"),
+        ("postgres://", "database_url"),
+        ("postgresql://", "database_url"),
+        ("mysql://", "database_url"),
+        ("mongodb://", "mongodb_uri"),
+        ("mongodb+srv://", "mongodb_uri"),
+        ("redis://", "redis_url"),
+        ("-----BEGIN", "private_key"),
+    ];
+    if let Some((_, key)) = PREFIXES
+        .iter()
+        .find(|(prefix, _)| value.len() > prefix.len() && value.starts_with(prefix))
+    {
+        return Some(key);
+    }
+    // JWT: drei base64url-Teile, der Header beginnt immer mit `{"`
+    (value.starts_with("eyJ") && value.split('.').count() == 3).then_some("jwt")
+}
+
+/// `name`, oder wenn der schon vergeben ist, mit der nächsten freien Nummer am Ende:
+/// aus `server_2` wird `server_3`, aus `api_key` wird `api_key_2`
+fn next_free_key(items: &IndexMap<String, EntryValue>, name: &str) -> String {
+    if !items.contains_key(name) {
+        return name.to_string();
+    }
+    let taken = |candidate: &str| items.contains_key(candidate);
+    let stem = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    // Bei u64::MAX als Endzahl lässt sich nicht weiterzählen
+    let (stem, start) = match name[stem.len()..].parse::<u64>().map(|n| n.checked_add(1)) {
+        Ok(Some(next)) => (stem.to_string(), next),
+        Ok(None) => return unique_key(name, taken),
+        Err(_) => (format!("{}_", name), 2),
+    };
+    (start..=u64::MAX)
+        .map(|n| format!("{}{}", stem, n))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_else(|| unique_key(name, taken))
+}
+
+/// Wie `remember_recent_file`, für Schlüsselnamen im Hinzufügen-Formular
+fn remember_key_name(recent: &mut Vec<String>, key: &str) {
+    recent.retain(|name| name != key);
+    recent.insert(0, key.to_string());
+    recent.truncate(MAX_RECENT_KEY_NAMES);
+}
+
+/// Hängt ` (2)`, ` (3)`, ... an, bis `taken` den Schlüssel nicht mehr kennt
+fn unique_key(key: &str, taken: impl Fn(&str) -> bool) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", key, n))
+        .find(|candidate| !taken(candidate))
+        .expect("unbegrenzte Folge")
+}
+
+/// Wendet `f` auf alle Text-Blätter eines JSON-Baums an
+fn map_json_strings(value: &mut serde_json::Value, f: fn(&str) -> String) {
+    match value {
+        serde_json::Value::String(s) => *s = f(s),
+        serde_json::Value::Array(list) => list.iter_mut().for_each(|v| map_json_strings(v, f)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| map_json_strings(v, f)),
+        _ => {}
+    }
+}
+
+/// Zeichnet einen JSON-Wert als editierbaren Baum. Objekte und Listen werden
+/// einklappbar mit ihrem Pfad (`a › b › c`) als Breadcrumb dargestellt.
+/// Liefert `true`, wenn ein Blatt geändert wurde.
+fn show_json_value(
+    ui: &mut egui::Ui,
+    path: &str,
+    value: &mut serde_json::Value,
+    masked: bool,
+    editable: bool,
+) -> bool {
+    match value {
+        serde_json::Value::String(text) => ui
+            .add(
+                egui::TextEdit::singleline(text)
+                    .interactive(editable)
+                    .password(masked)
+                    .desired_width(ui.available_width()),
+            )
+            .changed(),
+        serde_json::Value::Number(number) => {
+            if let Some(mut int) = number.as_i64() {
+                let changed = ui
+                    .add_enabled(editable, egui::DragValue::new(&mut int))
+                    .changed();
+                if changed {
+                    *value = serde_json::Value::from(int);
+                }
+                changed
+            } else {
+                let mut float = number.as_f64().unwrap_or_default();
+                let changed = ui
+                    .add_enabled(editable, egui::DragValue::new(&mut float).speed(0.1))
+                    .changed();
+                if changed {
+                    *value = serde_json::Value::from(float);
+                }
+                changed
+            }
+        }
+        serde_json::Value::Bool(flag) => ui
+            .add_enabled(editable, egui::Checkbox::without_text(flag))
+            .changed(),
+        serde_json::Value::Null => {
+            ui.weak("null");
+            false
+        }
+        serde_json::Value::Array(list) => {
+            let mut changed = false;
+            egui::CollapsingHeader::new(format!("{} [{}]", path, list.len()))
+                .id_source(path)
+                .show(ui, |ui| {
+                    for (index, child) in list.iter_mut().enumerate() {
+                        let child_path = format!("{} › {}", path, index);
+                        changed |= show_json_child(
+                            ui,
+                            &child_path,
+                            &index.to_string(),
+                            child,
+                            masked,
+                            editable,
+                        );
+                    }
+                });
+            changed
+        }
+        serde_json::Value::Object(map) => {
+            let mut changed = false;
+            egui::CollapsingHeader::new(format!("{} {{{}}}", path, map.len()))
+                .id_source(path)
+                .show(ui, |ui| {
+                    for (name, child) in map.iter_mut() {
+                        let child_path = format!("{} › {}", path, name);
+                        changed |= show_json_child(ui, &child_path, name, child, masked, editable);
+                    }
+                });
+            changed
+        }
+    }
+}
+
+/// Fetter Text, in dem der (kleingeschriebene) Suchbegriff farbig hinterlegt ist.
+/// Ändert das Kleinschreiben die Byte-Längen, wird auf die Hervorhebung verzichtet.
+fn highlight_match(ui: &egui::Ui, text: &str, needle_lower: &str) -> egui::text::LayoutJob {
+    let lower = text.to_lowercase();
+    let found = (!needle_lower.is_empty() && lower.len() == text.len())
+        .then(|| lower.find(needle_lower))
+        .flatten()
+        .map(|start| start..start + needle_lower.len());
+    highlight_range(ui, text, found)
+}
+
+/// Wie `highlight_match`, mit bereits gefundenem Bereich (z.B. aus einem Regex)
+fn highlight_range(
+    ui: &egui::Ui,
+    text: &str,
+    found: Option<std::ops::Range<usize>>,
+) -> egui::text::LayoutJob {
+    highlight_ranges(ui, text, found.as_slice())
+}
+
+/// Hebt mehrere aufsteigende, nicht überlappende Bereiche hervor (z.B. aus der
+/// unscharfen Suche)
+fn highlight_ranges(
+    ui: &egui::Ui,
+    text: &str,
+    ranges: &[std::ops::Range<usize>],
+) -> egui::text::LayoutJob {
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let normal = egui::TextFormat::simple(font_id.clone(), ui.visuals().strong_text_color());
+    let highlighted = egui::TextFormat {
+        background: egui::Color32::from_rgb(255, 193, 7),
+        ..egui::TextFormat::simple(font_id, egui::Color32::BLACK)
+    };
+
+    let mut job = egui::text::LayoutJob::default();
+    let mut done = 0;
+    for range in ranges.iter().filter(|range| !range.is_empty()) {
+        job.append(&text[done..range.start], 0.0, normal.clone());
+        job.append(&text[range.clone()], 0.0, highlighted.clone());
+        done = range.end;
+    }
+    job.append(&text[done..], 0.0, normal);
+    job
+}
+
+/// Ein- oder mehrzeiliges Textfeld, mehrzeilig mit vier Zeilen Anfangshöhe
+fn text_edit(text: &mut String, multiline: bool) -> egui::TextEdit<'_> {
+    if multiline {
+        egui::TextEdit::multiline(text).desired_rows(4)
+    } else {
+        egui::TextEdit::singleline(text)
+    }
+}
+
+/// Feste Id des Wertfelds, damit die Befehlspalette den Fokus hineinsetzen kann
+fn entry_value_id(key: &str) -> egui::Id {
+    egui::Id::new(("entry_value", key))
+}
+
+/// Nicht leere Textwerte, die unter mehreren Schlüsseln stehen: je Schlüssel
+/// die anderen Schlüssel mit demselben Wert, in Listenreihenfolge. Zahlen und
+/// Wahrheitswerte wiederholen sich zu Recht und zählen nicht.
+fn find_reused_values(items: &IndexMap<String, EntryValue>) -> HashMap<String, Vec<String>> {
+    let mut by_value: HashMap<&str, Vec<&String>> = HashMap::new();
+    for (key, value) in items {
+        if let EntryValue::Text(text) = value {
+            if !text.trim().is_empty() {
+                by_value.entry(text.as_str()).or_default().push(key);
+            }
+        }
+    }
+    let mut reused = HashMap::new();
+    for keys in by_value.values().filter(|keys| keys.len() > 1) {
+        for key in keys {
+            let others = keys
+                .iter()
+                .filter(|other| *other != key)
+                .map(|other| other.to_string())
+                .collect();
+            reused.insert(key.to_string(), others);
+        }
+    }
+    reused
+}
+
+/// Wie viele verschiedene Werte mehrfach vorkommen
+fn count_reused_values(
+    items: &IndexMap<String, EntryValue>,
+    reused: &HashMap<String, Vec<String>>,
+) -> usize {
+    reused
+        .keys()
+        .filter_map(|key| match items.get(key) {
+            Some(EntryValue::Text(text)) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+/// Auswahl der Argon2-Vorgabe mit geschätzter Dauer; "Eigene Werte" öffnet die
+/// Rohparameter. Liefert, ob gerade eine Vorgabe gewählt wurde.
+fn show_kdf_editor(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    params: &mut KdfParams,
+    custom_open: &mut bool,
+) -> bool {
+    let current = if *custom_open {
+        KdfPreset::Custom
+    } else {
+        KdfPreset::of(params)
+    };
+    let mut picked = false;
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(current.label())
+        .show_ui(ui, |ui| {
+            for preset in KdfPreset::ALL {
+                let text = match preset.params() {
+                    Some(preset_params) => format!(
+                        "{} ({})",
+                        preset.label(),
+                        format_kdf_estimate(&preset_params)
+                    ),
+                    None => preset.label().to_string(),
+                };
+                if ui.selectable_label(current == preset, text).clicked() {
+                    match preset.params() {
+                        Some(preset_params) => {
+                            picked = *params != preset_params;
+                            *params = preset_params;
+                            *custom_open = false;
+                        }
+                        None => *custom_open = true,
+                    }
+                }
+            }
+        });
+    if current == KdfPreset::Custom {
+        ui.horizontal(|ui| {
+            let mut memory = params.m_cost / 1024;
+            if ui
+                .add(
+                    egui::DragValue::new(&mut memory)
+                        .range(8..=KdfParams::MAX_M_COST / 1024)
+                        .suffix(" MiB"),
+                )
+                .on_hover_text(tr("Speicher"))
+                .changed()
+            {
+                params.m_cost = memory * 1024;
+            }
+            ui.add(egui::DragValue::new(&mut params.t_cost).range(1..=KdfParams::MAX_T_COST))
+                .on_hover_text(tr("Durchläufe"));
+            ui.add(egui::DragValue::new(&mut params.p_cost).range(1..=KdfParams::MAX_P_COST))
+                .on_hover_text(tr("Parallelität"));
+        });
+    }
+    ui.label(
+        egui::RichText::new(format_kdf_estimate(params))
+            .size(12.0)
+            .color(egui::Color32::from_gray(140)),
+    )
+    .on_hover_text(tr("Ungefähre Dauer beim Entsperren"));
+    picked
+}
+
+fn format_kdf_estimate(params: &KdfParams) -> String {
+    let seconds = params.estimated_seconds();
+    if seconds < 1.0 {
+        trf("≈ {} ms", &[&((seconds * 1000.0).round() as u64).max(1)])
+    } else {
+        trf("≈ {} s", &[&format!("{:.1}", seconds)])
+    }
+}
+
+/// Liest ein Tastenkürzel wie "Ctrl+Shift+L". Strg, Ctrl und Cmd stehen für
+/// die Befehlstaste der Plattform. Ein Buchstabe ohne Modifier würde beim
+/// Tippen auslösen und gilt deshalb als ungültig.
+fn parse_shortcut(text: &str) -> Option<egui::KeyboardShortcut> {
+    let mut parts: Vec<&str> = text.split('+').map(str::trim).collect();
+    let key = egui::Key::from_name(parts.pop()?)?;
+    let mut modifiers = egui::Modifiers::NONE;
+    for part in parts {
+        modifiers = modifiers
+            | match part.to_lowercase().as_str() {
+                "ctrl" | "strg" | "cmd" => egui::Modifiers::COMMAND,
+                "shift" | "umschalt" => egui::Modifiers::SHIFT,
+                "alt" => egui::Modifiers::ALT,
+                _ => return None,
+            };
+    }
+    if modifiers.is_none() && key.name().chars().count() == 1 {
+        return None;
+    }
+    Some(egui::KeyboardShortcut::new(modifiers, key))
+}
+
+/// Editor passend zum Typ des Eintrags; liefert `true` bei Änderungen.
+/// Texte mit Zeilenumbrüchen (z.B. PEM-Schlüssel) oder mit `multiline` werden
+/// mehrzeilig bearbeitet; das Feld wächst mit dem Inhalt. Ohne `editable`
+/// bleibt alles sichtbar, lässt sich aber nicht ändern.
+fn show_entry_value(
+    ui: &mut egui::Ui,
+    key: &str,
+    value: &mut EntryValue,
+    masked: bool,
+    multiline: bool,
+    editable: bool,
+) -> bool {
+    match value {
+        EntryValue::Text(text) if multiline || text.contains('\n') => {
+            let rows = text.lines().count().clamp(2, 20);
+            ui.add(
+                egui::TextEdit::multiline(text)
+                    .id(entry_value_id(key))
+                    .interactive(editable)
+                    .password(masked)
+                    .desired_rows(rows)
+                    .desired_width(ui.available_width()),
+            )
+            .changed()
+        }
+        EntryValue::Text(text) => ui
+            .add(
+                egui::TextEdit::singleline(text)
+                    .id(entry_value_id(key))
+                    .interactive(editable)
+                    .password(masked)
+                    .desired_width(ui.available_width()),
+            )
+            .changed(),
+        EntryValue::Number(number) => ui
+            .add_enabled(editable, egui::DragValue::new(number).speed(0.1))
+            .changed(),
+        EntryValue::Bool(flag) => ui
+            .add_enabled(editable, egui::Checkbox::without_text(flag))
+            .changed(),
+        EntryValue::Attachment(attachment) => {
+            ui.label(format!("📎 {}", attachment.filename));
+            ui.label(
+                egui::RichText::new(format!(
+                    "{} · {}",
+                    format_size(attachment.size()),
+                    attachment.mime
+                ))
+                .size(12.0)
+                .color(egui::Color32::from_gray(140)),
+            );
+            false
+        }
+        EntryValue::Json(json) => show_json_value(ui, key, json, masked, editable),
+    }
+}
+
+fn show_json_child(
+    ui: &mut egui::Ui,
+    path: &str,
+    name: &str,
+    value: &mut serde_json::Value,
+    masked: bool,
+    editable: bool,
+) -> bool {
+    if value.is_object() || value.is_array() {
+        show_json_value(ui, path, value, masked, editable)
+    } else {
+        ui.horizontal(|ui| {
+            ui.label(name);
+            show_json_value(ui, path, value, masked, editable)
+        })
+        .inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vault::{decrypt_bytes, derive_key, encrypt_with_key, ERR_MISSING_SALT};
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+    /// Wartet auf die Schlüsselableitung im Hintergrund
+    /// Legt in `dir` einen Vault `data.enc` mit `password` an und entsperrt ihn
+    fn unlocked_app(dir: &Path, password: &str) -> App {
+        let mut app = App::default();
+        app.set_vault_path(dir.join("data.enc"), VaultPathSource::CliArgument);
+        app.password = password.into();
+        app.password_repeat = password.into();
+        app.create_vault(0.0);
+        settle(&mut app);
+        app
+    }
+
+    fn settle(app: &mut App) {
+        while app.key_job.is_some() {
+            std::thread::sleep(Duration::from_millis(1));
+            app.poll_key_job(0.0);
+        }
+    }
+
+    fn encrypt(
+        password: &str,
+        salt: &[u8],
+        header: &FileHeader,
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let key = derive_key(password, None, salt, header)?;
+        encrypt_with_key(&key, header, plaintext)
+    }
+
+    #[test]
+    fn invalid_json_plaintext_is_kept_for_recovery() {
+        let salt = [7u8; 16];
+        let broken = r#"{"items": {"api_key": "sk-123""#;
+        let header = FileHeader::current();
+        let encrypted = encrypt("geheim", &salt, &header, broken.as_bytes()).unwrap();
+        let plaintext = decrypt_bytes("geheim", None, &salt, &header, &encrypted).unwrap();
+
+        let mut app = App::default();
+        let json_str = String::from_utf8(plaintext).unwrap();
+        assert!(app.load_json(json_str).is_err());
+        assert_eq!(app.recovery_json.as_deref(), Some(broken));
+
+        let repaired = format!("{}}}}}", broken);
+        assert!(app.load_json(repaired).is_ok());
+        assert_eq!(app.data.items["api_key"], EntryValue::Text("sk-123".into()));
+        assert!(app.recovery_json.is_none());
+    }
+
+    #[test]
+    fn legacy_string_entries_load_as_text() {
+        let data: AppData = serde_json::from_str(
+            r#"{"items": {"token": "abc", "port": 8080, "debug": true, "db": {"host": "x"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(data.items["token"], EntryValue::Text("abc".into()));
+        assert_eq!(data.items["port"], EntryValue::Number(8080.0));
+        assert_eq!(data.items["debug"], EntryValue::Bool(true));
+        assert!(matches!(data.items["db"], EntryValue::Json(_)));
+
+        let round_trip: AppData =
+            serde_json::from_str(&serde_json::to_string(&data).unwrap()).unwrap();
+        assert_eq!(round_trip.items, data.items);
+        assert!(round_trip.items.keys().eq(["token", "port", "debug", "db"]));
+    }
+
+    #[test]
+    fn old_entries_get_metadata_and_edits_update_modified() {
+        let mut app = App::default();
+        app.load_json(r#"{"items": {"token": "abc"}}"#.to_string())
+            .unwrap();
+        let meta = app.data.meta["token"].clone();
+        assert!(meta.note.is_empty());
+        assert_eq!(meta.created, meta.modified);
+
+        std::thread::sleep(Duration::from_millis(5));
+        app.data
+            .set_value("token".into(), EntryValue::Text("neu".into()));
+        assert_eq!(app.data.meta["token"].created, meta.created);
+        assert!(app.data.meta["token"].modified > meta.modified);
+
+        app.data.remove_entry("token");
+        assert!(app.data.meta.is_empty());
+    }
+
+    #[test]
+    fn tags_survive_round_trip_and_filter_with_and() {
+        let mut data = AppData::default();
+        for (key, tags) in [
+            ("a", vec!["prod", "db"]),
+            ("b", vec!["prod"]),
+            ("c", vec![]),
+        ] {
+            data.set_value(key.into(), EntryValue::Text(String::new()));
+            data.meta.get_mut(key).unwrap().tags = tags.into_iter().map(String::from).collect();
+        }
+        let data: AppData = serde_json::from_str(&serde_json::to_string(&data).unwrap()).unwrap();
+
+        let counts = data.tag_counts();
+        assert_eq!(counts["prod"], 2);
+        assert_eq!(counts["db"], 1);
+
+        let filter: BTreeSet<String> = ["prod".to_string(), "db".to_string()].into();
+        assert!(data.has_tags("a", &filter));
+        assert!(!data.has_tags("b", &filter));
+        assert!(data.has_tags("c", &BTreeSet::new()));
+    }
+
+    #[test]
+    fn relative_times_read_naturally() {
+        let now = SystemTime::now();
+        let german = |seconds| {
+            format_relative_time_in(Language::German, now - Duration::from_secs(seconds), now)
+        };
+        let english = |seconds| {
+            format_relative_time_in(Language::English, now - Duration::from_secs(seconds), now)
+        };
+        assert_eq!(german(5), "gerade eben");
+        assert_eq!(german(60), "vor einer Minute");
+        assert_eq!(german(7_200), "vor 2 Stunden");
+        assert_eq!(german(3 * 86_400), "vor 3 Tagen");
+        assert_eq!(german(400 * 86_400), "vor einem Jahr");
+        assert_eq!(english(5), "just now");
+        assert_eq!(english(3_600), "an hour ago");
+        assert_eq!(english(3 * 86_400), "3 days ago");
+    }
+
+    #[test]
+    fn rapid_value_edits_form_a_single_undo_step() {
+        let mut app = App::default();
+        app.data
+            .items
+            .insert("k".into(), EntryValue::Text(String::new()));
+
+        for (i, t) in [0.0, 0.3, 0.6].into_iter().enumerate() {
+            app.push_undo_for_value_edit("k", t);
+            app.data
+                .items
+                .insert("k".into(), EntryValue::Text("abc"[..=i].into()));
+        }
+        // Pause -> neuer Schritt
+        app.push_undo_for_value_edit("k", 5.0);
+        app.data
+            .items
+            .insert("k".into(), EntryValue::Text("abcd".into()));
+        assert_eq!(app.undo_stack.len(), 2);
+
+        app.undo(6.0);
+        assert_eq!(app.data.items["k"], EntryValue::Text("abc".into()));
+        app.undo(6.0);
+        assert_eq!(app.data.items["k"], EntryValue::Text(String::new()));
+        app.redo(6.0);
+        assert_eq!(app.data.items["k"], EntryValue::Text("abc".into()));
+
+        app.undo_depth = 2;
+        for _ in 0..5 {
+            app.push_undo();
+        }
+        assert_eq!(app.undo_stack.len(), 2);
+        assert!(app.redo_stack.is_empty());
+    }
+
+    #[test]
+    fn adding_an_existing_key_asks_before_overwriting() {
+        let mut app = App::default();
+        app.data
+            .items
+            .insert("api".into(), EntryValue::Text("alt".into()));
+        app.new_key = "api".into();
+        app.new_value = "neu".into();
+
+        app.add_new_entry(0.0, false);
+        assert_eq!(app.overwrite_candidate.as_deref(), Some("api"));
+        assert_eq!(app.data.items["api"], EntryValue::Text("alt".into()));
+
+        // Abbrechen: Formular bleibt befüllt
+        app.overwrite_candidate = None;
+        assert_eq!(app.new_key, "api");
+        assert_eq!(app.new_value, "neu");
+
+        app.add_new_entry(0.0, false);
+        app.add_new_entry(0.0, true);
+        assert!(app.overwrite_candidate.is_none());
+        assert_eq!(app.data.items["api"], EntryValue::Text("neu".into()));
+        assert!(app.new_key.is_empty());
+    }
+
+    #[test]
+    fn json_snippet_escapes_key_and_keeps_value_type() {
+        assert_eq!(
+            entry_json_snippet("api \"key\"", &EntryValue::Text("a\nb".into())),
+            r#""api \"key\"": "a\nb""#
+        );
+        assert_eq!(
+            entry_json_snippet("port", &EntryValue::Number(8080.0)),
+            r#""port": 8080.0"#
+        );
+    }
+
+    #[test]
+    fn large_vaults_only_lay_out_visible_rows() {
+        let mut app = App::default();
+        for i in 0..1000 {
+            app.data
+                .set_value(format!("key_{:04}", i), EntryValue::Text(i.to_string()));
+        }
+        app.screen = Screen::Editor;
+
+        let ctx = egui::Context::default();
+        let input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(1000.0, 700.0),
+            )),
+            ..Default::default()
+        };
+        let _ = ctx.run(input, |ctx| app.show_editor_screen(ctx));
+
+        // Jede gezeichnete Zeile legt einen Hover-Zustand an
+        assert!(!app.item_hover_states.is_empty());
+        assert!(app.item_hover_states.len() < 20);
+
+        app.search_query = "key_09".into();
+        assert_eq!(app.visible_keys(0.0).len(), 100);
+
+        // Geänderte Daten machen die gecachte Liste ungültig, getippte Suche erst nach der Pause
+        app.data
+            .set_value("extra_key_09".into(), EntryValue::Text(String::new()));
+        assert_eq!(app.visible_keys(0.0).len(), 101);
+        app.search_query = "key_099".into();
+        app.search_debounce_until = 1.0;
+        assert_eq!(app.visible_keys(0.5).len(), 101);
+        assert_eq!(app.visible_keys(1.0).len(), 10);
+    }
+
+    #[test]
+    fn escape_closes_open_dialogs_but_not_recovery() {
+        let mut app = App::default();
+        assert!(!app.close_dialogs());
+
+        app.delete_candidate = Some("k".into());
+        app.delete_confirm_text = "k".into();
+        app.recovery_json = Some("{".into());
+        assert!(app.close_dialogs());
+        assert!(app.delete_candidate.is_none());
+        assert!(app.delete_confirm_text.is_empty());
+        assert!(app.recovery_json.is_some());
+    }
+
+    #[test]
+    fn sort_modes_apply_to_filtered_keys() {
+        let mut app = App::default();
+        for (key, value) in [("b1", "x"), ("a1", "Z"), ("c1", "y"), ("skip", "a")] {
+            app.data
+                .set_value(key.into(), EntryValue::Text(value.into()));
+        }
+        let now = SystemTime::now();
+        for (key, age) in [("a1", 30), ("b1", 10), ("c1", 20)] {
+            app.data.meta.get_mut(key).unwrap().modified = now - Duration::from_secs(age);
+        }
+        app.search_query = "1".into();
+        app.search_scope = SearchScope::Keys;
+
+        let expected = [
+            (SortMode::KeyAscending, ["a1", "b1", "c1"]),
+            (SortMode::KeyDescending, ["c1", "b1", "a1"]),
+            (SortMode::ValueAscending, ["b1", "c1", "a1"]),
+            (SortMode::RecentlyModified, ["b1", "c1", "a1"]),
+            (SortMode::Insertion, ["b1", "a1", "c1"]),
+        ];
+        for (mode, keys) in expected {
+            app.sort_mode = mode;
+            assert_eq!(app.visible_keys(100.0), keys);
+        }
+    }
+
+    #[test]
+    fn duplicates_land_below_the_original_with_free_keys() {
+        let mut app = App::default();
+        for key in ["a", "b"] {
+            app.data.set_value(key.into(), EntryValue::Text(key.into()));
+        }
+        app.data.critical.insert("a".into());
+
+        assert_eq!(app.duplicate_entry("a", 0.0).as_deref(), Some("a (Kopie)"));
+        assert_eq!(
+            app.duplicate_entry("a", 0.0).as_deref(),
+            Some("a (Kopie) (2)")
+        );
+        assert_eq!(
+            app.data.items.keys().collect::<Vec<_>>(),
+            ["a", "a (Kopie) (2)", "a (Kopie)", "b"]
+        );
+        assert!(app.data.critical.contains("a (Kopie)"));
+        assert_eq!(app.rename_key.as_deref(), Some("a (Kopie) (2)"));
+        assert_eq!(app.duplicate_entry("fehlt", 0.0), None);
+    }
+
+    #[test]
+    fn recent_files_are_deduplicated_and_capped() {
+        let mut recent = Vec::new();
+        for n in 0..10 {
+            remember_recent_file(&mut recent, Path::new(&format!("/nicht/da/{}.enc", n)));
+        }
+        remember_recent_file(&mut recent, Path::new("/nicht/da/5.enc"));
+
+        assert_eq!(recent.len(), MAX_RECENT_FILES);
+        assert_eq!(recent[0], PathBuf::from("/nicht/da/5.enc"));
+        assert_eq!(recent[1], PathBuf::from("/nicht/da/9.enc"));
+        assert_eq!(recent.iter().filter(|p| p.ends_with("5.enc")).count(), 1);
+    }
+
+    #[test]
+    fn bulk_delete_is_a_single_undo_step() {
+        let mut app = App::default();
+        for key in ["a", "b", "c"] {
+            app.data.set_value(key.into(), EntryValue::Text(key.into()));
+        }
+        app.selected_keys = ["a".to_string(), "c".to_string()].into();
+        let keys: Vec<String> = app.selected_keys.iter().cloned().collect();
+        app.delete_entries(&keys, 0.0);
+
+        // Die Zeilen bleiben stehen, bis die Lösch-Animation durch ist
+        assert_eq!(app.data.items.len(), 3);
+        app.finish_pending_deletes();
+        assert_eq!(app.data.items.keys().collect::<Vec<_>>(), ["b"]);
+        assert!(app.selected_keys.is_empty());
+        assert_eq!(app.undo_stack.len(), 1);
+    }
+
+    #[test]
+    fn new_vault_is_only_created_when_passwords_match() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("data.enc");
+
+        let mut app = App::default();
+        app.set_vault_path(vault.clone(), VaultPathSource::CliArgument);
+        assert!(matches!(app.screen, Screen::Setup));
+
+        app.password = "geheim".into();
+        app.password_repeat = "gehiem".into();
+        app.create_vault(1.0);
+        settle(&mut app);
+        assert!(!vault.exists());
+        assert!(matches!(app.screen, Screen::Setup));
+
+        app.password_repeat = "geheim".into();
+        app.create_vault(2.0);
+        settle(&mut app);
+        assert!(vault.exists());
+        assert!(matches!(app.screen, Screen::Editor));
+        assert!(app.vault.load("geheim").is_ok());
+    }
+
+    #[test]
+    fn repeated_wrong_passwords_delay_the_next_attempt() {
+        assert_eq!(login_delay(2), Duration::ZERO);
+        assert_eq!(login_delay(3), Duration::from_secs(5));
+        assert_eq!(login_delay(4), Duration::from_secs(10));
+        assert_eq!(
+            login_delay(40),
+            Duration::from_secs(MAX_LOGIN_DELAY_SECONDS)
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "geheim");
+        app.lock(0.0);
+
+        for _ in 0..FREE_LOGIN_ATTEMPTS {
+            app.password = "falsch".into();
+            app.try_login();
+            settle(&mut app);
+        }
+        assert_eq!(app.failed_logins, FREE_LOGIN_ATTEMPTS);
+        assert!(app.login_wait(SystemTime::now()).is_some());
+
+        // Während der Wartezeit wird auch das richtige Passwort nicht geprüft
+        app.password = "geheim".into();
+        app.try_login();
+        settle(&mut app);
+        assert!(matches!(app.screen, Screen::PasswordInput));
+
+        app.last_failed_login = Some(SystemTime::now() - Duration::from_secs(60));
+        app.try_login();
+        settle(&mut app);
+        assert!(matches!(app.screen, Screen::Editor));
+        assert_eq!(app.failed_logins, 0);
+    }
+
+    #[test]
+    fn restored_window_is_pulled_onto_a_smaller_monitor() {
+        let saved = WindowGeometry {
+            x: 2500.0,
+            y: 100.0,
+            width: 1400.0,
+            height: 900.0,
+            monitor: Some([3840.0, 1080.0]),
+        };
+        let clamped = saved.clamped_to(egui::vec2(1280.0, 800.0));
+        assert_eq!((clamped.width, clamped.height), (1280.0, 800.0));
+        assert_eq!((clamped.x, clamped.y), (0.0, 0.0));
+
+        let tiny = saved.clamped_to(egui::vec2(500.0, 400.0));
+        assert_eq!(
+            (tiny.width, tiny.height),
+            (MIN_WINDOW_SIZE[0], MIN_WINDOW_SIZE[1])
+        );
+    }
+
+    #[test]
+    fn locking_saves_and_forgets_the_decrypted_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "geheim");
+
+        app.push_undo();
+        app.data
+            .set_value("token".into(), EntryValue::Text("sk-123".into()));
+        app.search_query = "tok".into();
+        app.help_open = true;
+        app.lock(1.0);
+
+        assert!(matches!(app.screen, Screen::PasswordInput));
+        assert!(app.data.items.is_empty() && app.undo_stack.is_empty());
+        assert!(app.password.is_empty() && app.vault.key.is_none());
+        assert!(app.search_query.is_empty() && !app.help_open);
+
+        app.password = "geheim".into();
+        app.try_login();
+        settle(&mut app);
+        assert!(matches!(app.screen, Screen::Editor));
+        assert!(app.data.items.contains_key("token"));
+    }
+
+    #[test]
+    fn missing_salt_file_for_existing_vault_is_reported_without_shake() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("data.enc");
+        let encrypted = encrypt("geheim", &[2u8; 16], &FileHeader::default(), b"{}").unwrap();
+        fs::write(&vault, BASE64.encode(encrypted)).unwrap();
+
+        let mut app = App::default();
+        app.set_vault_path(vault, VaultPathSource::CliArgument);
+        app.password = "geheim".into();
+        app.login_shake_time = -1.0;
+        app.try_login();
+        settle(&mut app);
+
+        assert!(matches!(app.screen, Screen::PasswordInput));
+        assert!(app.error_message.starts_with(ERR_MISSING_SALT));
+        assert_eq!(app.login_shake_time, -1.0);
+    }
+
+    #[test]
+    fn import_collisions_follow_the_chosen_policy() {
+        let text = r#"{"items": {"a": "neu", "b": 2}, "notes": "", "critical": []}"#;
+        let entries = parse_json_import(text, ImportShape::Auto, "key", "value").unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let mut app = App::default();
+        app.data
+            .items
+            .insert("a".into(), EntryValue::Text("alt".into()));
+
+        assert_eq!(
+            app.merge_import(entries.clone(), ImportCollision::Skip),
+            (1, 1)
+        );
+        assert_eq!(app.data.items["a"], EntryValue::Text("alt".into()));
+
+        assert_eq!(
+            app.merge_import(entries.clone(), ImportCollision::KeepBoth),
+            (2, 0)
+        );
+        assert_eq!(app.data.items["a (2)"], EntryValue::Text("neu".into()));
+        assert_eq!(app.data.items["b (2)"], EntryValue::Number(2.0));
+
+        assert_eq!(
+            app.merge_import(entries, ImportCollision::Overwrite),
+            (2, 0)
+        );
+        assert_eq!(app.data.items["a"], EntryValue::Text("neu".into()));
+    }
+
+    #[test]
+    fn env_values_with_special_characters_round_trip() {
+        let mut items = IndexMap::new();
+        for (key, value) in [
+            ("PLAIN", "abc123"),
+            ("WITH_EQUALS", "a=b=c"),
+            ("WITH_HASH", "pw#1 # kein Kommentar"),
+            ("MULTILINE", "zeile1\nzeile2"),
+            ("QUOTES", r#"sag "hallo" und 'tschüss' \ $HOME"#),
+            ("EMPTY", ""),
+        ] {
+            items.insert(key.to_string(), EntryValue::Text(value.to_string()));
+        }
+        items.insert("kein gültiger name".into(), EntryValue::Text("x".into()));
+        items.insert(
+            "CERT".into(),
+            EntryValue::Attachment(Attachment {
+                filename: "ca.pem".into(),
+                mime: "application/x-pem-file".into(),
+                base64: "AAEC".into(),
+            }),
+        );
+        items.insert(
+            "CONFIG".into(),
+            EntryValue::Json(serde_json::json!({"a": 1})),
+        );
+
+        let (text, skipped) = format_env(&items);
+        assert_eq!(skipped, 3);
+        assert!(!text.contains("CERT") && !text.contains("CONFIG"));
+        let parsed = parse_env(&text).unwrap();
+        assert_eq!(parsed.len(), 6);
+        for (key, value) in parsed {
+            assert_eq!(items[&key], EntryValue::Text(value), "{}", key);
+        }
+
+        let hand_written = "# Kommentar\n\nexport A=1 # Notiz\nB='wört\\lich'\nC=\"x\ny\"\n";
+        assert_eq!(
+            parse_env(hand_written).unwrap(),
+            vec![
+                ("A".to_string(), "1".to_string()),
+                ("B".to_string(), "wört\\lich".to_string()),
+                ("C".to_string(), "x\ny".to_string()),
+            ]
+        );
+        assert_eq!(
+            parse_env("A=1\nB=\"offen\n").unwrap_err(),
+            "Zeile 2: schließendes \" fehlt"
+        );
+    }
+
+    #[test]
+    fn csv_round_trips_quoted_fields_and_reports_bad_rows() {
+        let mut items = IndexMap::new();
+        items.insert("a,b".to_string(), EntryValue::Text("x, \"y\"\nz".into()));
+        items.insert("port".to_string(), EntryValue::Number(8080.0));
+        items.insert(
+            "cert".to_string(),
+            EntryValue::Attachment(Attachment {
+                filename: "ca.pem".into(),
+                mime: "application/x-pem-file".into(),
+                base64: "AAEC".into(),
+            }),
+        );
+        items.insert(
+            "list".to_string(),
+            EntryValue::Json(serde_json::json!([1, 2])),
+        );
+
+        let (text, skipped) = format_csv(&items).unwrap();
+        // Anhänge und JSON würden beim Import zu bloßem Text
+        assert_eq!(skipped, 2);
+        let parsed = parse_csv(&text, true).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                ("a,b".to_string(), "x, \"y\"\nz".to_string()),
+                ("port".to_string(), "8080".to_string()),
+            ]
+        );
+
+        assert_eq!(parse_csv("k,v\n", false).unwrap().len(), 1);
+        assert_eq!(
+            parse_csv("k,v\nnur_eine_spalte\n", false).unwrap_err(),
+            "Zeile 2: erwartet 2 Spalten (Schlüssel, Wert), gefunden 1"
+        );
+    }
+
+    #[test]
+    fn new_keys_are_trimmed_and_blank_ones_rejected() {
+        let mut app = App {
+            new_key: "   ".into(),
+            ..App::default()
+        };
+        app.add_new_entry(0.0, false);
+        assert!(app.data.items.is_empty());
+        assert_eq!(app.new_key_error, "Schlüssel darf nicht leer sein");
+
+        app.new_key = "  api ".into();
+        app.add_new_entry(0.0, false);
+        assert!(app.new_key_error.is_empty());
+        assert!(app.data.items.contains_key("api"));
+
+        // Groß-/Kleinschreibung zählt, führende Leerzeichen nicht
+        app.new_key = "API".into();
+        app.add_new_entry(0.0, false);
+        assert_eq!(app.overwrite_candidate, None);
+        app.new_key = "api  ".into();
+        app.add_new_entry(0.0, false);
+        assert_eq!(app.overwrite_candidate.as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn toasts_beyond_the_visible_limit_wait_in_a_queue() {
+        let mut app = App::default();
+        let red = egui::Color32::from_rgb(220, 53, 69);
+        for i in 0..MAX_VISIBLE_TOASTS + 2 {
+            app.add_toast(&format!("Meldung {}", i), red, 1.0, 0.0);
+        }
+        app.add_toast("Meldung 5", red, 1.0, 0.0);
+        assert_eq!(app.toast_messages.len(), MAX_VISIBLE_TOASTS);
+        assert_eq!(app.toast_queue.len(), 2);
+
+        let ctx = egui::Context::default();
+        ctx.begin_frame(egui::RawInput {
+            time: Some(2.0),
+            ..Default::default()
+        });
+        app.update_animations(&ctx, 0.016);
+        assert_eq!(app.toast_messages.len(), 2);
+        assert!(app.toast_queue.is_empty());
+        assert_eq!(app.toast_messages[0].start_time, 2.0);
+    }
+
+    #[test]
+    fn settings_round_trip_and_fill_missing_fields_with_defaults() {
+        let mut app = App::default();
+        app.apply_settings(Settings {
+            theme: Theme::Light,
+            backup_count: 7,
+            auto_save_enabled: true,
+            undo_depth: 50,
+            ..Settings::default()
+        });
+        let json = serde_json::to_string(&app.settings()).unwrap();
+        assert!(!json.contains("password"));
+        let restored: Settings = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, app.settings());
+
+        let old: Settings = serde_json::from_str(r#"{"theme": "light"}"#).unwrap();
+        assert_eq!(old.theme, Theme::Light);
+        assert_eq!(old.backup_count, DEFAULT_BACKUP_COUNT);
+        assert_eq!(old.undo_depth, DEFAULT_UNDO_DEPTH);
+    }
+
+    #[test]
+    fn rule_violations_hold_back_saving_until_overridden() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = App {
+            vault: Vault::new(dir.path().join("data.enc")),
+            password: "geheim".into(),
+            ..App::default()
+        };
+        app.data
+            .set_value("port".into(), EntryValue::Text("80a".into()));
+        app.data.meta.get_mut("port").unwrap().rule = ValidationRule::Integer;
+        app.revalidate_all();
+        app.dirty = true;
+
+        app.save(0.0, false);
+        assert!(app.validation_save_prompt);
+        assert!(!app.vault.exists());
+
+        app.save(0.0, true);
+        assert!(!app.validation_save_prompt);
+        assert!(app.vault.exists());
+
+        let json = serde_json::to_string(&app.data).unwrap();
+        app.load_json(json).unwrap();
+        assert_eq!(app.data.meta["port"].rule, ValidationRule::Integer);
+        assert!(app.validation_errors.contains_key("port"));
+    }
+
+    #[test]
+    fn hovered_rows_reveal_their_value_only_when_enabled() {
+        let mut app = App::default();
+        app.item_hover_states.insert("token".into(), 1.0);
+        app.item_hover_states.insert("other".into(), 0.2);
+        assert!(!app.value_masked("token"));
+        assert!(app.value_masked("other"));
+
+        app.reveal_on_hover = false;
+        assert!(app.value_masked("token"));
+        app.revealed_items.insert("token".into());
+        assert!(!app.value_masked("token"));
+    }
+
+    #[test]
+    fn dropped_files_are_dispatched_by_extension() {
+        let kind = |name: &str| DroppedFile::classify(Path::new(name));
+        assert_eq!(kind("/tmp/data.enc"), Some(DroppedFile::Vault));
+        assert_eq!(
+            kind("export.JSON"),
+            Some(DroppedFile::Import(PlaintextFormat::Json))
+        );
+        assert_eq!(
+            kind("/home/a/.env"),
+            Some(DroppedFile::Import(PlaintextFormat::Env))
+        );
+        assert_eq!(kind("notes.txt"), None);
+        assert_eq!(kind("README"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prod.env");
+        fs::write(&path, "API_KEY=abc\n").unwrap();
+        let mut app = App {
+            screen: Screen::Editor,
+            ..App::default()
+        };
+        app.open_dropped_file(path, 0.0);
+        assert!(app.data.items.is_empty());
+        assert_eq!(app.dropped_import.as_ref().unwrap().1.len(), 1);
+    }
+
+    #[test]
+    fn unlocking_runs_in_the_background_and_blocks_a_second_attempt() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "geheim");
+        app.lock(0.0);
+
+        app.password = "geheim".into();
+        app.try_login();
+        assert!(app.key_job.is_some());
+        assert!(matches!(app.screen, Screen::PasswordInput));
+        settle(&mut app);
+        assert!(matches!(app.screen, Screen::Editor));
+        assert!(app.vault.key.is_some());
+    }
+
+    #[test]
+    fn json_source_redacts_values_that_are_masked() {
+        let mut app = App {
+            reveal_on_hover: false,
+            ..App::default()
+        };
+        app.data
+            .set_value("token".into(), EntryValue::Text("sk-123".into()));
+        app.data
+            .set_value("host".into(), EntryValue::Text("db.local".into()));
+        app.revealed_items.insert("host".into());
+
+        let redacted = app.source_json(true);
+        assert!(!redacted.contains("sk-123"));
+        assert!(redacted.contains("db.local"));
+        assert!(app.source_json(false).contains("sk-123"));
+    }
+
+    #[test]
+    fn new_values_are_trimmed_only_when_enabled() {
+        let mut app = App {
+            new_key: "a".into(),
+            new_value: "sk-123\n".into(),
+            ..App::default()
+        };
+        assert!(has_outer_whitespace(&app.new_value));
+        app.add_new_entry(0.0, false);
+        assert_eq!(app.data.items["a"], EntryValue::Text("sk-123\n".into()));
+
+        app.auto_trim_values = true;
+        app.new_key = "b".into();
+        app.new_value = "  sk-456 ".into();
+        app.add_new_entry(0.0, false);
+        assert_eq!(app.data.items["b"], EntryValue::Text("sk-456".into()));
+    }
+
+    #[test]
+    fn tabs_keep_their_own_data_and_unsaved_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "geheim");
+        app.push_undo();
+        app.data
+            .set_value("work".into(), EntryValue::Text("w".into()));
+
+        app.open_in_new_tab(dir.path().join("home.enc"), VaultPathSource::FileDialog);
+        assert_eq!(app.tabs.len(), 2);
+        assert!(matches!(app.screen, Screen::Setup));
+        assert!(app.data.items.is_empty() && !app.dirty && app.password.is_empty());
+        app.password = "privat".into();
+        app.password_repeat = "privat".into();
+        app.create_vault(1.0);
+        settle(&mut app);
+
+        app.switch_tab(0);
+        assert!(matches!(app.screen, Screen::Editor));
+        assert!(app.data.items.contains_key("work") && app.dirty);
+        assert!(app.any_unsaved());
+
+        // Schließen mit ungespeicherten Änderungen fragt erst nach
+        app.request_close_tab(0);
+        assert!(app.tab_close_prompt);
+        app.close_active_tab();
+        assert_eq!(app.tabs.len(), 1);
+        assert_eq!(app.vault.path, dir.path().join("home.enc"));
+        assert!(!app.any_unsaved());
+
+        // Sperren erfasst auch wartende Tabs
+        app.open_in_new_tab(dir.path().join("data.enc"), VaultPathSource::FileDialog);
+        app.password = "geheim".into();
+        app.try_login();
+        settle(&mut app);
+        assert!(matches!(app.screen, Screen::Editor));
+        app.lock(3.0);
+        assert!(matches!(app.screen, Screen::PasswordInput));
+        assert!(app.tabs.iter().flatten().all(|session| {
+            session.data.items.is_empty()
+                && session.password.is_empty()
+                && session.vault.key.is_none()
+                && matches!(session.screen, Screen::PasswordInput)
+        }));
+    }
+
+    #[test]
+    fn replaced_values_go_into_a_bounded_history() {
+        let mut data = AppData::default();
+        data.set_value("token".into(), EntryValue::Text("v1".into()));
+        // Tippen direkt danach gehört zum selben Bearbeitungsvorgang
+        data.set_value("token".into(), EntryValue::Text("v1x".into()));
+        assert!(data.meta["token"].history.is_empty());
+
+        for i in 2..5 {
+            data.meta.get_mut("token").unwrap().modified -=
+                Duration::from_secs(HISTORY_COALESCE_SECONDS);
+            data.set_value("token".into(), EntryValue::Text(format!("v{}", i)));
+        }
+        let history: Vec<_> = data.meta["token"]
+            .history
+            .iter()
+            .map(|(_, value)| value.as_text())
+            .collect();
+        assert_eq!(history, ["v1x", "v2", "v3"]);
+
+        data.trim_history(2);
+        assert_eq!(data.meta["token"].history.len(), 2);
+        assert_eq!(
+            data.meta["token"].history[0].1,
+            EntryValue::Text("v2".into())
+        );
+    }
+
+    #[test]
+    fn external_rewrite_blocks_saving_until_merged() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "geheim");
+        let path = app.vault.path.clone();
+
+        // Zweite Instanz schreibt dazwischen
+        let mut theirs = Vault::new(path.clone());
+        let mut data = theirs.load("geheim").unwrap();
+        data.set_value("theirs".into(), EntryValue::Text("1".into()));
+        theirs.save("geheim", &data, 0).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+
+        app.push_undo();
+        app.data
+            .set_value("ours".into(), EntryValue::Text("2".into()));
+        app.save(0.0, false);
+        assert!(app.external_change.is_some());
+        let on_disk = Vault::new(path.clone()).load("geheim").unwrap();
+        assert!(!on_disk.items.contains_key("ours"));
+
+        app.reload_from_disk(KeyJobKind::Merge);
+        settle(&mut app);
+        assert!(app.data.items.contains_key("theirs") && app.data.items.contains_key("ours"));
+        app.save(0.0, false);
+        assert!(app.external_change.is_none());
+        let saved = Vault::new(path).load("geheim").unwrap();
+        assert_eq!(saved.items.len(), 2);
+    }
+
+    #[test]
+    fn key_suggestions_detect_tokens_and_continue_numbering() {
+        assert_eq!(suggest_key_for_value("sk-abc123"), Some("openai_api_key"));
+        assert_eq!(
+            suggest_key_for_value(" sk-ant-api03-xyz "),
+            Some("anthropic_api_key")
+        );
+        assert_eq!(
+            suggest_key_for_value("ghp_0123456789"),
+            Some("github_token")
+        );
+        assert_eq!(
+            suggest_key_for_value("eyJhbGciOi.eyJzdWIi.c2ln"),
+            Some("jwt")
+        );
+        assert_eq!(suggest_key_for_value("sk-"), None);
+        assert_eq!(suggest_key_for_value("hunter2"), None);
+
+        let mut items = IndexMap::new();
+        items.insert("server_2".to_string(), EntryValue::Bool(true));
+        items.insert("server_3".to_string(), EntryValue::Bool(true));
+        items.insert("api_key".to_string(), EntryValue::Bool(true));
+        assert_eq!(next_free_key(&items, "server_2"), "server_4");
+        assert_eq!(next_free_key(&items, "api_key"), "api_key_2");
+        assert_eq!(next_free_key(&items, "token"), "token");
+        items.insert("x18446744073709551615".to_string(), EntryValue::Bool(true));
+        assert_eq!(
+            next_free_key(&items, "x18446744073709551615"),
+            "x18446744073709551615 (2)"
+        );
+
+        let mut app = App {
+            key_suggestions: true,
+            ..App::default()
+        };
+        for key in ["a", "b", "a"] {
+            app.new_key = key.into();
+            app.add_new_entry(0.0, true);
+        }
+        assert_eq!(app.recent_key_names, ["a", "b"]);
+    }
+
+    #[test]
+    fn toast_durations_follow_the_setting_with_a_floor() {
+        let mut app = App {
+            toast_duration_factor: 2.0,
+            ..App::default()
+        };
+        app.add_toast("a", egui::Color32::WHITE, 2.0, 0.0);
+        app.toast_duration_factor = 0.25;
+        app.add_toast("b", egui::Color32::WHITE, 2.0, 0.0);
+        let durations: Vec<f64> = app.toast_messages.iter().map(|t| t.duration).collect();
+        assert_eq!(durations, [4.0, MIN_TOAST_SECONDS]);
+
+        let (align, offset) = ToastPosition::TopCenter.anchor(10.0);
+        assert_eq!(align, egui::Align2::CENTER_TOP);
+        assert_eq!(offset, egui::vec2(0.0, TOAST_TOP_MARGIN + 10.0));
+    }
+
+    #[test]
+    fn row_edits_survive_a_cancelled_delete() {
+        let mut app = App::default();
+        app.data
+            .set_value("token".into(), EntryValue::Text("alt".into()));
+        let ctx = egui::Context::default();
+        let frame = |app: &mut App, events: Vec<egui::Event>| {
+            let input = egui::RawInput {
+                events,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    app.show_item_row(ui, "token".into(), 0.0);
+                });
+            });
+        };
+
+        app.value_focus_pending = Some("token".into());
+        frame(&mut app, vec![]);
+        frame(&mut app, vec![egui::Event::Text("neu".into())]);
+        assert_eq!(app.data.items["token"], EntryValue::Text("altneu".into()));
+
+        // Mit offenem Dialog nimmt die Zeile keine Eingaben mehr an
+        app.delete_candidate = Some("token".into());
+        ctx.memory_mut(|m| m.request_focus(entry_value_id("token")));
+        frame(&mut app, vec![]);
+        frame(&mut app, vec![egui::Event::Text("!".into())]);
+        assert!(app.close_dialogs());
+        assert_eq!(app.data.items["token"], EntryValue::Text("altneu".into()));
+    }
+
+    #[test]
+    fn favorites_are_pinned_filtered_and_persisted() {
+        let mut app = App::default();
+        for key in ["alpha", "beta", "gamma"] {
+            app.data
+                .set_value(key.into(), EntryValue::Text(String::new()));
+        }
+        app.toggle_favorite("gamma");
+        app.toggle_favorite("alpha");
+        let pinned = |app: &mut App| {
+            let keys = app.visible_keys(0.0);
+            app.split_favorites(keys)
+        };
+        let (favorites, rest) = pinned(&mut app);
+        assert_eq!(favorites, ["alpha", "gamma"]);
+        assert_eq!(rest, ["beta"]);
+
+        app.search_query = "gam".into();
+        let (favorites, rest) = pinned(&mut app);
+        assert_eq!(favorites, ["gamma"]);
+        assert!(rest.is_empty());
+        app.search_query = "bet".into();
+        assert!(pinned(&mut app).0.is_empty());
+
+        let json = serde_json::to_string(&app.data).unwrap();
+        let loaded: AppData = serde_json::from_str(&json).unwrap();
+        assert!(loaded.is_favorite("alpha") && !loaded.is_favorite("beta"));
+        assert!(!json.contains("\"favorite\":false"));
+    }
+
+    #[test]
+    fn panic_shortcut_locks_and_clears_the_editor() {
+        let shortcut = parse_shortcut("Ctrl+L").unwrap();
+        assert_eq!(shortcut.logical_key, egui::Key::L);
+        assert_eq!(shortcut.modifiers, egui::Modifiers::COMMAND);
+        assert!(parse_shortcut("strg + shift + F12").is_some());
+        assert!(parse_shortcut("F12").is_some());
+        assert!(parse_shortcut("L").is_none());
+        assert!(parse_shortcut("Hyper+L").is_none());
+        assert!(parse_shortcut("").is_none());
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.data
+            .set_value("token".into(), EntryValue::Text("geheim".into()));
+        app.dirty = true;
+
+        // Ein zweiter, entsperrter Tab; der erste wartet mit ungespeicherten Änderungen
+        app.open_in_new_tab(dir.path().join("zweit.enc"), VaultPathSource::FileDialog);
+        app.password = "pw2".into();
+        app.password_repeat = "pw2".into();
+        app.create_vault(1.0);
+        settle(&mut app);
+        app.data
+            .set_value("anderes".into(), EntryValue::Text("geheim2".into()));
+        app.dirty = true;
+
+        let ctx = egui::Context::default();
+        let input = egui::RawInput {
+            events: vec![egui::Event::Key {
+                key: egui::Key::L,
+                physical_key: None,
+                pressed: true,
+                repeat: false,
+                modifiers: egui::Modifiers::COMMAND,
+            }],
+            ..Default::default()
+        };
+        let _ = ctx.run(input, |ctx| app.handle_panic_shortcut(ctx));
+        assert!(matches!(app.screen, Screen::PasswordInput));
+        assert!(app.data.items.is_empty());
+        assert!(!app.dirty);
+
+        let parked = app.tabs[0].as_ref().unwrap();
+        assert!(matches!(parked.screen, Screen::PasswordInput));
+        assert!(parked.data.items.is_empty() && parked.password.is_empty());
+        assert!(parked.vault.key.is_none() && !parked.dirty);
+        // Die wartenden Änderungen wurden vor dem Sperren gespeichert
+        let saved = Vault::new(dir.path().join("data.enc")).load("pw").unwrap();
+        assert!(saved.items.contains_key("token"));
+    }
+
+    #[test]
+    fn panic_lock_clears_everything_even_when_saving_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.serve_config = Some(("127.0.0.1:0".parse().unwrap(), serve::new_token()));
+        app.sync_server(0.0);
+        assert!(app.server.is_some());
+        app.data
+            .set_value("token".into(), EntryValue::Text("geheim".into()));
+        app.dirty = true;
+
+        // Ein anderer Prozess hat die Datei angefasst, normales Speichern scheitert
+        fs::File::options()
+            .write(true)
+            .open(&app.vault.path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        app.panic_lock(&egui::Context::default(), 0.0);
+        assert!(matches!(app.screen, Screen::PasswordInput));
+        assert!(app.data.items.is_empty() && app.password.is_empty());
+        assert!(app.vault.key.is_none() && !app.dirty);
+        assert!(app.server.is_none() && app.external_change.is_none());
+
+        // Die Änderung liegt in einer Notkopie, die sich mit demselben Passwort öffnet
+        let copy = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.to_string_lossy().contains("data-unsaved-"))
+            .unwrap();
+        let rescued = Vault::new(copy).load("pw").unwrap();
+        assert_eq!(rescued.items["token"], EntryValue::Text("geheim".into()));
+        assert!(!Vault::new(app.vault.path.clone())
+            .load("pw")
+            .unwrap()
+            .items
+            .contains_key("token"));
+    }
+
+    #[test]
+    fn running_key_jobs_block_saving_and_are_dropped_by_locking() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.auto_save_enabled = true;
+        app.data.set_value("k".into(), EntryValue::Text("v".into()));
+        app.dirty = true;
+
+        // Speichern liefe über dieselbe .tmp-Datei wie der Job
+        app.start_rekey();
+        assert!(app.encrypt_data().is_err());
+        assert!(!app.auto_save_due(1e9));
+        settle(&mut app);
+        assert!(app.encrypt_data().is_ok());
+
+        // Ein nach dem Sperren fertiger Job bringt nichts zurück
+        app.reload_from_disk(KeyJobKind::Reload);
+        app.lock(1.0);
+        assert!(app.key_job.is_none());
+        std::thread::sleep(Duration::from_millis(200));
+        app.poll_key_job(2.0);
+        assert!(matches!(app.screen, Screen::PasswordInput));
+        assert!(app.data.items.is_empty() && app.vault.key.is_none());
+    }
+
+    #[test]
+    fn changing_the_kdf_preset_rekeys_the_open_vault() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.data.set_value("k".into(), EntryValue::Text("v".into()));
+        app.dirty = true;
+
+        app.kdf_params = KdfParams {
+            m_cost: 8 * 1024,
+            t_cost: 1,
+            ..KdfParams::default()
+        };
+        app.start_rekey();
+        settle(&mut app);
+        assert!(!app.dirty);
+        assert_eq!(app.vault.header.kdf, app.kdf_params);
+
+        let mut reopened = Vault::new(app.vault.path.clone());
+        assert_eq!(reopened.load("pw").unwrap().items["k"], app.data.items["k"]);
+        assert_eq!(reopened.header.kdf.m_cost, 8 * 1024);
+    }
+
+    #[test]
+    fn shared_sub_vault_holds_only_the_selected_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = App::default();
+        app.set_vault_path(dir.path().join("vault.enc"), VaultPathSource::CliArgument);
+        for key in ["a", "b", "c"] {
+            app.data
+                .set_value(key.into(), EntryValue::Text(key.to_uppercase()));
+        }
+        app.data.meta.get_mut("a").unwrap().history =
+            vec![(SystemTime::now(), EntryValue::Text("alt".into()))];
+        app.data.critical.insert("c".into());
+        app.selected_keys = ["a", "c"].into_iter().map(String::from).collect();
+        app.share_password = "einmal".into();
 
-                ui.allocate_ui_with_layout(
-                    egui::vec2(400.0, 200.0),
-                    egui::Layout::top_down(egui::Align::Center),
-                    |ui| {
-                        ui.add_space(shake_offset.max(0.0));
+        let own = app.vault.path.clone();
+        assert!(app.export_selection_to(&own).is_err());
+        let path = dir.path().join("geteilt.enc");
+        assert_eq!(app.export_selection_to(&path), Ok(2));
 
-                        ui.vertical(|ui| {
-                            egui::Frame::group(ui.style())
-                                .rounding(egui::Rounding::same(16.0))
-                                .fill(egui::Color32::DARK_GRAY)
-                                .stroke(egui::Stroke::new(
-                                    1.0,
-                                    egui::Color32::from_rgb(222, 226, 230),
-                                ))
-                                .inner_margin(egui::Margin::symmetric(24.0, 20.0))
-                                .shadow(egui::epaint::Shadow {
-                                    offset: egui::vec2(0.0, 2.0),
-                                    blur: 12.0,
-                                    spread: 0.0,
-                                    color: egui::Color32::from_black_alpha(30),
-                                })
-                                .show(ui, |ui| {
-                                    ui.vertical(|ui| {
-                                        ui.label("Passwort eingeben:");
-                                        ui.add_space(8.0);
+        let shared = Vault::new(path).load("einmal").unwrap();
+        assert_eq!(shared.items.keys().collect::<Vec<_>>(), ["a", "c"]);
+        assert_eq!(shared.items["c"], EntryValue::Text("C".into()));
+        assert!(shared.critical.contains("c"));
+        assert!(shared.meta.values().all(|meta| meta.history.is_empty()));
+        assert!(!app.vault.path.exists());
+    }
 
-                                        let password_field =
-                                            egui::TextEdit::singleline(&mut self.password)
-                                                .password(!self.show_password)
-                                                .hint_text("Dein sicheres Passwort")
-                                                .desired_width(ui.available_width());
+    #[test]
+    fn reused_values_are_found_and_refreshed_on_change() {
+        let mut app = App::default();
+        for (key, value) in [
+            ("db_password", "hunter2"),
+            ("smtp_password", "hunter2"),
+            ("backup_password", "hunter2"),
+            ("api_key", "sk-1"),
+            ("legacy_key", "sk-1"),
+            ("empty_a", ""),
+            ("empty_b", ""),
+        ] {
+            app.data
+                .set_value(key.into(), EntryValue::Text(value.into()));
+        }
+        app.data
+            .set_value("port_a".into(), EntryValue::Number(5432.0));
+        app.data
+            .set_value("port_b".into(), EntryValue::Number(5432.0));
 
-                                        let response = ui.add(password_field);
+        app.refresh_reused_values();
+        assert_eq!(
+            app.reused_values["db_password"],
+            ["smtp_password", "backup_password"]
+        );
+        assert!(!app.reused_values.contains_key("empty_a"));
+        assert!(!app.reused_values.contains_key("port_a"));
+        assert_eq!(count_reused_values(&app.data.items, &app.reused_values), 2);
 
-                                        ui.add_space(8.0);
-                                        ui.horizontal(|ui| {
-                                            ui.checkbox(
-                                                &mut self.show_password,
-                                                "Passwort anzeigen",
-                                            );
-                                        });
+        app.data
+            .set_value("legacy_key".into(), EntryValue::Text("sk-2".into()));
+        app.refresh_reused_values();
+        assert!(!app.reused_values.contains_key("api_key"));
+        assert_eq!(count_reused_values(&app.data.items, &app.reused_values), 1);
+    }
 
-                                        ui.add_space(12.0);
+    #[test]
+    fn regex_search_matches_keys_and_falls_back_on_errors() {
+        let mut app = App::default();
+        for (key, value) in [
+            ("db_host", "localhost"),
+            ("db_port", "5432"),
+            ("cache_host", "redis"),
+            ("odd(key", "x"),
+        ] {
+            app.data
+                .set_value(key.into(), EntryValue::Text(value.into()));
+        }
+        app.search_regex = true;
+        app.search_scope = SearchScope::Keys;
+        app.search_query = "^DB_(host|port)$".into();
+        assert_eq!(app.visible_keys(0.0), ["db_host", "db_port"]);
 
-                                        let login_enabled = !self.password.trim().is_empty();
-                                        let button_color = if login_enabled {
-                                            egui::Color32::from_rgb(40, 167, 69)
-                                        } else {
-                                            egui::Color32::from_rgb(108, 117, 125)
-                                        };
+        app.search_scope = SearchScope::Values;
+        app.search_query = "^\\d+$".into();
+        assert_eq!(app.visible_keys(0.0), ["db_port"]);
 
-                                        let button = egui::Button::new("🚀 Entsperren")
-                                            .fill(button_color)
-                                            .rounding(egui::Rounding::same(8.0))
-                                            .min_size(egui::vec2(ui.available_width(), 36.0));
+        app.search_scope = SearchScope::Both;
+        app.search_query = "odd(".into();
+        assert_eq!(app.visible_keys(0.0), ["odd(key"]);
+        assert!(app.search_regex_error().is_some());
 
-                                        if ui.add_enabled(login_enabled, button).clicked() {
-                                            self.try_login(current_time);
-                                        }
+        app.search_regex = false;
+        app.search_query = "_host".into();
+        assert_eq!(app.visible_keys(0.0).len(), 2);
+        assert!(app.search_regex_error().is_none());
+    }
 
-                                        if response.lost_focus()
-                                            && ui.input(|i| i.key_pressed(egui::Key::Enter))
-                                            && login_enabled
-                                        {
-                                            self.try_login(current_time);
-                                        }
+    #[test]
+    fn http_access_runs_only_while_unlocked() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.serve_config = Some(("127.0.0.1:0".parse().unwrap(), serve::new_token()));
+        app.sync_server(0.0);
+        let addr = app.server.as_ref().unwrap().local_addr();
+        assert!(std::net::TcpStream::connect(addr).is_ok());
 
-                                        if !self.error_message.is_empty() {
-                                            ui.add_space(12.0);
-                                            ui.colored_label(
-                                                egui::Color32::from_rgb(220, 53, 69),
-                                                format!("❌ {}", self.error_message),
-                                            );
-                                        }
-                                    });
-                                });
-                        });
-                    },
-                );
+        app.lock(0.0);
+        assert!(app.server.is_none());
+        assert!(std::net::TcpStream::connect(addr).is_err());
+        app.sync_server(0.0);
+        assert!(app.server.is_none());
+    }
 
-                ui.add_space(20.0);
-                ui.label(
-                    egui::RichText::new("Deine Daten werden lokal mit AES-256 verschlüsselt")
-                        .size(12.0)
-                        .color(egui::Color32::from_gray(120)),
-                );
+    #[test]
+    fn grid_cells_edit_values_and_rename_keys() {
+        let mut app = App {
+            grid_view: true,
+            ..App::default()
+        };
+        app.data
+            .set_value("host".into(), EntryValue::Text("a".into()));
+        app.data
+            .set_value("port".into(), EntryValue::Text("1".into()));
+        let keys = vec!["host".to_string(), "port".to_string()];
+        let ctx = egui::Context::default();
+        let frame = |app: &mut App, events: Vec<egui::Event>| {
+            let input = egui::RawInput {
+                events,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    app.show_entry_grid(ui, &keys, 0.0);
+                });
+            });
+        };
+        let press = |key| egui::Event::Key {
+            key,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: egui::Modifiers::NONE,
+        };
+
+        // Im ersten Frame vermisst das Grid nur seine Spalten
+        frame(&mut app, vec![]);
+        app.value_focus_pending = Some("host".into());
+        frame(&mut app, vec![]);
+        frame(&mut app, vec![egui::Event::Text("b".into())]);
+        assert_eq!(app.data.items["host"], EntryValue::Text("ab".into()));
+
+        // Tab aus der Schlüsselzelle benennt um und springt weiter
+        let key_cell = |key: &str| egui::Id::new(("grid_key", key));
+        ctx.memory_mut(|m| m.request_focus(key_cell("host")));
+        frame(&mut app, vec![]);
+        frame(&mut app, vec![egui::Event::Text("name".into())]);
+        frame(&mut app, vec![press(egui::Key::Tab)]);
+        assert_eq!(
+            app.data.items.keys().collect::<Vec<_>>(),
+            ["hostname", "port"]
+        );
+        assert_eq!(app.data.items["hostname"], EntryValue::Text("ab".into()));
+
+        // Ein schon vergebener Name ändert nichts
+        ctx.memory_mut(|m| m.request_focus(key_cell("port")));
+        frame(&mut app, vec![]);
+        let mut events = vec![press(egui::Key::Backspace); 4];
+        events.push(egui::Event::Text("hostname".into()));
+        frame(&mut app, events);
+        frame(&mut app, vec![press(egui::Key::Tab)]);
+        assert_eq!(
+            app.data.items.keys().collect::<Vec<_>>(),
+            ["hostname", "port"]
+        );
+        assert!(app.rename_key.is_none());
+        assert!(app
+            .toast_messages
+            .iter()
+            .any(|toast| toast.text.contains("hostname")));
+    }
+
+    #[test]
+    fn rows_of_vanished_entries_are_skipped() {
+        let mut app = App::default();
+        app.data
+            .set_value("bleibt".into(), EntryValue::Text("a".into()));
+        let ctx = egui::Context::default();
+        let _ = ctx.run(egui::RawInput::default(), |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                app.show_item_row(ui, "weg".into(), 0.0);
+                app.show_grid_row(ui, "weg", true, 0.0);
+                app.show_item_row(ui, "bleibt".into(), 0.0);
             });
         });
+        let mut keys = vec!["weg".to_string(), "bleibt".to_string()];
+        app.sort_mode = SortMode::ValueAscending;
+        app.sort_keys(&mut keys);
+        assert_eq!(keys, ["weg", "bleibt"]);
     }
 
-    fn show_editor_screen(&mut self, ctx: &egui::Context) {
-        let current_time = ctx.input(|i| i.time);
+    #[test]
+    fn login_import_keeps_notes_through_key_collisions() {
+        let mut app = App::default();
+        app.data
+            .set_value("Mail".into(), EntryValue::Text("alt".into()));
+        let table = logins::read_csv(
+            "Title,Username,Password,URL,Notes\nMail,tim,neu,,Zweitkonto\n,x,y,,\n",
+            true,
+        )
+        .unwrap();
+        let columns = logins::guess_columns(&table.headers);
+        app.login_import = Some((table, columns));
+        app.finish_login_import(0.0);
+        assert!(app.login_import.is_none());
+        assert!(app.pending_import.is_some());
+        assert!(app
+            .toast_messages
+            .iter()
+            .any(|toast| toast.text.contains("1 Zeilen ohne Titel")));
 
-        // Animated slide-in effect
-        let slide_progress = ease_in_out(self.screen_transition_progress);
-        let slide_offset = (1.0 - slide_progress) * 50.0;
+        let entries = app.pending_import.take().unwrap();
+        app.finish_import(entries, ImportCollision::KeepBoth, 0.0);
+        assert_eq!(app.data.items["Mail"], EntryValue::Text("alt".into()));
+        assert_eq!(
+            app.data.items["Mail (2)"],
+            EntryValue::Json(serde_json::json!({"username": "tim", "password": "neu"}))
+        );
+        assert_eq!(app.data.meta["Mail (2)"].note, "Zweitkonto");
+        assert!(app.pending_import_notes.is_empty());
+    }
 
-        // Top Bar with gradient background
-        egui::TopBottomPanel::top("top_bar")
-            .frame(
-                egui::Frame::none()
-                    .fill(egui::Color32::from_rgb(52, 58, 64))
-                    .inner_margin(egui::Margin::symmetric(16.0, 12.0)),
-            )
-            .show(ctx, |ui| {
-                ui.horizontal_wrapped(|ui| {
-                    ui.strong(
-                        egui::RichText::new("🔐 Encrypted JSON Editor")
-                            .size(16.0)
-                            .color(egui::Color32::WHITE),
-                    );
-                    ui.separator();
-                    ui.add_space(8.0);
+    #[test]
+    fn list_returns_to_the_focused_entry_after_a_sort_change() {
+        let mut app = App::default();
+        for i in 0..300 {
+            app.data
+                .set_value(format!("key_{:04}", i), EntryValue::Text(i.to_string()));
+        }
+        app.screen = Screen::Editor;
+        let ctx = egui::Context::default();
+        let frame = |app: &mut App| {
+            let input = egui::RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(
+                    egui::Pos2::ZERO,
+                    egui::vec2(1000.0, 700.0),
+                )),
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| app.show_editor_screen(ctx));
+        };
 
-                    // Save button with success animation
-                    if ui
-                        .add(
-                            egui::Button::new("💾 Speichern")
-                                .fill(egui::Color32::from_rgb(40, 167, 69))
-                                .rounding(egui::Rounding::same(6.0)),
-                        )
-                        .clicked()
-                    {
-                        match self.encrypt_data() {
-                            Ok(_) => self.add_toast(
-                                "Erfolgreich gespeichert",
-                                egui::Color32::from_rgb(40, 167, 69),
-                                2.0,
-                                current_time,
-                            ),
-                            Err(e) => {
-                                self.add_toast(
-                                    "Fehler beim Speichern",
-                                    egui::Color32::from_rgb(220, 53, 69),
-                                    3.0,
-                                    current_time,
-                                );
-                                self.error_message = format!("❌ {}", e);
-                            }
-                        }
-                    }
+        app.jump_to_entry("key_0150".into(), 0.0);
+        for _ in 0..3 {
+            frame(&mut app);
+        }
+        assert_eq!(app.last_focused_key.as_deref(), Some("key_0150"));
+        let offset_before = app.list_scroll_offset;
+        assert!(offset_before > 0.0);
 
-                    if ui
-                        .add(
-                            egui::Button::new("🚪 Speichern & Beenden")
-                                .fill(egui::Color32::from_rgb(220, 53, 69))
-                                .rounding(egui::Rounding::same(6.0)),
-                        )
-                        .clicked()
-                    {
-                        let _ = self.encrypt_data();
-                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
-                    }
+        // Absteigend steht der Eintrag an anderer Stelle, die Liste folgt ihm
+        app.sort_mode = SortMode::KeyDescending;
+        app.restore_list_position();
+        app.item_hover_states.clear();
+        // Bis die Scroll-Animation ausgelaufen ist
+        for _ in 0..60 {
+            frame(&mut app);
+        }
+        assert!(app.item_hover_states.contains_key("key_0150"));
+        assert!(app.list_scroll_offset != offset_before);
 
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.add_sized(
-                            [250.0, 28.0],
-                            egui::TextEdit::singleline(&mut self.search_query)
-                                .hint_text("🔍 Einträge durchsuchen..."),
-                        );
-                    });
-                });
-            });
+        // Ohne den Eintrag bleibt wenigstens die Position erhalten
+        let offset = app.list_scroll_offset;
+        app.data.items.shift_remove("key_0150");
+        app.data.touch();
+        app.restore_list_position();
+        assert_eq!(app.list_scroll_restore, Some(offset));
+        frame(&mut app);
+        assert!((app.list_scroll_offset - offset).abs() < 1.0);
+    }
 
-        // Main content with slide animation
-        egui::CentralPanel::default().show(ctx, |ui| {
-            let rect = ui.available_rect_before_wrap();
-            let offset_rect = rect.translate(egui::vec2(0.0, slide_offset));
+    #[test]
+    fn backup_dir_copies_are_throttled_by_the_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = dir.path().join("sync");
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.backup_dir = Some(backups.clone());
+        app.backup_dir_interval_minutes = 10;
+        let copies = || fs::read_dir(&backups).map_or(0, |entries| entries.count());
+        app.encrypt_data().unwrap();
+        assert_eq!(copies(), 1);
 
-            ui.allocate_ui_at_rect(offset_rect, |ui| {
-                ui.add_space(8.0);
+        // Innerhalb des Intervalls keine weitere Kopie, danach schon
+        let last = app.last_backup_dir_copy[&app.vault.path];
+        app.copy_to_backup_dir(last + Duration::from_secs(5 * 60));
+        assert_eq!(copies(), 1);
+        app.copy_to_backup_dir(last + Duration::from_secs(11 * 60));
+        assert_eq!(copies(), 2);
+        assert!(app.error_message.is_empty());
+    }
 
-                // Add new entry card
-                egui::Frame::group(ui.style())
-                    .rounding(egui::Rounding::same(12.0))
-                    .fill(egui::Color32::DARK_GRAY)
-                    .stroke(egui::Stroke::new(
-                        1.5,
-                        egui::Color32::from_rgb(52, 144, 220),
-                    ))
-                    .inner_margin(egui::Margin::symmetric(16.0, 12.0))
-                    .shadow(egui::epaint::Shadow {
-                        offset: egui::vec2(0.0, 2.0),
-                        blur: 8.0,
-                        spread: 0.0,
-                        color: egui::Color32::from_black_alpha(20),
-                    })
-                    .show(ui, |ui| {
-                        ui.horizontal_wrapped(|ui| {
-                            ui.strong("➕ Neuen Eintrag hinzufügen");
-                        });
-                        ui.add_space(8.0);
-                        ui.horizontal_wrapped(|ui| {
-                            ui.label("Schlüssel:");
-                            let key_response = ui.add_sized(
-                                [180.0, 28.0],
-                                egui::TextEdit::singleline(&mut self.new_key)
-                                    .hint_text("z.B. api_key"),
-                            );
+    #[test]
+    fn expiry_dates_are_counted_sorted_and_saved() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "pw");
+        let now = SystemTime::now();
+        let days = |n: u64| Duration::from_secs(n * 86_400);
+        for key in ["alt", "bald", "später", "ohne"] {
+            app.data.set_value(key.into(), EntryValue::Text(key.into()));
+        }
+        app.set_expiry("alt", Some(now - days(1)));
+        app.set_expiry("bald", Some(now + days(3)));
+        app.set_expiry("später", Some(now + days(100)));
+        assert_eq!(expiry_state(now + days(3), now), Some(Expiry::Soon(3)));
+        assert_eq!(expiry_state(now + days(100), now), None);
+        assert_eq!(app.data.expiry_counts(now), (1, 1));
 
-                            ui.add_space(8.0);
-                            ui.label("Wert:");
-                            let value_response = ui.add_sized(
-                                [250.0, 28.0],
-                                egui::TextEdit::singleline(&mut self.new_value)
-                                    .hint_text("z.B. sk-1234567890abcdef"),
-                            );
+        app.sort_mode = SortMode::ExpiresSoonest;
+        assert_eq!(app.visible_keys(100.0), ["alt", "bald", "später", "ohne"]);
 
-                            ui.add_space(8.0);
-                            let can_add = !self.new_key.trim().is_empty();
-                            if ui
-                                .add_enabled(
-                                    can_add,
-                                    egui::Button::new("Hinzufügen")
-                                        .fill(egui::Color32::from_rgb(40, 167, 69))
-                                        .rounding(egui::Rounding::same(6.0))
-                                        .min_size(egui::vec2(80.0, 28.0)),
-                                )
-                                .clicked()
-                            {
-                                self.add_new_entry(current_time);
-                            }
+        app.encrypt_data().unwrap();
+        let mut reopened = Vault::new(app.vault.path.clone());
+        let data = reopened.load("pw").unwrap();
+        assert_eq!(data.expires_at("bald"), app.data.expires_at("bald"));
+        assert_eq!(data.expires_at("ohne"), None);
 
-                            // Enter key support
-                            if (key_response.lost_focus() || value_response.lost_focus())
-                                && ui.input(|i| i.key_pressed(egui::Key::Enter))
-                                && can_add
-                            {
-                                self.add_new_entry(current_time);
-                            }
-                        });
-                    });
+        app.set_expiry("alt", None);
+        assert_eq!(app.data.expiry_counts(now), (0, 1));
+    }
 
-                ui.add_space(12.0);
-                ui.separator();
-                ui.add_space(8.0);
+    #[test]
+    fn about_dialog_reads_the_open_files_header() {
+        let info = build_info();
+        assert_eq!(info[0].1, env!("CARGO_PKG_VERSION"));
+        assert_eq!(info[1].1, FORMAT_VERSION.to_string());
 
-                // Items list with animations
-                egui::ScrollArea::vertical()
-                    .auto_shrink([false; 2])
-                    .show(ui, |ui| {
-                        if self.data.items.is_empty() {
-                            ui.vertical_centered(|ui| {
-                                ui.add_space(40.0);
-                                ui.label(
-                                    egui::RichText::new("📝 Noch keine Einträge vorhanden")
-                                        .size(16.0)
-                                        .color(egui::Color32::from_gray(120)),
-                                );
-                                ui.add_space(8.0);
-                                ui.label(
-                                    egui::RichText::new("Füge oben deinen ersten Eintrag hinzu")
-                                        .size(14.0)
-                                        .color(egui::Color32::from_gray(100)),
-                                );
-                                ui.add_space(40.0);
-                            });
-                        } else {
-                            let mut keys: Vec<String> = self.data.items.keys().cloned().collect();
-                            keys.sort();
-
-                            let filter = self.search_query.to_lowercase();
-
-                            for key in keys {
-                                if !filter.is_empty() && !key.to_lowercase().contains(&filter) {
-                                    continue;
-                                }
-
-                                let mut value = self.data.items[&key].clone();
-                                let hover_state =
-                                    self.item_hover_states.entry(key.clone()).or_insert(0.0);
-
-                                let hover_progress = *hover_state;
-                                let bg_color = egui::Color32::DARK_GRAY;
-
-                                egui::Frame::group(ui.style())
-                                    .rounding(egui::Rounding::same(10.0))
-                                    .fill(bg_color)
-                                    .stroke(egui::Stroke::new(
-                                        1.0 + hover_progress * 0.5,
-                                        egui::Color32::from_rgb(
-                                            (222 as f32 * (1.0 - hover_progress)
-                                                + 52.0 * hover_progress)
-                                                as u8,
-                                            (226 as f32 * (1.0 - hover_progress)
-                                                + 144.0 * hover_progress)
-                                                as u8,
-                                            (230 as f32 * (1.0 - hover_progress)
-                                                + 220.0 * hover_progress)
-                                                as u8,
-                                        ),
-                                    ))
-                                    .inner_margin(egui::Margin::symmetric(14.0, 10.0))
-                                    .shadow(egui::epaint::Shadow {
-                                        offset: egui::vec2(0.0, 1.0 + hover_progress * 2.0),
-                                        blur: 4.0 + hover_progress * 4.0,
-                                        spread: 0.0,
-                                        color: egui::Color32::from_black_alpha(
-                                            (20.0 + hover_progress * 20.0) as u8,
-                                        ),
-                                    })
-                                    .show(ui, |ui| {
-                                        ui.vertical(|ui| {
-                                            ui.horizontal(|ui| {
-                                                ui.strong(&key);
-                                                ui.with_layout(
-                                                    egui::Layout::right_to_left(
-                                                        egui::Align::Center,
-                                                    ),
-                                                    |ui| {
-                                                        if ui
-                                                            .add(
-                                                                egui::Button::new("❌")
-                                                                    .fill(egui::Color32::from_rgb(
-                                                                        255, 240, 240,
-                                                                    ))
-                                                                    .stroke(egui::Stroke::new(
-                                                                        1.0,
-                                                                        egui::Color32::from_rgb(
-                                                                            220, 53, 69,
-                                                                        ),
-                                                                    ))
-                                                                    .rounding(
-                                                                        egui::Rounding::same(6.0),
-                                                                    ),
-                                                            )
-                                                            .on_hover_text("Eintrag löschen")
-                                                            .clicked()
-                                                        {
-                                                            self.delete_candidate =
-                                                                Some(key.clone());
-                                                        }
-
-                                                        ui.add_space(4.0);
-
-                                                        if ui
-                                                            .add(
-                                                                egui::Button::new("📋")
-                                                                    .fill(egui::Color32::from_rgb(
-                                                                        240, 248, 255,
-                                                                    ))
-                                                                    .stroke(egui::Stroke::new(
-                                                                        1.0,
-                                                                        egui::Color32::from_rgb(
-                                                                            52, 144, 220,
-                                                                        ),
-                                                                    ))
-                                                                    .rounding(
-                                                                        egui::Rounding::same(6.0),
-                                                                    ),
-                                                            )
-                                                            .on_hover_text("Wert kopieren")
-                                                            .clicked()
-                                                        {
-                                                            ui.output_mut(|o| {
-                                                                o.copied_text =
-                                                                    self.data.items[&key].clone()
-                                                            });
-                                                            self.add_toast(
-                                                                "In Zwischenablage kopiert",
-                                                                egui::Color32::from_rgb(
-                                                                    52, 144, 220,
-                                                                ),
-                                                                1.5,
-                                                                current_time,
-                                                            );
-                                                        }
-                                                    },
-                                                );
-                                            });
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = App {
+            vault: Vault::new(dir.path().join("data.enc")),
+            ..App::default()
+        };
+        app.open_about();
+        assert!(app.about_open && app.about_file.is_none());
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.vault.header.cipher = CipherSuite::ChaCha20Poly1305;
+        app.encrypt_data().unwrap();
+        app.open_about();
+        let (version, header) = app.about_file.clone().unwrap();
+        assert_eq!(version, FORMAT_VERSION);
+        assert_eq!(header.cipher, CipherSuite::ChaCha20Poly1305);
+        assert!(app.close_dialogs());
+        assert!(!app.about_open);
+    }
 
-                                            ui.add_space(4.0);
-                                            let text_response = ui.add(
-                                                egui::TextEdit::singleline(&mut value)
-                                                    .desired_width(ui.available_width()),
-                                            );
+    #[test]
+    fn fuzzy_search_tolerates_gaps_and_ranks_by_score() {
+        let mut app = App {
+            search_fuzzy: true,
+            sort_mode: SortMode::KeyAscending,
+            ..App::default()
+        };
+        for (key, value) in [
+            ("database_password", "x"),
+            ("db_pw", "y"),
+            ("dumb_paperwork", "z"),
+            ("smtp_host", "dbpw-mirror"),
+        ] {
+            app.data
+                .set_value(key.into(), EntryValue::Text(value.into()));
+        }
+        app.search_query = "dbpw".into();
+        assert_eq!(
+            app.visible_keys(0.0),
+            ["db_pw", "smtp_host", "database_password", "dumb_paperwork"]
+        );
 
-                                            if text_response.changed() {
-                                                self.data.items.insert(key.clone(), value);
-                                            }
-                                        });
-                                    });
-                                ui.add_space(4.0);
-                            }
-                        }
-                    });
+        app.search_scope = SearchScope::Keys;
+        assert_eq!(app.visible_keys(0.0).len(), 3);
 
-                // Error display
-                if !self.error_message.is_empty() {
-                    ui.add_space(8.0);
-                    ui.colored_label(egui::Color32::from_rgb(220, 53, 69), &self.error_message);
-                }
-            });
+        // Ohne Unschärfe bleibt es bei der wörtlichen Suche
+        app.search_fuzzy = false;
+        assert!(app.visible_keys(0.0).is_empty());
+        app.search_query = "pw".into();
+        assert_eq!(app.visible_keys(0.0), ["db_pw"]);
+    }
+
+    #[test]
+    fn attachments_survive_saving_and_export_their_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let cert = dir.path().join("server.pem");
+        let bytes = b"-----BEGIN CERTIFICATE-----\n\x00\xff\n".to_vec();
+        fs::write(&cert, &bytes).unwrap();
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.data
+            .set_value("tls".into(), EntryValue::Text("alt".into()));
+        app.attach_file("tls", &cert, 0.0);
+        let EntryValue::Attachment(attachment) = &app.data.items["tls"] else {
+            panic!("kein Anhang: {:?}", app.data.items["tls"]);
+        };
+        assert_eq!(attachment.mime, "application/x-pem-file");
+        assert_eq!(app.data.items["tls"].as_text(), "server.pem");
+
+        // Zu große Dateien lassen den Wert unverändert
+        let big = dir.path().join("big.bin");
+        fs::write(&big, vec![0u8; MAX_ATTACHMENT_BYTES + 1]).unwrap();
+        app.attach_file("tls", &big, 0.0);
+        assert!(app.error_message.contains("1.0 MiB"));
+        assert!(matches!(app.data.items["tls"], EntryValue::Attachment(_)));
+
+        // Normale Objekte mit ähnlichen Feldern bleiben JSON
+        app.data.set_value(
+            "config".into(),
+            EntryValue::from(serde_json::json!({"mime": "text/plain", "base64": "AA=="})),
+        );
+        app.encrypt_data().unwrap();
+        let data = Vault::new(app.vault.path.clone()).load("pw").unwrap();
+        assert_eq!(data.items["tls"], app.data.items["tls"]);
+        assert!(matches!(data.items["config"], EntryValue::Json(_)));
+
+        let exported = dir.path().join("export.pem");
+        app.save_attachment("tls", &exported, 0.0);
+        assert_eq!(fs::read(&exported).unwrap(), bytes);
+    }
+
+    #[test]
+    fn password_policy_blocks_weak_new_passwords_but_not_unlocking() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = PasswordPolicy {
+            min_length: 8,
+            require_digit: true,
+            ..PasswordPolicy::default()
+        };
+        let mut weak = App {
+            vault: Vault::new(dir.path().join("data.enc")),
+            password: "kurz".into(),
+            password_repeat: "kurz".into(),
+            password_policy: policy.clone(),
+            ..App::default()
+        };
+        weak.create_vault(0.0);
+        assert!(weak.key_job.is_none() && !weak.vault.exists());
+        assert!(weak.error_message.contains("8"), "{}", weak.error_message);
+
+        let mut app = unlocked_app(dir.path(), "langes-pw1");
+        app.password_policy = policy;
+        assert!(matches!(app.screen, Screen::Editor));
+
+        app.password_change_current = "langes-pw1".into();
+        app.password_change_new = "ohneziffer".into();
+        app.password_change_repeat = app.password_change_new.clone();
+        app.change_password();
+        assert!(app.password_change_error.contains("Ziffer"));
+        assert_eq!(app.password, "langes-pw1");
+
+        // `--policy` hat Vorrang; ein bestehendes Passwort bleibt beim Entsperren gültig
+        app.policy_override = Some(PasswordPolicy {
+            min_length: 20,
+            ..PasswordPolicy::default()
         });
+        app.lock(1.0);
+        app.password = "langes-pw1".into();
+        app.try_login();
+        settle(&mut app);
+        assert!(matches!(app.screen, Screen::Editor));
+        app.password_change_current = "falsch".into();
+        app.password_change_new = "zwanzig-zeichen-lang".into();
+        app.password_change_repeat = app.password_change_new.clone();
+        // Beide Ableitungen laufen im Hintergrund
+        app.change_password();
+        assert!(app.key_job.is_some());
+        settle(&mut app);
+        assert_eq!(app.password_change_error, "Aktuelles Passwort ist falsch");
+        assert_eq!(app.password, "langes-pw1");
+
+        app.password_change_current = "langes-pw1".into();
+        app.change_password();
+        settle(&mut app);
+        assert!(app.password_change_error.is_empty());
+        assert_eq!(app.password, "zwanzig-zeichen-lang");
     }
-}
 
-impl eframe::App for App {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let dt = ctx.input(|i| i.stable_dt);
-        self.update_animations(ctx, dt);
+    #[test]
+    fn typing_and_saving_in_the_same_frame_keeps_the_last_keystroke() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.data
+            .set_value("token".into(), EntryValue::Text("alt".into()));
 
-        match self.screen {
-            Screen::PasswordInput => {
-                self.show_login_screen(ctx);
-            }
-            Screen::Editor => {
-                self.show_editor_screen(ctx);
-            }
-        }
+        let ctx = egui::Context::default();
+        let frame = |app: &mut App, events: Vec<egui::Event>| {
+            let input = egui::RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(
+                    egui::Pos2::ZERO,
+                    egui::vec2(1000.0, 700.0),
+                )),
+                events,
+                ..Default::default()
+            };
+            let _ = ctx.run(input, |ctx| app.show_editor_screen(ctx));
+        };
+        frame(&mut app, Vec::new());
+        ctx.memory_mut(|m| m.request_focus(entry_value_id("token")));
+        frame(&mut app, Vec::new());
+        frame(
+            &mut app,
+            vec![
+                egui::Event::Text("neu".into()),
+                egui::Event::Key {
+                    key: egui::Key::S,
+                    physical_key: None,
+                    pressed: true,
+                    repeat: false,
+                    modifiers: egui::Modifiers::COMMAND,
+                },
+            ],
+        );
 
-        // Show overlays
-        self.show_toasts(ctx);
-        self.show_delete_confirm_dialog(ctx);
+        assert!(!app.dirty);
+        let data = Vault::new(app.vault.path.clone()).load("pw").unwrap();
+        assert_eq!(data.items["token"], EntryValue::Text("altneu".into()));
     }
-}
 
-fn main() -> eframe::Result<()> {
-    let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1000.0, 700.0])
-            .with_min_inner_size([600.0, 500.0])
-            .with_icon(eframe::icon_data::from_png_bytes(&[]).unwrap_or_default()),
-        ..Default::default()
-    };
+    #[test]
+    fn clearing_all_entries_backs_up_first_and_can_be_undone() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = unlocked_app(dir.path(), "pw");
+        app.push_undo();
+        app.data
+            .set_value("db".into(), EntryValue::Text("geheim".into()));
+        app.data.critical.insert("db".into());
+        app.data.notes = "Notizblock".into();
 
-    eframe::run_native(
-        "Encrypted JSON Editor",
-        options,
-        Box::new(|_cc| Ok(Box::<App>::default())),
-    )
-}
+        // Die ungespeicherte Änderung landet in der Sicherung, nicht nur der alte Stand
+        app.clear_all_entries(true, 0.0);
+        assert!(app.data.items.is_empty() && app.data.critical.is_empty());
+        assert_eq!(app.data.notes, "Notizblock");
+        assert!(app.dirty);
+        let backups: Vec<PathBuf> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.to_string_lossy().contains("data-"))
+            .collect();
+        assert_eq!(backups.len(), 1);
+        let backup = Vault::new(backups[0].clone()).load("pw").unwrap();
+        assert_eq!(backup.items["db"], EntryValue::Text("geheim".into()));
 
-/// Einfaches Ease-In-Out (Smoothstep) Helferlein
-fn ease_in_out(t: f32) -> f32 {
-    // clamp zwischen 0 und 1
-    let t = t.clamp(0.0, 1.0);
-    t * t * (3.0 - 2.0 * t)
+        app.undo(0.0);
+        assert!(app.data.items.contains_key("db"));
+
+        // Scheitert die Sicherung, wird nichts gelöscht
+        app.backup_dir = Some(backups[0].clone());
+        app.clear_all_entries(true, 0.0);
+        assert!(app.data.items.contains_key("db"));
+        assert!(app.error_message.contains("nichts gelöscht"));
+    }
+
+    #[test]
+    fn vault_argument_rejects_directories_and_options() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(check_vault_arg(dir.path().to_str().unwrap()).is_err());
+        assert!(check_vault_arg("--verbose").is_err());
+        assert!(check_vault_arg(dir.path().join("my.vault").to_str().unwrap()).is_ok());
+    }
 }
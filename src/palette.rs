@@ -0,0 +1,163 @@
+//! Befehlspalette (Strg+P): unscharfe Suche über Schlüssel und Aktionen
+
+use std::ops::Range;
+
+use crate::i18n::tr;
+
+/// Mehr Treffer passen ohnehin nicht in das Fenster
+const MAX_RESULTS: usize = 50;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum PaletteCommand {
+    AddEntry,
+    Save,
+    Export,
+    Lock,
+    Settings,
+    JsonSource,
+    Shortcuts,
+    About,
+}
+
+impl PaletteCommand {
+    const ALL: [PaletteCommand; 8] = [
+        PaletteCommand::AddEntry,
+        PaletteCommand::Save,
+        PaletteCommand::Export,
+        PaletteCommand::Lock,
+        PaletteCommand::Settings,
+        PaletteCommand::JsonSource,
+        PaletteCommand::Shortcuts,
+        PaletteCommand::About,
+    ];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            PaletteCommand::AddEntry => tr("Eintrag hinzufügen"),
+            PaletteCommand::Save => tr("Speichern"),
+            PaletteCommand::Export => tr("Exportieren (JSON)"),
+            PaletteCommand::Lock => tr("Sperren"),
+            PaletteCommand::Settings => tr("Einstellungen"),
+            PaletteCommand::JsonSource => tr("JSON-Quelltext anzeigen"),
+            PaletteCommand::Shortcuts => tr("Tastenkürzel"),
+            PaletteCommand::About => tr("Über"),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum PaletteItem {
+    Command(PaletteCommand),
+    Key(String),
+}
+
+/// Bewertet, ob alle Zeichen von `query` in dieser Reihenfolge in `candidate`
+/// vorkommen (ohne Groß-/Kleinschreibung). Zusammenhängende Treffer und Treffer
+/// am Wortanfang zählen mehr; `None` heißt kein Treffer.
+pub(crate) fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    fuzzy_match(query, candidate).map(|(score, _)| score)
+}
+
+/// Wie `fuzzy_score`, zusätzlich mit den getroffenen Bytebereichen in `candidate`
+/// zum Hervorheben; benachbarte Zeichen sind zu einem Bereich zusammengefasst
+pub(crate) fn fuzzy_match(query: &str, candidate: &str) -> Option<(i32, Vec<Range<usize>>)> {
+    let mut score = 0;
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut needle = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut previous: Option<char> = None;
+    let mut previous_matched = false;
+    for (index, c) in candidate.char_indices() {
+        let Some(&wanted) = needle.peek() else {
+            break;
+        };
+        let word_start = match previous {
+            None => true,
+            Some(p) => !p.is_alphanumeric() || (p.is_lowercase() && c.is_uppercase()),
+        };
+        if c.to_lowercase().eq(std::iter::once(wanted)) {
+            needle.next();
+            score += 1;
+            match ranges.last_mut() {
+                Some(last) if last.end == index => last.end += c.len_utf8(),
+                _ => ranges.push(index..index + c.len_utf8()),
+            }
+            if previous_matched {
+                score += 5;
+            }
+            if word_start {
+                score += 8;
+            }
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous = Some(c);
+    }
+    needle.peek().is_none().then_some((score, ranges))
+}
+
+/// Treffer für die Eingabe, beste zuerst. Ohne Eingabe erscheinen erst alle
+/// Befehle, dann die Schlüssel in Listenreihenfolge.
+pub(crate) fn palette_matches(query: &str, keys: &[String], read_only: bool) -> Vec<PaletteItem> {
+    let commands = PaletteCommand::ALL
+        .into_iter()
+        .filter(|command| !read_only || *command != PaletteCommand::AddEntry);
+    let query = query.trim();
+    if query.is_empty() {
+        return commands
+            .map(PaletteItem::Command)
+            .chain(keys.iter().cloned().map(PaletteItem::Key))
+            .take(MAX_RESULTS)
+            .collect();
+    }
+    let mut scored: Vec<(i32, usize, PaletteItem)> = commands
+        .filter_map(|command| {
+            let label = command.label();
+            fuzzy_score(query, label)
+                .map(|score| (score, label.len(), PaletteItem::Command(command)))
+        })
+        .chain(keys.iter().filter_map(|key| {
+            fuzzy_score(query, key).map(|score| (score, key.len(), PaletteItem::Key(key.clone())))
+        }))
+        .collect();
+    // Bei gleicher Wertung gewinnt der kürzere Name, die Reihenfolge bleibt sonst stabil
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    scored
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, _, item)| item)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn subsequence_matches_prefer_word_starts_and_runs() {
+        assert!(fuzzy_score("dbp", "database_password").is_some());
+        assert!(fuzzy_score("pdb", "database_password").is_none());
+        assert!(fuzzy_score("", "egal").is_some());
+        assert!(
+            fuzzy_score("dbpw", "db_password").unwrap()
+                > fuzzy_score("dbpw", "dumb_paperwork").unwrap()
+        );
+        assert!(fuzzy_score("key", "apiKey").unwrap() > fuzzy_score("key", "monkeys").unwrap());
+        assert_eq!(
+            fuzzy_match("dbpw", "db_password").unwrap().1,
+            [0..2, 3..4, 7..8]
+        );
+        assert_eq!(fuzzy_match("gün", "grün").unwrap().1, [0..1, 2..5]);
+
+        let keys = vec![
+            "stripe_secret_key".to_string(),
+            "smtp_server".to_string(),
+            "ssh_key".to_string(),
+        ];
+        let matches = palette_matches("sk", &keys, false);
+        assert_eq!(matches[0], PaletteItem::Key("ssh_key".into()));
+        assert!(!matches.contains(&PaletteItem::Key("smtp_server".into())));
+        assert!(!palette_matches("", &keys, true)
+            .contains(&PaletteItem::Command(PaletteCommand::AddEntry)));
+    }
+}
@@ -0,0 +1,156 @@
+//! Passwortrichtlinie für neue Vaults und Passwortwechsel, z.B. für Teams mit
+//! gemeinsamer Konfiguration. Bestehende Vaults werden beim Entsperren nicht geprüft.
+
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, trf};
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub(crate) struct PasswordPolicy {
+    /// In Zeichen, nicht Bytes; 0 = keine Vorgabe
+    pub(crate) min_length: usize,
+    pub(crate) require_lowercase: bool,
+    pub(crate) require_uppercase: bool,
+    pub(crate) require_digit: bool,
+    /// Alles außer Buchstaben, Ziffern und Leerraum
+    pub(crate) require_symbol: bool,
+}
+
+impl PasswordPolicy {
+    pub(crate) fn is_active(&self) -> bool {
+        *self != PasswordPolicy::default()
+    }
+
+    /// Liest die Angabe von `--policy`, z.B. `min=12,upper,lower,digit,symbol`
+    pub(crate) fn parse(spec: &str) -> Result<Self, String> {
+        let mut policy = PasswordPolicy::default();
+        for part in spec
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            match part.split_once('=') {
+                Some(("min", length)) => {
+                    policy.min_length = length
+                        .trim()
+                        .parse()
+                        .map_err(|_| format!("Ungültige Mindestlänge in --policy: {}", length))?;
+                }
+                None if part == "lower" => policy.require_lowercase = true,
+                None if part == "upper" => policy.require_uppercase = true,
+                None if part == "digit" => policy.require_digit = true,
+                None if part == "symbol" => policy.require_symbol = true,
+                _ => {
+                    return Err(format!(
+                        "Unbekannte Angabe in --policy: {} (erlaubt: min=N, lower, upper, digit, symbol)",
+                        part
+                    ))
+                }
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Geforderte Zeichenklassen mit Prüfung und Bezeichnung
+    fn required_classes(&self) -> impl Iterator<Item = (fn(char) -> bool, &'static str)> {
+        [
+            (
+                self.require_lowercase,
+                char::is_lowercase as fn(char) -> bool,
+                "ein Kleinbuchstabe",
+            ),
+            (
+                self.require_uppercase,
+                char::is_uppercase,
+                "ein Großbuchstabe",
+            ),
+            (self.require_digit, char::is_numeric, "eine Ziffer"),
+            (self.require_symbol, is_symbol, "ein Sonderzeichen"),
+        ]
+        .into_iter()
+        .filter(|(required, _, _)| *required)
+        .map(|(_, test, label)| (test, label))
+    }
+
+    /// Die unerfüllten Vorgaben, übersetzt
+    fn unmet(&self, password: &str) -> Vec<String> {
+        let mut unmet = Vec::new();
+        if password.chars().count() < self.min_length {
+            unmet.push(trf("mindestens {} Zeichen", &[&self.min_length]));
+        }
+        for (test, label) in self.required_classes() {
+            if !password.chars().any(test) {
+                unmet.push(tr(label).to_string());
+            }
+        }
+        unmet
+    }
+
+    /// Fehlermeldung mit allen fehlenden Vorgaben auf einmal
+    pub(crate) fn check(&self, password: &str) -> Result<(), String> {
+        let unmet = self.unmet(password);
+        if unmet.is_empty() {
+            Ok(())
+        } else {
+            Err(trf(
+                "Passwort erfüllt die Richtlinie nicht, es fehlt: {}",
+                &[&unmet.join(", ")],
+            ))
+        }
+    }
+
+    /// Kurzbeschreibung für Hinweise unter den Passwortfeldern
+    pub(crate) fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.min_length > 0 {
+            parts.push(trf("mindestens {} Zeichen", &[&self.min_length]));
+        }
+        parts.extend(
+            self.required_classes()
+                .map(|(_, label)| tr(label).to_string()),
+        );
+        trf("Passwort-Richtlinie: {}", &[&parts.join(", ")])
+    }
+}
+
+fn is_symbol(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_accepts_and_rejects_at_the_boundaries() {
+        let policy = PasswordPolicy::parse("min=12, upper,digit").unwrap();
+        assert_eq!(
+            policy,
+            PasswordPolicy {
+                min_length: 12,
+                require_uppercase: true,
+                require_digit: true,
+                ..PasswordPolicy::default()
+            }
+        );
+        assert!(policy.check("Abcdefghij1").is_err());
+        assert!(policy.check("Abcdefghijk1").is_ok());
+        // Zeichen zählen, nicht Bytes
+        assert!(policy.check("Äöüäöüäöüä1").is_err());
+        assert!(policy.check("Äöüäöüäöüäö1").is_ok());
+
+        let e = policy.check("abc").unwrap_err();
+        assert!(e.contains("12") && e.contains("Großbuchstabe") && e.contains("Ziffer"));
+
+        let symbol = PasswordPolicy::parse("symbol,lower").unwrap();
+        assert!(symbol.check("nur buchstaben").is_err());
+        assert!(symbol.check("mit-strich").is_ok());
+        assert!(symbol.check("MIT-STRICH").is_err());
+
+        assert!(!PasswordPolicy::parse("").unwrap().is_active());
+        assert!(PasswordPolicy::default().check("").is_ok());
+        assert!(PasswordPolicy::parse("min=zwölf").is_err());
+        assert!(PasswordPolicy::parse("emoji").is_err());
+    }
+}
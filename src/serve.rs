@@ -0,0 +1,250 @@
+//! Schreibgeschützter HTTP-Zugang für lokale Skripte (`--serve 127.0.0.1:PORT`).
+//! Der Server lauscht nur auf Loopback, verlangt ein Bearer-Token und läuft nur,
+//! solange der Vault entsperrt ist.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use aes_gcm::aead::{rand_core::RngCore, OsRng};
+
+/// So oft prüft der Server, ob er beendet werden soll
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// Langsame Clients dürfen den einzigen Server-Thread nicht blockieren
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+/// Mehr braucht keine GET-Anfrage
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// Prüft die Adresse für `--serve`; alles außer Loopback wird abgelehnt
+pub(crate) fn parse_serve_addr(text: &str) -> Result<SocketAddr, String> {
+    let addr: SocketAddr = text
+        .parse()
+        .map_err(|_| format!("Ungültige Adresse für --serve: {}", text))?;
+    if !addr.ip().is_loopback() {
+        return Err(format!(
+            "--serve erlaubt nur 127.0.0.1 oder [::1], nicht {}",
+            addr.ip()
+        ));
+    }
+    Ok(addr)
+}
+
+/// Zufälliges Bearer-Token, 32 Bytes als Hex
+pub(crate) fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Laufender Server. Die Einträge liegen als fertiges JSON bereit und werden
+/// von der Oberfläche bei jeder Änderung ersetzt; `Drop` beendet den Thread.
+pub(crate) struct Server {
+    local_addr: SocketAddr,
+    entries: Arc<Mutex<String>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    pub(crate) fn start(addr: SocketAddr, token: String, entries: String) -> Result<Self, String> {
+        if !addr.ip().is_loopback() {
+            return Err(format!("Keine Loopback-Adresse: {}", addr));
+        }
+        let listener = TcpListener::bind(addr).map_err(|e| e.to_string())?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+        let entries = Arc::new(Mutex::new(entries));
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = {
+            let entries = entries.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    match listener.accept() {
+                        Ok((stream, peer)) if peer.ip().is_loopback() => {
+                            let _ = handle_connection(stream, &token, &entries);
+                        }
+                        // Kann über Loopback nicht vorkommen, wird aber nie bedient
+                        Ok(_) => {}
+                        Err(_) => std::thread::sleep(POLL_INTERVAL),
+                    }
+                }
+            })
+        };
+        Ok(Self {
+            local_addr,
+            entries,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    pub(crate) fn update(&self, entries: String) {
+        if let Ok(mut current) = self.entries.lock() {
+            *current = entries;
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        // Entschlüsselte Daten nicht länger als nötig im Speicher halten
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.clear();
+        }
+    }
+}
+
+/// Beantwortet genau eine Anfrage:
+/// `GET /entries` liefert alle Einträge, `GET /entries?key=NAME` einen Wert
+fn handle_connection(
+    mut stream: TcpStream,
+    token: &str,
+    entries: &Mutex<String>,
+) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let request = read_request_head(&mut stream)?;
+    let (status, body) = respond(&request, token, entries);
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())
+}
+
+fn read_request_head(stream: &mut TcpStream) -> std::io::Result<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_BYTES {
+        let read = stream.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+fn respond(request: &str, token: &str, entries: &Mutex<String>) -> (&'static str, String) {
+    let mut lines = request.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (method, target) = (request_line.next(), request_line.next().unwrap_or_default());
+    let authorized = lines
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.split_once(':'))
+        .filter(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .any(|(_, value)| {
+            value
+                .trim()
+                .strip_prefix("Bearer ")
+                .is_some_and(|given| tokens_equal(given.trim(), token))
+        });
+    if !authorized {
+        return (
+            "401 Unauthorized",
+            error_body("Token fehlt oder ist falsch"),
+        );
+    }
+    if method != Some("GET") {
+        return (
+            "405 Method Not Allowed",
+            error_body("Nur GET wird unterstützt"),
+        );
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path != "/entries" {
+        return ("404 Not Found", error_body("Unbekannter Pfad"));
+    }
+    let entries = entries.lock().map(|e| e.clone()).unwrap_or_default();
+    let key = url::form_urlencoded::parse(query.as_bytes())
+        .find(|(name, _)| name == "key")
+        .map(|(_, value)| value.into_owned());
+    let Some(key) = key else {
+        return ("200 OK", entries);
+    };
+    let value = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&entries)
+        .ok()
+        .and_then(|mut map| map.remove(&key));
+    match value {
+        Some(value) => ("200 OK", value.to_string()),
+        None => ("404 Not Found", error_body("Schlüssel nicht gefunden")),
+    }
+}
+
+fn error_body(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+/// Vergleich ohne frühen Abbruch, damit die Laufzeit das Token nicht verrät
+fn tokens_equal(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(addr: SocketAddr, target: &str, token: Option<&str>) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let auth = token
+            .map(|token| format!("Authorization: Bearer {}\r\n", token))
+            .unwrap_or_default();
+        write!(stream, "GET {} HTTP/1.1\r\nHost: x\r\n{}\r\n", target, auth).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn serves_entries_on_loopback_with_a_token_only() {
+        assert!(parse_serve_addr("127.0.0.1:8080").is_ok());
+        assert!(parse_serve_addr("[::1]:8080").is_ok());
+        assert!(parse_serve_addr("0.0.0.0:8080").is_err());
+        assert!(parse_serve_addr("192.168.1.5:8080").is_err());
+        assert!(parse_serve_addr("localhost").is_err());
+
+        let token = new_token();
+        assert_eq!(token.len(), 64);
+        let server = Server::start(
+            "127.0.0.1:0".parse().unwrap(),
+            token.clone(),
+            r#"{"db password":"hunter2","port":5432}"#.into(),
+        )
+        .unwrap();
+        let addr = server.local_addr();
+
+        assert!(get(addr, "/entries", None).starts_with("HTTP/1.1 401"));
+        assert!(get(addr, "/entries", Some("falsch")).starts_with("HTTP/1.1 401"));
+        let all = get(addr, "/entries", Some(&token));
+        assert!(all.starts_with("HTTP/1.1 200") && all.ends_with(r#""port":5432}"#));
+        let one = get(addr, "/entries?key=db+password", Some(&token));
+        assert!(one.ends_with("\r\n\r\n\"hunter2\""));
+        assert!(get(addr, "/entries?key=x", Some(&token)).starts_with("HTTP/1.1 404"));
+
+        server.update(r#"{"port":1}"#.into());
+        assert!(get(addr, "/entries", Some(&token)).ends_with(r#"{"port":1}"#));
+
+        drop(server);
+        assert!(TcpStream::connect(addr).is_err());
+    }
+}
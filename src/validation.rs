@@ -0,0 +1,132 @@
+//! Optionale Prüfregeln für den Wert eines Eintrags
+
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, trf};
+use crate::EntryValue;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ValidationRule {
+    #[default]
+    None,
+    Url,
+    Integer,
+    /// Muss auf den ganzen Wert passen, nicht nur auf einen Teil
+    Regex(String),
+}
+
+impl ValidationRule {
+    pub(crate) fn is_none(&self) -> bool {
+        *self == ValidationRule::None
+    }
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            ValidationRule::None => tr("Keine Regel"),
+            ValidationRule::Url => tr("URL"),
+            ValidationRule::Integer => tr("Ganzzahl"),
+            ValidationRule::Regex(_) => tr("Regulärer Ausdruck"),
+        }
+    }
+
+    /// Liefert die bereits übersetzte Meldung, wenn der Wert die Regel verletzt
+    pub(crate) fn check(&self, value: &EntryValue) -> Result<(), String> {
+        match (self, value) {
+            (ValidationRule::None, _) => Ok(()),
+            (ValidationRule::Url, EntryValue::Text(text)) => match url::Url::parse(text.trim()) {
+                Ok(url) if url.has_host() => Ok(()),
+                _ => Err(tr("Keine gültige URL (z.B. https://example.com)").to_string()),
+            },
+            (ValidationRule::Integer, EntryValue::Number(number)) if number.fract() == 0.0 => {
+                Ok(())
+            }
+            (ValidationRule::Integer, EntryValue::Text(text))
+                if text.trim().parse::<i64>().is_ok() =>
+            {
+                Ok(())
+            }
+            (ValidationRule::Integer, _) => Err(tr("Keine ganze Zahl").to_string()),
+            (ValidationRule::Regex(pattern), value) => {
+                let regex = regex::Regex::new(&format!("^(?:{})$", pattern))
+                    .map_err(|_| trf("Ungültiges Muster: {}", &[&pattern]))?;
+                if regex.is_match(&value.as_text()) {
+                    Ok(())
+                } else {
+                    Err(trf("Passt nicht zum Muster {}", &[&pattern]))
+                }
+            }
+            (ValidationRule::Url, _) => {
+                Err(tr("Keine gültige URL (z.B. https://example.com)").to_string())
+            }
+        }
+    }
+}
+
+/// Inhalt des ✔-Menüs einer Zeile; liefert die neue Regel, sobald sie sich ändert
+pub(crate) fn show_rule_menu(
+    ui: &mut egui::Ui,
+    rule: &ValidationRule,
+    pattern: &mut String,
+) -> Option<ValidationRule> {
+    let mut chosen = None;
+    for candidate in [
+        ValidationRule::None,
+        ValidationRule::Url,
+        ValidationRule::Integer,
+    ] {
+        if ui
+            .selectable_label(*rule == candidate, candidate.label())
+            .clicked()
+        {
+            chosen = Some(candidate);
+        }
+    }
+    ui.separator();
+    ui.label(ValidationRule::Regex(String::new()).label());
+    ui.horizontal(|ui| {
+        ui.add(
+            egui::TextEdit::singleline(pattern)
+                .hint_text("[A-Z]{3}-\\d+")
+                .desired_width(160.0),
+        );
+        if ui
+            .add_enabled(!pattern.is_empty(), egui::Button::new(tr("Übernehmen")))
+            .clicked()
+        {
+            chosen = Some(ValidationRule::Regex(pattern.clone()));
+        }
+    });
+    if chosen.is_some() {
+        ui.close_menu();
+    }
+    chosen.filter(|candidate| candidate != rule)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_accept_matching_values_only() {
+        let text = |s: &str| EntryValue::Text(s.to_string());
+        assert!(ValidationRule::Url
+            .check(&text("https://example.com/x"))
+            .is_ok());
+        assert!(ValidationRule::Url.check(&text("example.com")).is_err());
+        assert!(ValidationRule::Integer
+            .check(&EntryValue::Number(42.0))
+            .is_ok());
+        assert!(ValidationRule::Integer
+            .check(&EntryValue::Number(4.2))
+            .is_err());
+        assert!(ValidationRule::Integer.check(&text(" 17 ")).is_ok());
+
+        let rule = ValidationRule::Regex("[A-Z]{3}-\\d+".into());
+        assert!(rule.check(&text("ABC-12")).is_ok());
+        assert!(rule.check(&text("xABC-12")).is_err());
+        assert!(ValidationRule::Regex("(".into()).check(&text("x")).is_err());
+        assert!(ValidationRule::None.check(&EntryValue::Bool(true)).is_ok());
+    }
+}
@@ -0,0 +1,1517 @@
+//! Verschlüsselung und Dateiformat des Vaults, unabhängig von der Oberfläche
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use aes_gcm::{
+    aead::{self, Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm,
+};
+use argon2::{
+    password_hash::{rand_core::RngCore, SaltString},
+    Algorithm, Argon2, Params, PasswordHasher, Version,
+};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use blake2::{
+    digest::{Digest, Mac},
+    Blake2s256, Blake2sMac256,
+};
+use chacha20poly1305::ChaCha20Poly1305;
+use serde::{Deserialize, Serialize};
+
+use crate::dates::{civil_from_days, split_days};
+use crate::i18n::tr;
+use crate::AppData;
+
+/// Gemeinsame Salt-Datei älterer Versionen
+const SALT_FILE: &str = "salt.txt";
+
+/// Erste Zeile jeder Vault-Datei; die Ziffer ist die Formatversion
+pub(crate) const MAGIC: &str = "EJE1";
+/// Die Ziffer aus `MAGIC`; Dateien ohne Kennung zählen als Version 0
+pub(crate) const FORMAT_VERSION: u32 = 1;
+/// Fehlermeldung, wenn die Datei gar nicht nach einem Vault aussieht
+pub(crate) const ERR_NOT_A_VAULT: &str = "Kein gültiger Vault";
+/// Fehlermeldung, wenn die Authentifizierung beim Entschlüsseln scheitert
+pub(crate) const ERR_WRONG_PASSWORD: &str = "Falsches Passwort";
+/// Fehlermeldung, wenn die Datei schon strukturell nicht stimmen kann
+pub(crate) const ERR_CORRUPTED: &str = "Datei beschädigt oder manipuliert";
+/// Wie `ERR_WRONG_PASSWORD`, wenn zusätzlich eine Schlüsseldatei im Spiel ist
+pub(crate) const ERR_WRONG_PASSWORD_OR_KEY_FILE: &str =
+    "Falsches Passwort oder falsche Schlüsseldatei";
+/// Fehlermeldung, wenn der Header eine Schlüsseldatei verlangt, aber keine gewählt ist
+pub(crate) const ERR_KEY_FILE_REQUIRED: &str = "Dieser Vault braucht eine Schlüsseldatei";
+/// Fehlermeldung, wenn ein Vault im alten Format ohne seine Salt-Datei vorliegt
+pub(crate) const ERR_MISSING_SALT: &str = "Salt-Datei fehlt – Entschlüsselung unmöglich";
+/// Kontext für den Prüfwert, an dem ein richtig abgeleiteter Schlüssel erkannt wird
+const KEY_CHECK_CONTEXT: &[u8] = b"encrypted-json-editor key check";
+/// Nonce (12 Bytes) plus Authentifizierungs-Tag (16 Bytes) beider Verfahren
+pub(crate) const MIN_ENCRYPTED_LEN: usize = 12 + 16;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum KdfAlgorithm {
+    Argon2d,
+    Argon2i,
+    Argon2id,
+}
+
+/// Argon2-Parameter eines Vaults. Sie stehen im Dateiheader, damit geänderte
+/// Standardwerte der argon2-Crate bestehende Dateien nicht unlesbar machen.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub(crate) struct KdfParams {
+    pub(crate) algorithm: KdfAlgorithm,
+    pub(crate) version: u32,
+    pub(crate) m_cost: u32,
+    pub(crate) t_cost: u32,
+    pub(crate) p_cost: u32,
+}
+
+impl Default for KdfParams {
+    /// Entspricht `Argon2::default()` aus argon2 0.5, mit dem Vaults ohne Header erstellt wurden
+    fn default() -> Self {
+        Self {
+            algorithm: KdfAlgorithm::Argon2id,
+            version: 0x13,
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+}
+
+impl KdfParams {
+    /// Höchstwerte für Parameter aus dem Header, der vor dem Entschlüsseln nicht
+    /// authentifiziert ist: 1 GiB Speicher, 64 Durchläufe, 16 Lanes
+    pub(crate) const MAX_M_COST: u32 = 1024 * 1024;
+    pub(crate) const MAX_T_COST: u32 = 64;
+    pub(crate) const MAX_P_COST: u32 = 16;
+
+    /// Lehnt Werte ab, mit denen eine manipulierte Datei beim Entsperren
+    /// Speicher oder Rechenzeit ausschöpfen könnte
+    pub(crate) fn check_limits(&self) -> Result<(), String> {
+        if self.m_cost > Self::MAX_M_COST {
+            return Err(format!(
+                "Speicher {} MiB über der Grenze von {} MiB",
+                self.m_cost / 1024,
+                Self::MAX_M_COST / 1024
+            ));
+        }
+        if self.t_cost > Self::MAX_T_COST {
+            return Err(format!(
+                "{} Durchläufe über der Grenze von {}",
+                self.t_cost,
+                Self::MAX_T_COST
+            ));
+        }
+        if self.p_cost > Self::MAX_P_COST {
+            return Err(format!(
+                "Parallelität {} über der Grenze von {}",
+                self.p_cost,
+                Self::MAX_P_COST
+            ));
+        }
+        Ok(())
+    }
+
+    /// Argon2-Instanz; eine Schlüsseldatei geht als Argon2-Secret in die Ableitung ein
+    pub(crate) fn argon2<'k>(&self, secret: Option<&'k [u8]>) -> Result<Argon2<'k>, String> {
+        self.check_limits()?;
+        let algorithm = match self.algorithm {
+            KdfAlgorithm::Argon2d => Algorithm::Argon2d,
+            KdfAlgorithm::Argon2i => Algorithm::Argon2i,
+            KdfAlgorithm::Argon2id => Algorithm::Argon2id,
+        };
+        let version = Version::try_from(self.version).map_err(|e| e.to_string())?;
+        let params =
+            Params::new(self.m_cost, self.t_cost, self.p_cost, None).map_err(|e| e.to_string())?;
+        match secret {
+            Some(secret) => Argon2::new_with_secret(secret, algorithm, version, params)
+                .map_err(|e| e.to_string()),
+            None => Ok(Argon2::new(algorithm, version, params)),
+        }
+    }
+
+    /// Kurzform wie in PHC-Strings, z.B. "Argon2id v19, m=19 MiB, t=2, p=1"
+    pub(crate) fn describe(&self) -> String {
+        let algorithm = match self.algorithm {
+            KdfAlgorithm::Argon2d => "Argon2d",
+            KdfAlgorithm::Argon2i => "Argon2i",
+            KdfAlgorithm::Argon2id => "Argon2id",
+        };
+        format!(
+            "{} v{}, m={} MiB, t={}, p={}",
+            algorithm,
+            self.version,
+            self.m_cost / 1024,
+            self.t_cost,
+            self.p_cost
+        )
+    }
+
+    /// Grobe Dauer einer Ableitung: Argon2 schafft auf üblicher Hardware etwa
+    /// 1 GiB je Durchlauf und Sekunde, einzelne Lanes laufen nacheinander.
+    pub(crate) fn estimated_seconds(&self) -> f64 {
+        self.m_cost as f64 / (1024.0 * 1024.0) * self.t_cost as f64
+    }
+}
+
+/// Vorgegebene Argon2id-Parameter, damit niemand Rohwerte wählen muss
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) enum KdfPreset {
+    /// Die bisherigen Standardwerte
+    Fast,
+    Balanced,
+    Paranoid,
+    /// Von Hand gesetzte oder aus einem älteren Vault übernommene Werte
+    Custom,
+}
+
+impl KdfPreset {
+    pub(crate) const ALL: [KdfPreset; 4] = [
+        KdfPreset::Fast,
+        KdfPreset::Balanced,
+        KdfPreset::Paranoid,
+        KdfPreset::Custom,
+    ];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            KdfPreset::Fast => tr("Schnell"),
+            KdfPreset::Balanced => tr("Ausgewogen"),
+            KdfPreset::Paranoid => tr("Paranoid"),
+            KdfPreset::Custom => tr("Eigene Werte"),
+        }
+    }
+
+    /// (Speicher in KiB, Durchläufe, Parallelität); `None` für eigene Werte
+    pub(crate) fn params(self) -> Option<KdfParams> {
+        let (m_cost, t_cost, p_cost) = match self {
+            KdfPreset::Fast => return Some(KdfParams::default()),
+            KdfPreset::Balanced => (64 * 1024, 3, 2),
+            KdfPreset::Paranoid => (256 * 1024, 4, 4),
+            KdfPreset::Custom => return None,
+        };
+        Some(KdfParams {
+            m_cost,
+            t_cost,
+            p_cost,
+            ..KdfParams::default()
+        })
+    }
+
+    /// Welche Vorgabe `params` entspricht
+    pub(crate) fn of(params: &KdfParams) -> KdfPreset {
+        KdfPreset::ALL
+            .into_iter()
+            .find(|preset| preset.params().as_ref() == Some(params))
+            .unwrap_or(KdfPreset::Custom)
+    }
+}
+
+/// Wie aus der Argon2-Ausgabe der 256-Bit-Schlüssel gewonnen wird
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum KeyDerivation {
+    /// Erste 32 Bytes des im PHC-String eingebetteten Hashes (ältere Vaults).
+    /// Mit argon2 0.5 identisch zu `Raw`, hängt aber an deren Standard-Ausgabelänge.
+    #[default]
+    Phc,
+    /// Direkte 32-Byte-Ausgabe von `hash_password_into`
+    Raw,
+}
+
+/// Symmetrisches Verfahren, mit dem der Vault-Inhalt verschlüsselt ist
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CipherSuite {
+    #[default]
+    Aes256Gcm,
+    ChaCha20Poly1305,
+}
+
+impl CipherSuite {
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            CipherSuite::Aes256Gcm => "AES-256-GCM",
+            CipherSuite::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+        }
+    }
+}
+
+/// Klartext-Header in der ersten Zeile der Vault-Datei, gefolgt vom base64-Block.
+/// Dateien ohne Header stammen aus älteren Versionen und nutzen die Standardwerte.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub(crate) struct FileHeader {
+    pub(crate) kdf: KdfParams,
+    #[serde(default)]
+    pub(crate) key_derivation: KeyDerivation,
+    #[serde(default)]
+    pub(crate) cipher: CipherSuite,
+    /// Base64-kodiertes Salt. Fehlt es, liegt das Salt noch in der separaten Salt-Datei.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) salt: Option<String>,
+    /// Base64-Prüfwert des Schlüssels. Ist er gesetzt, ist der ganze Header als
+    /// Associated Data in die Verschlüsselung eingebunden; ältere Dateien haben keinen.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) key_check: Option<String>,
+    /// Zum Entsperren wird neben dem Passwort eine Schlüsseldatei gebraucht
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub(crate) key_file: bool,
+}
+
+impl FileHeader {
+    /// Header für neu angelegte oder migrierte Vaults
+    pub(crate) fn current() -> Self {
+        Self {
+            kdf: KdfParams::default(),
+            key_derivation: KeyDerivation::Raw,
+            cipher: CipherSuite::default(),
+            salt: None,
+            key_check: None,
+            key_file: false,
+        }
+    }
+
+    /// Bindet den Header an `key`: danach wird er beim Verschlüsseln mit authentifiziert
+    pub(crate) fn bind_key(&mut self, key: &[u8; 32]) {
+        self.key_check = Some(BASE64.encode(key_check(key)));
+    }
+
+    /// Bytes, die als Associated Data mit authentifiziert werden. Ohne Prüfwert
+    /// (ältere Dateien) bleibt der Header wie bisher außen vor.
+    fn associated_data(&self) -> Result<Vec<u8>, String> {
+        if self.key_check.is_none() {
+            return Ok(Vec::new());
+        }
+        serde_json::to_vec(self).map_err(|e| e.to_string())
+    }
+
+    pub(crate) fn embedded_salt(&self) -> Result<Option<Vec<u8>>, String> {
+        self.salt
+            .as_deref()
+            .map(|salt| {
+                BASE64
+                    .decode(salt)
+                    .map_err(|e| format!("{} (ungültiges Salt: {})", ERR_CORRUPTED, e))
+            })
+            .transpose()
+    }
+}
+
+/// Eine Vault-Datei samt Format und, nach dem Entsperren, abgeleitetem Schlüssel.
+/// Kapselt Lesen, Schreiben und Backups, damit Oberfläche und Kommandozeile
+/// denselben Weg nutzen.
+#[derive(Clone)]
+pub(crate) struct Vault {
+    pub(crate) path: PathBuf,
+    /// Salt-Datei, nur noch für Vaults ohne Salt im Header
+    pub(crate) salt_path: PathBuf,
+    /// Header für das nächste Speichern
+    pub(crate) header: FileHeader,
+    /// Aus Passwort und Salt abgeleiteter Schlüssel, gültig bis sich eins von beiden ändert
+    pub(crate) key: Option<[u8; 32]>,
+    /// Hash der gewählten Schlüsseldatei, siehe `read_key_file`
+    pub(crate) key_file: Option<[u8; 32]>,
+    /// Änderungszeit der Datei beim letzten Lesen oder Schreiben durch uns
+    pub(crate) disk_modified: Option<SystemTime>,
+}
+
+impl Vault {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self {
+            salt_path: salt_path_for(&path),
+            path,
+            header: FileHeader::current(),
+            key: None,
+            key_file: None,
+            disk_modified: None,
+        }
+    }
+
+    pub(crate) fn exists(&self) -> bool {
+        fs::metadata(&self.path).is_ok()
+    }
+
+    fn modified_on_disk(&self) -> Option<SystemTime> {
+        fs::metadata(&self.path).and_then(|m| m.modified()).ok()
+    }
+
+    /// Neue Änderungszeit, falls ein anderer Prozess die Datei seit unserem letzten
+    /// Lesen oder Schreiben ersetzt hat
+    pub(crate) fn external_change(&self) -> Option<SystemTime> {
+        self.modified_on_disk()
+            .filter(|modified| self.disk_modified != Some(*modified))
+    }
+
+    /// Vault- und Salt-Datei, die Gruppe oder andere lesen dürfen (nur unter Unix)
+    pub(crate) fn loosely_permitted_files(&self) -> Vec<PathBuf> {
+        [&self.path, &self.salt_path]
+            .into_iter()
+            .filter(|path| is_loosely_permitted(path))
+            .cloned()
+            .collect()
+    }
+
+    /// Setzt vorhandene Vault- und Salt-Datei auf 0600
+    pub(crate) fn restrict_permissions(&self) -> Result<(), String> {
+        for path in [&self.path, &self.salt_path] {
+            if path.exists() {
+                restrict_to_owner(path).map_err(|e| e.to_string())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Formatversion und Header der Datei auf der Platte, ohne zu entschlüsseln;
+    /// `None`, wenn es dort keine lesbare Vault-Datei gibt
+    pub(crate) fn file_info(&self) -> Option<(u32, FileHeader)> {
+        let content = fs::read_to_string(&self.path).ok()?;
+        let (header, _) = parse_vault_file(&content).ok()?;
+        let tagged = content.trim().lines().next().map(str::trim_end) == Some(MAGIC);
+        Some((if tagged { FORMAT_VERSION } else { 0 }, header))
+    }
+
+    pub(crate) fn read_file(&self) -> Result<(FileHeader, Vec<u8>), String> {
+        let content = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        parse_vault_file(&content)
+    }
+
+    /// Ob der Header der vorhandenen Datei eine Schlüsseldatei verlangt
+    pub(crate) fn requires_key_file(&self) -> bool {
+        self.read_file()
+            .map(|(header, _)| header.key_file)
+            .unwrap_or(false)
+    }
+
+    /// Salt aus dem Header oder, bei Vaults im alten Format, aus der Salt-Datei
+    pub(crate) fn read_salt(&self, header: &FileHeader) -> Result<Vec<u8>, String> {
+        match header.embedded_salt()? {
+            Some(salt) => Ok(salt),
+            None if !self.salt_path.exists() => Err(format!(
+                "{} ({})",
+                ERR_MISSING_SALT,
+                self.salt_path.display()
+            )),
+            None => fs::read(&self.salt_path).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Entschlüsselt die Datei und merkt sich Header und Schlüssel fürs Speichern.
+    /// Ältere Vaults werden dabei auf die Raw-Ableitung umgestellt, der dafür nötige
+    /// Schlüssel wird dann beim nächsten Speichern neu abgeleitet.
+    pub(crate) fn decrypt(&mut self, password: &str) -> Result<Vec<u8>, String> {
+        // Vor dem Lesen, damit ein gleichzeitiges Schreiben als Änderung auffällt
+        let modified = self.modified_on_disk();
+        let (header, encrypted_data) = self.read_file()?;
+        let salt = self.read_salt(&header)?;
+        let key = derive_key(password, self.key_file.as_ref(), &salt, &header)?;
+        let plaintext = decrypt_with_key(&key, &header, &encrypted_data)?;
+        self.key = (header.key_derivation == KeyDerivation::Raw).then_some(key);
+        self.disk_modified = modified;
+        self.header = FileHeader {
+            key_derivation: KeyDerivation::Raw,
+            ..header
+        };
+        Ok(plaintext)
+    }
+
+    /// Wie `decrypt`, liefert aber gleich die geparsten Daten
+    pub(crate) fn load(&mut self, password: &str) -> Result<AppData, String> {
+        let plaintext = self.decrypt(password)?;
+        serde_json::from_slice(&plaintext).map_err(|e| format!("Daten nicht lesbar: {}", e))
+    }
+
+    /// Prüft ein Passwort, indem die gespeicherte Datei damit entschlüsselt wird
+    pub(crate) fn verify_password(&self, password: &str) -> Result<(), String> {
+        let (header, encrypted_data) = self.read_file()?;
+        let salt = self.read_salt(&header)?;
+        decrypt_bytes(
+            password,
+            self.key_file.as_ref(),
+            &salt,
+            &header,
+            &encrypted_data,
+        )
+        .map(|_| ())
+    }
+
+    /// Verschlüsselt `data` und ersetzt die Datei atomar, vorher rotieren die Backups.
+    /// Der Schlüssel aus dem Entsperren wird wiederverwendet, damit nicht jedes
+    /// Speichern die volle Argon2-Ableitung kostet.
+    pub(crate) fn save(
+        &mut self,
+        password: &str,
+        data: &AppData,
+        backup_count: usize,
+    ) -> Result<(), String> {
+        self.write(password, self.key_file, data, backup_count, false)
+    }
+
+    /// Verschlüsselt `data` mit neuem Passwort und gegebenenfalls neuer Schlüsseldatei.
+    /// Das Salt wird dabei immer neu erzeugt, damit alter und neuer Stand keine
+    /// KDF-Arbeit gemeinsam haben.
+    pub(crate) fn save_with_new_password(
+        &mut self,
+        new_password: &str,
+        key_file: Option<[u8; 32]>,
+        data: &AppData,
+        backup_count: usize,
+    ) -> Result<(), String> {
+        self.write(new_password, key_file, data, backup_count, true)
+    }
+
+    /// Verschlüsselt mit neuen Argon2-Parametern neu: frisches Salt, neu
+    /// abgeleiteter Schlüssel. Scheitert das Schreiben, bleiben Header und
+    /// Schlüssel wie vorher, damit weitere Speichervorgänge zur Datei passen.
+    pub(crate) fn rekey(
+        &mut self,
+        password: &str,
+        kdf: KdfParams,
+        data: &AppData,
+        backup_count: usize,
+    ) -> Result<(), String> {
+        kdf.argon2(None)?;
+        let previous = self.header.kdf;
+        self.header.kdf = kdf;
+        let result = self.write(password, self.key_file, data, backup_count, true);
+        if result.is_err() {
+            self.header.kdf = previous;
+        }
+        result
+    }
+
+    /// Gemeinsamer Weg beider Speicherarten. Mit `regenerate_salt` wird ein frisches
+    /// Salt gezogen und der Schlüssel neu abgeleitet; nur dann (oder beim ersten
+    /// Speichern) ändert sich, ob eine Schlüsseldatei verlangt wird. Header und
+    /// Schlüssel werden erst übernommen, wenn die Datei geschrieben ist.
+    fn write(
+        &mut self,
+        password: &str,
+        key_file: Option<[u8; 32]>,
+        data: &AppData,
+        backup_count: usize,
+        regenerate_salt: bool,
+    ) -> Result<(), String> {
+        let json_data = serde_json::to_string(data).map_err(|e| e.to_string())?;
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let mut header = self.header.clone();
+        let mut key = self.key;
+        // Salt aus dem Header; ältere Vaults übernehmen es beim Speichern aus der Salt-Datei
+        let existing_salt = match header.embedded_salt()? {
+            _ if regenerate_salt => None,
+            Some(salt) => Some(salt),
+            None if fs::metadata(&self.salt_path).is_ok() => {
+                Some(fs::read(&self.salt_path).map_err(|e| e.to_string())?)
+            }
+            None => None,
+        };
+        let salt = match existing_salt {
+            Some(salt) => salt,
+            None => {
+                let mut salt = [0u8; 16];
+                OsRng.fill_bytes(&mut salt);
+                key = None;
+                header.key_file = key_file.is_some();
+                salt.to_vec()
+            }
+        };
+        header.salt = Some(BASE64.encode(&salt));
+        let key = match key {
+            Some(key) => key,
+            None => derive_key(password, key_file.as_ref(), &salt, &header)?,
+        };
+        header.bind_key(&key);
+        let encrypted_data = encrypt_with_key(&key, &header, json_data.as_bytes())?;
+        let content = format_vault_file(&header, &encrypted_data)?;
+
+        // Die neue Datei setzt `write_atomic` schon vor dem Umbenennen auf 0600. Nach
+        // dem Umbenennen darf nichts mehr scheitern, sonst passte der Zustand hier
+        // nicht mehr zur Datei; die Salt-Datei wird deshalb vorher eingeschränkt.
+        if self.salt_path.exists() {
+            restrict_to_owner(&self.salt_path).map_err(|e| e.to_string())?;
+        }
+        rotate_backups(&self.path, &self.salt_path, backup_count)?;
+        write_atomic(&self.path, content)?;
+        self.disk_modified = self.modified_on_disk();
+        self.header = header;
+        self.key = Some(key);
+        self.key_file = key_file;
+        Ok(())
+    }
+
+    /// Legt eine Kopie der verschlüsselten Datei mit Zeitstempel in `dir` ab, z.B.
+    /// `data-20261015-142301.enc`, und löscht die ältesten Kopien über `keep` hinaus.
+    /// Kopiert wird nur eine Datei, die sich als Vault mit eigenem Salt lesen lässt,
+    /// Klartext verlässt so nie das Arbeitsverzeichnis.
+    pub(crate) fn copy_to_backup_dir(
+        &self,
+        dir: &Path,
+        keep: usize,
+        now: SystemTime,
+    ) -> Result<PathBuf, String> {
+        let content = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        let (header, _) = parse_vault_file(&content)?;
+        if header.embedded_salt()?.is_none() {
+            return Err(tr(ERR_MISSING_SALT).to_string());
+        }
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let (stem, extension) = backup_name_parts(&self.path);
+        let target = dir.join(format!("{}-{}{}", stem, backup_timestamp(now), extension));
+        write_atomic(&target, content)?;
+        let backups = timestamped_backups(dir, &self.path);
+        for old in &backups[..backups.len().saturating_sub(keep.max(1))] {
+            let _ = fs::remove_file(old);
+        }
+        Ok(target)
+    }
+
+    /// Notkopie eines ungespeicherten Stands neben dem Vault, z.B.
+    /// `data-unsaved-20261015-142301.enc`, verschlüsselt mit dem schon abgeleiteten
+    /// Schlüssel. Für die Panik-Sperre, wenn das normale Speichern scheitert; die
+    /// Kopie öffnet sich mit demselben Passwort.
+    pub(crate) fn write_emergency_copy(
+        &self,
+        data: &AppData,
+        now: SystemTime,
+    ) -> Result<PathBuf, String> {
+        let key = self.key.ok_or("Kein Schlüssel abgeleitet")?;
+        if self.header.embedded_salt()?.is_none() {
+            return Err(tr(ERR_MISSING_SALT).to_string());
+        }
+        let json_data = serde_json::to_string(data).map_err(|e| e.to_string())?;
+        let encrypted_data = encrypt_with_key(&key, &self.header, json_data.as_bytes())?;
+        let content = format_vault_file(&self.header, &encrypted_data)?;
+        let (stem, extension) = backup_name_parts(&self.path);
+        let target = self.path.with_file_name(format!(
+            "{}-unsaved-{}{}",
+            stem,
+            backup_timestamp(now),
+            extension
+        ));
+        write_atomic(&target, content)?;
+        Ok(target)
+    }
+
+    /// Indizes der vorhandenen Backups, neuestes zuerst
+    pub(crate) fn available_backups(&self, backup_count: usize) -> Vec<usize> {
+        (0..backup_count)
+            .filter(|&index| backup_path(&self.path, index).exists())
+            .collect()
+    }
+
+    /// Entschlüsselt ein Backup. Gespeichert wird danach mit dem Salt des Backups,
+    /// daher wird der Schlüssel verworfen.
+    pub(crate) fn decrypt_backup(
+        &mut self,
+        index: usize,
+        password: &str,
+    ) -> Result<Vec<u8>, String> {
+        let content =
+            fs::read_to_string(backup_path(&self.path, index)).map_err(|e| e.to_string())?;
+        let (mut header, encrypted_data) = parse_vault_file(&content)?;
+        let salt = match header.embedded_salt()? {
+            Some(salt) => salt,
+            None => fs::read(backup_path(&self.salt_path, index))
+                .or_else(|_| fs::read(&self.salt_path))
+                .map_err(|e| e.to_string())?,
+        };
+        header.salt = Some(BASE64.encode(&salt));
+        let plaintext = decrypt_bytes(
+            password,
+            self.key_file.as_ref(),
+            &salt,
+            &header,
+            &encrypted_data,
+        )?;
+        self.header = FileHeader {
+            key_derivation: KeyDerivation::Raw,
+            ..header
+        };
+        self.key = None;
+        Ok(plaintext)
+    }
+}
+
+pub(crate) fn derive_key(
+    password: &str,
+    key_file: Option<&[u8; 32]>,
+    salt: &[u8],
+    header: &FileHeader,
+) -> Result<[u8; 32], String> {
+    let secret = match (header.key_file, key_file) {
+        (true, Some(key_file)) => Some(key_file.as_slice()),
+        (true, None) => return Err(ERR_KEY_FILE_REQUIRED.into()),
+        // Eine unnötig gewählte Schlüsseldatei stört nicht
+        (false, _) => None,
+    };
+    let argon2 = header.kdf.argon2(secret)?;
+    let mut key = [0u8; 32];
+    match header.key_derivation {
+        KeyDerivation::Raw => {
+            argon2
+                .hash_password_into(password.as_bytes(), salt, &mut key)
+                .map_err(|e| e.to_string())?;
+        }
+        KeyDerivation::Phc => {
+            let salt_string = SaltString::encode_b64(salt).map_err(|e| e.to_string())?;
+            let password_hash = argon2
+                .hash_password(password.as_bytes(), &salt_string)
+                .map_err(|e| e.to_string())?;
+            let hash_binding = password_hash.hash.ok_or("No hash".to_string())?;
+            let hash_bytes = hash_binding.as_bytes();
+            if hash_bytes.len() < 32 {
+                return Err("Hash too short".into());
+            }
+            key.copy_from_slice(&hash_bytes[..32]);
+        }
+    }
+    Ok(key)
+}
+
+pub(crate) fn decrypt_bytes(
+    password: &str,
+    key_file: Option<&[u8; 32]>,
+    salt: &[u8],
+    header: &FileHeader,
+    encrypted_data: &[u8],
+) -> Result<Vec<u8>, String> {
+    let key = derive_key(password, key_file, salt, header)?;
+    decrypt_with_key(&key, header, encrypted_data)
+}
+
+/// Entschlüsselt einen kompletten Dateiinhalt, z.B. von stdin. Neben einem Puffer
+/// liegt keine Salt-Datei, das Salt muss also im Header stehen.
+pub(crate) fn decrypt_content(
+    content: &str,
+    password: &str,
+    key_file: Option<&[u8; 32]>,
+) -> Result<Vec<u8>, String> {
+    let (header, encrypted_data) = parse_vault_file(content)?;
+    let salt = header
+        .embedded_salt()?
+        .ok_or_else(|| ERR_MISSING_SALT.to_string())?;
+    decrypt_bytes(password, key_file, &salt, &header, &encrypted_data)
+}
+
+/// Gegenstück zu `decrypt_content`: ein vollständiger Dateiinhalt im aktuellen
+/// Format mit frischem Salt, so wie ihn `Vault::save` schreiben würde
+pub(crate) fn encrypt_content(
+    plaintext: &[u8],
+    password: &str,
+    key_file: Option<&[u8; 32]>,
+) -> Result<String, String> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let mut header = FileHeader {
+        salt: Some(BASE64.encode(salt)),
+        key_file: key_file.is_some(),
+        ..FileHeader::current()
+    };
+    let key = derive_key(password, key_file, &salt, &header)?;
+    header.bind_key(&key);
+    let encrypted_data = encrypt_with_key(&key, &header, plaintext)?;
+    format_vault_file(&header, &encrypted_data)
+}
+
+pub(crate) fn encrypt_with_key(
+    key: &[u8; 32],
+    header: &FileHeader,
+    plaintext: &[u8],
+) -> Result<Vec<u8>, String> {
+    let aad = header.associated_data()?;
+    match header.cipher {
+        CipherSuite::Aes256Gcm => seal::<Aes256Gcm>(key, plaintext, &aad),
+        CipherSuite::ChaCha20Poly1305 => seal::<ChaCha20Poly1305>(key, plaintext, &aad),
+    }
+}
+
+/// Mit Prüfwert im Header wird zuerst der Schlüssel geprüft: Stimmt er, das
+/// Entschlüsseln scheitert aber trotzdem, wurden Header oder Inhalt verändert.
+pub(crate) fn decrypt_with_key(
+    key: &[u8; 32],
+    header: &FileHeader,
+    encrypted_data: &[u8],
+) -> Result<Vec<u8>, String> {
+    if encrypted_data.len() < MIN_ENCRYPTED_LEN {
+        return Err(ERR_CORRUPTED.into());
+    }
+    let aad = header.associated_data()?;
+    let plaintext = match header.cipher {
+        CipherSuite::Aes256Gcm => open::<Aes256Gcm>(key, encrypted_data, &aad),
+        CipherSuite::ChaCha20Poly1305 => open::<ChaCha20Poly1305>(key, encrypted_data, &aad),
+    };
+    let Some(expected) = &header.key_check else {
+        return plaintext.map_err(|_| ERR_WRONG_PASSWORD.to_string());
+    };
+    let expected = BASE64
+        .decode(expected)
+        .map_err(|e| format!("{} (ungültiger Prüfwert: {})", ERR_CORRUPTED, e))?;
+    if key_check(key) != expected.as_slice() {
+        return Err(if header.key_file {
+            ERR_WRONG_PASSWORD_OR_KEY_FILE.into()
+        } else {
+            ERR_WRONG_PASSWORD.into()
+        });
+    }
+    plaintext.map_err(|_| ERR_CORRUPTED.to_string())
+}
+
+/// Ob ein Fehler bedeutet, dass Passwort oder Schlüsseldatei nicht stimmen
+pub(crate) fn is_wrong_password(error: &str) -> bool {
+    error == ERR_WRONG_PASSWORD || error == ERR_WRONG_PASSWORD_OR_KEY_FILE
+}
+
+/// Liest eine Schlüsseldatei. In die Ableitung geht nur ihr Hash ein, daher
+/// darf sie beliebig groß sein (z.B. ein Foto); leer darf sie nicht sein.
+pub(crate) fn read_key_file(path: &Path) -> Result<[u8; 32], String> {
+    let bytes = fs::read(path).map_err(|e| format!("Schlüsseldatei nicht lesbar: {}", e))?;
+    if bytes.is_empty() {
+        return Err("Schlüsseldatei ist leer".into());
+    }
+    Ok(Blake2s256::digest(&bytes).into())
+}
+
+/// Schlüsselabhängiger Prüfwert; verrät nichts über den Schlüssel selbst
+fn key_check(key: &[u8; 32]) -> [u8; 32] {
+    let mut mac =
+        <Blake2sMac256 as Mac>::new_from_slice(key).expect("Blake2s akzeptiert 32-Byte-Schlüssel");
+    mac.update(KEY_CHECK_CONTEXT);
+    mac.finalize().into_bytes().into()
+}
+
+fn seal<C: Aead + AeadCore + KeyInit>(
+    key: &[u8; 32],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, String> {
+    let cipher = C::new_from_slice(key).map_err(|e| e.to_string())?;
+    let nonce = C::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            aead::Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    let mut encrypted_data = nonce.to_vec();
+    encrypted_data.extend_from_slice(&ciphertext);
+    Ok(encrypted_data)
+}
+
+fn open<C: Aead + AeadCore + KeyInit>(
+    key: &[u8; 32],
+    encrypted_data: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, String> {
+    let cipher = C::new_from_slice(key).map_err(|e| e.to_string())?;
+    let nonce_len = aead::Nonce::<C>::default().len();
+    if encrypted_data.len() < nonce_len {
+        return Err(ERR_CORRUPTED.into());
+    }
+    let (nonce_bytes, ciphertext) = encrypted_data.split_at(nonce_len);
+    cipher
+        .decrypt(
+            aead::Nonce::<C>::from_slice(nonce_bytes),
+            aead::Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| ERR_WRONG_PASSWORD.to_string())
+}
+
+/// Zerlegt den Dateiinhalt in Header und verschlüsselte Bytes. Aktuelle Dateien
+/// beginnen mit `MAGIC`; ältere haben nur den Header oder bestehen nur aus dem
+/// base64-Block (dann mit Standard-Header) und bekommen die Kennung beim nächsten
+/// Speichern. Strukturfehler werden hier als `ERR_CORRUPTED` gemeldet, bevor ein
+/// Entschlüsselungsversuch sie als falsches Passwort erscheinen lassen könnte.
+pub(crate) fn parse_vault_file(content: &str) -> Result<(FileHeader, Vec<u8>), String> {
+    let corrupted = |detail: String| format!("{} ({})", ERR_CORRUPTED, detail);
+    let content = content.trim();
+    let (first, rest) = content.split_once('\n').unwrap_or((content, ""));
+    let first = first.trim_end();
+    let (header, body) = if first == MAGIC {
+        let (header, body) = rest
+            .split_once('\n')
+            .ok_or_else(|| corrupted("Header fehlt".into()))?;
+        (parse_header(header)?, body)
+    } else if first.starts_with(&MAGIC[..3]) && first.len() < 8 && !rest.is_empty() {
+        return Err(format!(
+            "{} (Formatversion {} wird nicht unterstützt)",
+            ERR_NOT_A_VAULT, first
+        ));
+    } else if first.trim_start().starts_with('{') && !rest.is_empty() {
+        (parse_header(first)?, rest)
+    } else {
+        // Ohne Kennung und Header ist nur ein gültiger base64-Block noch ein Vault
+        let encrypted_data = BASE64
+            .decode(content)
+            .ok()
+            .filter(|bytes| bytes.len() >= MIN_ENCRYPTED_LEN)
+            .ok_or_else(|| ERR_NOT_A_VAULT.to_string())?;
+        return Ok((FileHeader::default(), encrypted_data));
+    };
+    let encrypted_data = BASE64
+        .decode(body.trim())
+        .map_err(|e| corrupted(e.to_string()))?;
+    if encrypted_data.len() < MIN_ENCRYPTED_LEN {
+        return Err(corrupted("Datei zu kurz".into()));
+    }
+    Ok((header, encrypted_data))
+}
+
+fn parse_header(line: &str) -> Result<FileHeader, String> {
+    let corrupted = |detail: String| format!("{} ({})", ERR_CORRUPTED, detail);
+    let header: FileHeader =
+        serde_json::from_str(line).map_err(|e| corrupted(format!("ungültiger Header: {}", e)))?;
+    header
+        .kdf
+        .argon2(None)
+        .map_err(|e| corrupted(format!("ungültige KDF-Parameter: {}", e)))?;
+    Ok(header)
+}
+
+pub(crate) fn format_vault_file(
+    header: &FileHeader,
+    encrypted_data: &[u8],
+) -> Result<String, String> {
+    let header = serde_json::to_string(header).map_err(|e| e.to_string())?;
+    Ok(format!(
+        "{}\n{}\n{}",
+        MAGIC,
+        header,
+        BASE64.encode(encrypted_data)
+    ))
+}
+
+/// Salt-Datei zum Vault: `<vault>.salt`. Liegt nur die alte gemeinsame `salt.txt`
+/// neben einem bestehenden Vault, wird diese weiterverwendet.
+pub(crate) fn salt_path_for(vault_path: &Path) -> PathBuf {
+    let mut name = vault_path.as_os_str().to_os_string();
+    name.push(".salt");
+    let salt_path = PathBuf::from(name);
+    let legacy = vault_path.with_file_name(SALT_FILE);
+    if !salt_path.exists() && vault_path.exists() && legacy.exists() {
+        legacy
+    } else {
+        salt_path
+    }
+}
+
+/// Pfad der temporären Datei, die vor dem Umbenennen geschrieben wird
+pub(crate) fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".tmp");
+    PathBuf::from(name)
+}
+
+/// `data.enc.bak` für das neueste Backup, danach `data.enc.bak.1`, `data.enc.bak.2`, ...
+pub(crate) fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".bak");
+    if index > 0 {
+        name.push(format!(".{}", index));
+    }
+    PathBuf::from(name)
+}
+
+/// Name ohne und mit Endung, z.B. `("data", ".enc")`
+fn backup_name_parts(vault_path: &Path) -> (String, String) {
+    let stem = vault_path
+        .file_stem()
+        .map_or("vault".into(), |stem| stem.to_string_lossy().into_owned());
+    let extension = vault_path
+        .extension()
+        .map_or(String::new(), |ext| format!(".{}", ext.to_string_lossy()));
+    (stem, extension)
+}
+
+/// `20261015-142301` in UTC, damit die Namen unabhängig von der Zeitzone sortieren
+fn backup_timestamp(time: SystemTime) -> String {
+    let (days, rest) = split_days(time);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        rest / 3600,
+        rest % 3600 / 60,
+        rest % 60
+    )
+}
+
+/// Zeitgestempelte Kopien dieses Vaults in `dir`, älteste zuerst
+fn timestamped_backups(dir: &Path, vault_path: &Path) -> Vec<PathBuf> {
+    let (stem, extension) = backup_name_parts(vault_path);
+    let prefix = format!("{}-", stem);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                return false;
+            };
+            name.strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(&extension))
+                .is_some_and(|stamp| {
+                    stamp.len() == 15
+                        && stamp.char_indices().all(|(i, c)| {
+                            if i == 8 {
+                                c == '-'
+                            } else {
+                                c.is_ascii_digit()
+                            }
+                        })
+                })
+        })
+        .collect();
+    // Die Zeitstempel sortieren als Text chronologisch
+    backups.sort();
+    backups
+}
+
+/// Verschiebt vorhandene Backups um eine Stelle und kopiert die aktuelle Datei
+/// samt Salt nach `.bak`. Das Salt gehört dazu, weil ein Passwortwechsel es ersetzt.
+pub(crate) fn rotate_backups(
+    vault_path: &Path,
+    salt_path: &Path,
+    count: usize,
+) -> Result<(), String> {
+    if count == 0 || !vault_path.exists() {
+        return Ok(());
+    }
+    for path in [vault_path, salt_path] {
+        let _ = fs::remove_file(backup_path(path, count - 1));
+        for index in (1..count).rev() {
+            let from = backup_path(path, index - 1);
+            if from.exists() {
+                fs::rename(&from, backup_path(path, index)).map_err(|e| e.to_string())?;
+            }
+        }
+        if path.exists() {
+            fs::copy(path, backup_path(path, 0)).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Ob Gruppe oder andere die Datei lesen oder schreiben dürfen
+#[cfg(unix)]
+fn is_loosely_permitted(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|meta| meta.permissions().mode() & 0o077 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_loosely_permitted(_path: &Path) -> bool {
+    false
+}
+
+/// Nur der Besitzer darf lesen und schreiben (0600)
+#[cfg(unix)]
+fn restrict_to_owner(path: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+}
+
+#[cfg(not(unix))]
+fn restrict_to_owner(_path: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Schreibt erst in eine `.tmp`-Datei und benennt sie dann um. Das Umbenennen ist
+/// auf demselben Dateisystem atomar, ein Absturz hinterlässt also nie eine halbe Datei.
+/// Unter Unix ist die Datei schon vor dem Umbenennen nur für den Besitzer lesbar.
+pub(crate) fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), String> {
+    let tmp = tmp_path(path);
+    let result = fs::File::create(&tmp).and_then(|mut file| {
+        std::io::Write::write_all(&mut file, contents.as_ref())?;
+        file.sync_all()?;
+        restrict_to_owner(&tmp)
+    });
+    if let Err(e) = result.and_then(|_| fs::rename(&tmp, path)) {
+        let _ = fs::remove_file(&tmp);
+        return Err(e.to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EntryValue;
+
+    #[test]
+    fn rekeying_stores_the_new_kdf_params_in_the_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = Vault::new(dir.path().join("vault.enc"));
+        vault.header = FileHeader::current();
+        let mut data = AppData::default();
+        data.set_value("k".into(), EntryValue::Text("v".into()));
+        vault.save("pw", &data, 0).unwrap();
+        let old_key = vault.key;
+
+        let kdf = KdfParams {
+            m_cost: 8 * 1024,
+            t_cost: 1,
+            ..KdfParams::default()
+        };
+        assert_eq!(KdfPreset::of(&kdf), KdfPreset::Custom);
+        assert_eq!(KdfPreset::of(&KdfParams::default()), KdfPreset::Fast);
+        vault.rekey("pw", kdf, &data, 0).unwrap();
+        assert_ne!(vault.key, old_key);
+
+        let mut reopened = Vault::new(vault.path.clone());
+        assert_eq!(reopened.load("pw").unwrap().items["k"], data.items["k"]);
+        assert_eq!(reopened.header.kdf, kdf);
+
+        // Ungültige Parameter ändern nichts an der Datei
+        let broken = KdfParams { p_cost: 0, ..kdf };
+        assert!(vault.rekey("pw", broken, &data, 0).is_err());
+        assert_eq!(vault.header.kdf, kdf);
+        assert!(KdfPreset::Paranoid.params().unwrap().estimated_seconds() > 0.5);
+    }
+
+    #[test]
+    fn oversized_kdf_params_in_the_header_are_rejected() {
+        let mut header = FileHeader::current();
+        let content = format_vault_file(&header, &[0u8; MIN_ENCRYPTED_LEN]).unwrap();
+        assert!(parse_vault_file(&content).is_ok());
+
+        header.kdf.m_cost = 4 * 1024 * 1024;
+        let content = format_vault_file(&header, &[0u8; MIN_ENCRYPTED_LEN]).unwrap();
+        let e = parse_vault_file(&content).unwrap_err();
+        assert!(
+            e.starts_with(ERR_CORRUPTED) && e.contains("1024 MiB"),
+            "{}",
+            e
+        );
+
+        header.kdf = KdfParams {
+            t_cost: KdfParams::MAX_T_COST + 1,
+            ..KdfParams::default()
+        };
+        let content = format_vault_file(&header, &[0u8; MIN_ENCRYPTED_LEN]).unwrap();
+        assert!(parse_vault_file(&content).is_err());
+        assert!(KdfPreset::Paranoid.params().unwrap().check_limits().is_ok());
+    }
+
+    #[test]
+    fn file_info_reports_the_format_version_without_decrypting() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = Vault::new(dir.path().join("data.enc"));
+        assert!(vault.file_info().is_none());
+        assert!(MAGIC.ends_with(&FORMAT_VERSION.to_string()));
+
+        vault.header.cipher = CipherSuite::ChaCha20Poly1305;
+        vault.save("pw", &AppData::default(), 0).unwrap();
+        let (version, header) = vault.file_info().unwrap();
+        assert_eq!(version, FORMAT_VERSION);
+        assert_eq!(header.cipher, CipherSuite::ChaCha20Poly1305);
+        assert_eq!(header.kdf.describe(), "Argon2id v19, m=19 MiB, t=2, p=1");
+
+        // Ältere Dateien bestehen nur aus dem base64-Block
+        fs::write(&vault.path, BASE64.encode([7u8; MIN_ENCRYPTED_LEN])).unwrap();
+        assert_eq!(vault.file_info().unwrap().0, 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn saving_restricts_vault_and_salt_to_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let dir = tempfile::tempdir().unwrap();
+        let mut vault = Vault::new(dir.path().join("data.enc"));
+        vault.save("pw", &AppData::default(), 1).unwrap();
+        assert_eq!(mode(&vault.path), 0o600);
+        assert!(vault.loosely_permitted_files().is_empty());
+
+        // Von außen gelockerte Rechte werden erkannt und beim Speichern wieder eingeschränkt
+        fs::write(&vault.salt_path, "altes salt").unwrap();
+        for path in [&vault.path, &vault.salt_path] {
+            fs::set_permissions(path, fs::Permissions::from_mode(0o644)).unwrap();
+        }
+        assert_eq!(
+            vault.loosely_permitted_files(),
+            [vault.path.clone(), vault.salt_path.clone()]
+        );
+        vault.save("pw", &AppData::default(), 1).unwrap();
+        assert_eq!(mode(&vault.path), 0o600);
+        assert_eq!(mode(&vault.salt_path), 0o600);
+        assert!(vault.loosely_permitted_files().is_empty());
+    }
+
+    #[test]
+    fn backup_dir_keeps_the_newest_encrypted_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let backups = dir.path().join("sync");
+        let mut vault = Vault::new(dir.path().join("data.enc"));
+        let mut data = AppData::default();
+        data.set_value("token".into(), EntryValue::Text("geheim".into()));
+        vault.save("pw", &data, 0).unwrap();
+
+        let day = |offset: u64| {
+            std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_792_074_181 + offset)
+        };
+        assert_eq!(backup_timestamp(day(0)), "20261015-142301");
+        for offset in [0, 60, 3600] {
+            vault.copy_to_backup_dir(&backups, 2, day(offset)).unwrap();
+        }
+        fs::write(backups.join("data-notizen.enc"), "fremd").unwrap();
+        let kept = timestamped_backups(&backups, &vault.path);
+        let names: Vec<_> = kept
+            .iter()
+            .map(|path| path.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            ["data-20261015-142401.enc", "data-20261015-152301.enc"]
+        );
+        assert!(backups.join("data-notizen.enc").exists());
+
+        let content = fs::read_to_string(&kept[1]).unwrap();
+        assert!(!content.contains("geheim"));
+        let mut copy = Vault::new(kept[1].clone());
+        assert_eq!(copy.load("pw").unwrap().items["token"], data.items["token"]);
+
+        // Was sich nicht als Vault lesen lässt, wird nicht kopiert
+        fs::write(&vault.path, "{\"token\": \"geheim\"}").unwrap();
+        assert!(vault.copy_to_backup_dir(&backups, 2, day(7200)).is_err());
+        assert_eq!(timestamped_backups(&backups, &vault.path).len(), 2);
+    }
+
+    fn encrypt(
+        password: &str,
+        salt: &[u8],
+        header: &FileHeader,
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let key = derive_key(password, None, salt, header)?;
+        encrypt_with_key(&key, header, plaintext)
+    }
+
+    #[test]
+    fn file_header_round_trips_and_legacy_files_use_defaults() {
+        let header = FileHeader {
+            kdf: KdfParams {
+                algorithm: KdfAlgorithm::Argon2id,
+                version: 0x13,
+                m_cost: 64 * 1024,
+                t_cost: 3,
+                p_cost: 2,
+            },
+            key_derivation: KeyDerivation::Raw,
+            cipher: CipherSuite::ChaCha20Poly1305,
+            salt: Some(BASE64.encode([4u8; 16])),
+            key_check: Some(BASE64.encode([8u8; 32])),
+            key_file: true,
+        };
+        let ciphertext = [9u8; MIN_ENCRYPTED_LEN];
+        let content = format_vault_file(&header, &ciphertext).unwrap();
+        assert!(content.starts_with("EJE1\n{"));
+        let (parsed, bytes) = parse_vault_file(&content).unwrap();
+        assert_eq!(parsed, header);
+        assert_eq!(bytes, ciphertext);
+
+        // Dateien von vor der Kennung: Header direkt in der ersten Zeile
+        let (parsed, bytes) = parse_vault_file(&content["EJE1\n".len()..]).unwrap();
+        assert_eq!(parsed, header);
+        assert_eq!(bytes, ciphertext);
+
+        let legacy = BASE64.encode(ciphertext);
+        let (parsed, bytes) = parse_vault_file(&legacy).unwrap();
+        assert_eq!(parsed, FileHeader::default());
+        assert_eq!(bytes, ciphertext);
+    }
+
+    #[test]
+    fn legacy_phc_derivation_matches_raw_output() {
+        let salt = [3u8; 16];
+        let legacy = FileHeader::default();
+        let encrypted = encrypt("geheim", &salt, &legacy, b"{}").unwrap();
+        assert!(decrypt_bytes("geheim", None, &salt, &legacy, &encrypted).is_ok());
+
+        // Alte Vaults bleiben lesbar, wenn sie beim Speichern auf Raw umgestellt werden
+        assert_eq!(
+            derive_key("geheim", None, &salt, &legacy).unwrap(),
+            derive_key("geheim", None, &salt, &FileHeader::current()).unwrap()
+        );
+    }
+
+    #[test]
+    fn cipher_suite_from_header_is_used_for_decryption() {
+        let salt = [5u8; 16];
+        let chacha = FileHeader {
+            cipher: CipherSuite::ChaCha20Poly1305,
+            ..FileHeader::current()
+        };
+        let encrypted = encrypt("geheim", &salt, &chacha, b"{}").unwrap();
+        assert_eq!(
+            decrypt_bytes("geheim", None, &salt, &chacha, &encrypted).unwrap(),
+            b"{}"
+        );
+        assert!(decrypt_bytes("geheim", None, &salt, &FileHeader::current(), &encrypted).is_err());
+    }
+
+    #[test]
+    fn corrupted_file_is_reported_differently_from_wrong_password() {
+        let salt = [1u8; 16];
+        let header = FileHeader::current();
+        let encrypted = encrypt("geheim", &salt, &header, b"{}").unwrap();
+        let content = format_vault_file(&header, &encrypted).unwrap();
+
+        let (parsed, bytes) = parse_vault_file(&content).unwrap();
+        assert_eq!(
+            decrypt_bytes("falsch", None, &salt, &parsed, &bytes).unwrap_err(),
+            ERR_WRONG_PASSWORD
+        );
+
+        let truncated = &content[..content.len() - BASE64.encode(&encrypted).len() / 2];
+        assert!(parse_vault_file(truncated)
+            .unwrap_err()
+            .starts_with(ERR_CORRUPTED));
+        assert_eq!(parse_vault_file("kein vault").unwrap_err(), ERR_NOT_A_VAULT);
+        assert_eq!(
+            parse_vault_file(&BASE64.encode([0u8; 8])).unwrap_err(),
+            ERR_NOT_A_VAULT
+        );
+        assert!(parse_vault_file(&content.replacen("EJE1", "EJE9", 1))
+            .unwrap_err()
+            .starts_with(ERR_NOT_A_VAULT));
+    }
+
+    #[test]
+    fn interrupted_write_keeps_previous_file_intact() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.enc");
+        write_atomic(&path, "gute Daten").unwrap();
+
+        // Absturz mitten im Schreiben: nur ein Teil landet in der temporären Datei
+        fs::write(tmp_path(&path), "kaputt").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "gute Daten");
+
+        write_atomic(&path, "neue Daten").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "neue Daten");
+        assert!(!tmp_path(&path).exists());
+    }
+
+    #[test]
+    fn salt_file_is_named_after_vault_with_legacy_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = dir.path().join("my.vault");
+        assert_eq!(salt_path_for(&vault), dir.path().join("my.vault.salt"));
+
+        // Alter Aufbau: Vault existiert, aber nur mit gemeinsamer salt.txt
+        fs::write(&vault, "x").unwrap();
+        fs::write(dir.path().join(SALT_FILE), "salt").unwrap();
+        assert_eq!(salt_path_for(&vault), dir.path().join(SALT_FILE));
+
+        fs::write(dir.path().join("my.vault.salt"), "salt").unwrap();
+        assert_eq!(salt_path_for(&vault), dir.path().join("my.vault.salt"));
+    }
+
+    #[test]
+    fn salt_is_stored_in_header_and_legacy_salt_file_is_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.enc");
+        let salt = [6u8; 16];
+        let legacy = FileHeader::default();
+        let encrypted = encrypt("geheim", &salt, &legacy, br#"{"items": {"a": "1"}}"#).unwrap();
+        fs::write(&path, BASE64.encode(encrypted)).unwrap();
+        fs::write(dir.path().join(SALT_FILE), salt).unwrap();
+
+        let mut vault = Vault::new(path.clone());
+        let data = vault.load("geheim").unwrap();
+        vault.save("geheim", &data, 0).unwrap();
+
+        let (header, _) = vault.read_file().unwrap();
+        assert_eq!(header.embedded_salt().unwrap(), Some(salt.to_vec()));
+
+        // Nach der Migration reicht die Vault-Datei allein
+        fs::remove_file(dir.path().join(SALT_FILE)).unwrap();
+        let data = Vault::new(path).load("geheim").unwrap();
+        assert_eq!(data.items["a"], EntryValue::Text("1".into()));
+    }
+
+    #[test]
+    fn saved_data_round_trips_and_rejects_wrong_password_or_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.enc");
+        let mut data = AppData::default();
+        data.items
+            .insert("token".into(), EntryValue::Text("sk-123".into()));
+        data.items.insert("port".into(), EntryValue::Number(8080.0));
+        data.notes = "Notiz".into();
+
+        Vault::new(path.clone()).save("geheim", &data, 0).unwrap();
+        assert!(!dir.path().join("data.enc.salt").exists());
+
+        let loaded = Vault::new(path.clone()).load("geheim").unwrap();
+        assert_eq!(loaded.items, data.items);
+        assert_eq!(loaded.notes, data.notes);
+
+        assert_eq!(
+            Vault::new(path.clone()).load("falsch").err().as_deref(),
+            Some(ERR_WRONG_PASSWORD)
+        );
+
+        // Ein gekipptes Bit im Chiffretext muss die Authentifizierung scheitern lassen
+        let (header, mut bytes) = Vault::new(path.clone()).read_file().unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+        fs::write(&path, format_vault_file(&header, &bytes).unwrap()).unwrap();
+        assert!(Vault::new(path).load("geheim").is_err());
+    }
+
+    #[test]
+    fn tampered_header_is_reported_as_manipulated() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.enc");
+        Vault::new(path.clone())
+            .save("geheim", &AppData::default(), 0)
+            .unwrap();
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.lines().nth(1).unwrap().contains("key_check"));
+
+        // Herabstufen des Verfahrens im Klartext-Header
+        let downgraded = content.replacen("aes256gcm", "chacha20poly1305", 1);
+        assert_ne!(downgraded, content);
+        fs::write(&path, &downgraded).unwrap();
+        assert_eq!(
+            Vault::new(path.clone()).load("geheim").err().as_deref(),
+            Some(ERR_CORRUPTED)
+        );
+        assert_eq!(
+            Vault::new(path.clone()).load("falsch").err().as_deref(),
+            Some(ERR_WRONG_PASSWORD)
+        );
+
+        // Entfernter Prüfwert: der Header wäre wieder ungeschützt, passt aber nicht mehr
+        let (mut header, bytes) = parse_vault_file(&content).unwrap();
+        header.key_check = None;
+        fs::write(&path, format_vault_file(&header, &bytes).unwrap()).unwrap();
+        assert!(Vault::new(path).load("geheim").is_err());
+    }
+
+    #[test]
+    fn password_change_uses_a_fresh_salt() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.enc");
+        let mut vault = Vault::new(path.clone());
+        vault.save("alt", &AppData::default(), 0).unwrap();
+        let (before, _) = vault.read_file().unwrap();
+
+        vault.save("alt", &AppData::default(), 0).unwrap();
+        assert_eq!(vault.read_file().unwrap().0.salt, before.salt);
+
+        vault
+            .save_with_new_password("neu", None, &AppData::default(), 0)
+            .unwrap();
+        let (after, _) = vault.read_file().unwrap();
+        assert_ne!(after.salt, before.salt);
+        assert_eq!(
+            Vault::new(path.clone()).load("alt").err().as_deref(),
+            Some(ERR_WRONG_PASSWORD)
+        );
+        assert!(Vault::new(path).load("neu").is_ok());
+    }
+
+    #[test]
+    fn key_file_is_required_once_the_header_asks_for_it() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.enc");
+        fs::write(dir.path().join("richtig.key"), "zufällige Bytes").unwrap();
+        fs::write(dir.path().join("falsch.key"), "andere Bytes").unwrap();
+        let right = read_key_file(&dir.path().join("richtig.key")).unwrap();
+        let wrong = read_key_file(&dir.path().join("falsch.key")).unwrap();
+
+        let mut vault = Vault::new(path.clone());
+        vault.key_file = Some(right);
+        vault.save("geheim", &AppData::default(), 0).unwrap();
+        assert!(Vault::new(path.clone()).requires_key_file());
+
+        assert_eq!(
+            Vault::new(path.clone()).load("geheim").err().as_deref(),
+            Some(ERR_KEY_FILE_REQUIRED)
+        );
+        let mut vault = Vault::new(path.clone());
+        vault.key_file = Some(wrong);
+        assert_eq!(
+            vault.load("geheim").err().as_deref(),
+            Some(ERR_WRONG_PASSWORD_OR_KEY_FILE)
+        );
+        vault.key_file = Some(right);
+        assert!(vault.load("geheim").is_ok());
+
+        // Beim Passwortwechsel lässt sich die Schlüsseldatei wieder abschalten
+        vault
+            .save_with_new_password("geheim", None, &AppData::default(), 0)
+            .unwrap();
+        assert!(!vault.requires_key_file());
+        assert!(Vault::new(path).load("geheim").is_ok());
+    }
+
+    #[test]
+    fn rewrite_by_another_process_is_noticed_until_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.enc");
+        let mut ours = Vault::new(path.clone());
+        ours.save("geheim", &AppData::default(), 0).unwrap();
+        assert_eq!(ours.external_change(), None);
+
+        let mut theirs = Vault::new(path.clone());
+        theirs.load("geheim").unwrap();
+        theirs.save("geheim", &AppData::default(), 0).unwrap();
+        // Grobe Zeitstempel mancher Dateisysteme würden das Schreiben sonst verdecken
+        let later = SystemTime::now() + std::time::Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        assert_eq!(ours.external_change(), Some(later));
+
+        ours.load("geheim").unwrap();
+        assert_eq!(ours.external_change(), None);
+    }
+
+    #[test]
+    fn content_buffers_round_trip_and_match_saved_files() {
+        let content = encrypt_content(b"{\"items\":{}}", "geheim", None).unwrap();
+        assert_eq!(
+            decrypt_content(&content, "geheim", None).unwrap(),
+            b"{\"items\":{}}"
+        );
+        assert_eq!(
+            decrypt_content(&content, "falsch", None).unwrap_err(),
+            ERR_WRONG_PASSWORD
+        );
+        assert_eq!(
+            decrypt_content("kein vault", "geheim", None).unwrap_err(),
+            ERR_NOT_A_VAULT
+        );
+
+        // Ein Puffer ist eine vollwertige Vault-Datei und umgekehrt
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.enc");
+        fs::write(&path, &content).unwrap();
+        assert!(Vault::new(path.clone()).load("geheim").is_ok());
+        let mut data = AppData::default();
+        data.items
+            .insert("token".into(), EntryValue::Text("sk-123".into()));
+        Vault::new(path.clone()).save("geheim", &data, 0).unwrap();
+        let plaintext =
+            decrypt_content(&fs::read_to_string(&path).unwrap(), "geheim", None).unwrap();
+        assert!(String::from_utf8(plaintext).unwrap().contains("sk-123"));
+    }
+}